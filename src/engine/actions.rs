@@ -1,13 +1,19 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::engine::conditions::conditions_met;
-use crate::engine::helpers::{apply_effects, item_in_inventory, item_in_room, item_visible};
+use crate::engine::conditions::{EvalContext, conditions_met};
+use crate::engine::helpers::{
+    apply_item_spawns, apply_item_transforms, apply_world_effects, item_in_inventory, item_in_room,
+    item_visible,
+};
+use crate::engine::locations::{ItemLiquids, ItemLocations};
 use crate::engine::output::Output;
 use crate::world;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ActionBlockReason {
     MissingInventory,
+    MissingReachable,
+    MissingInstrument,
     MissingScope,
     BlockedByConditions,
 }
@@ -31,8 +37,12 @@ fn phrase_matches_tokens(phrase: &str, tokens: &[String]) -> bool {
     words.iter().all(|w| tokens.iter().any(|t| t == w))
 }
 
+/// Weight of each matched verb word in an action's total score. It outweighs anything
+/// nouns, scope, inventory and instrument can add, so "look under" always beats "look".
+const VERB_WEIGHT: usize = 1000;
+
 /// Returns how many words matched (for scoring), or 0 if phrase doesn't match.
-fn phrase_match_score(phrase: &str, tokens: &[String]) -> usize {
+pub(crate) fn phrase_match_score(phrase: &str, tokens: &[String]) -> usize {
     if phrase_matches_tokens(phrase, tokens) {
         phrase.split_whitespace().filter(|w| !w.is_empty()).count()
     } else {
@@ -94,6 +104,70 @@ fn missing_inventory_message(action: &world::Action, world: &world::World) -> St
     }
 }
 
+fn missing_reachable_message(
+    action: &world::Action,
+    world: &world::World,
+    current_room_id: &str,
+    ctx: &EvalContext,
+) -> String {
+    let names: Vec<&str> = action
+        .requires_reachable
+        .iter()
+        .filter(|id| !item_reachable(id, world, current_room_id, ctx))
+        .map(|id| world.items.get(id).map(|it| it.name.as_str()).unwrap_or(id))
+        .collect();
+
+    if names.len() == 1 {
+        format!("You need the {} nearby.", names[0])
+    } else {
+        format!("You need these nearby: {}.", names.join(", "))
+    }
+}
+
+/// Carried, or lying visible in the current room.
+fn item_reachable(
+    item_id: &str,
+    world: &world::World,
+    current_room_id: &str,
+    ctx: &EvalContext,
+) -> bool {
+    item_in_inventory(item_id, ctx.item_locations)
+        || (item_in_room(item_id, ctx.item_locations, current_room_id)
+            && world
+                .items
+                .get(item_id)
+                .is_some_and(|i| item_visible(i, ctx)))
+}
+
+fn missing_instrument_message(action: &world::Action, world: &world::World) -> String {
+    if let Some(txt) = &action.missing_inventory_text {
+        let t = txt.trim();
+        if !t.is_empty() {
+            return t.to_string();
+        }
+    }
+
+    match action
+        .instrument
+        .as_ref()
+        .and_then(|id| world.items.get(id))
+    {
+        Some(it) => format!("You don't have the {}.", it.name),
+        None => "You don't have what you need.".to_string(),
+    }
+}
+
+fn blocked_message(action: &world::Action) -> String {
+    if let Some(txt) = &action.blocked_text {
+        let t = txt.trim();
+        if !t.is_empty() {
+            return t.to_string();
+        }
+    }
+
+    "You can't do that right now.".to_string()
+}
+
 fn missing_scope_message(action: &world::Action, world: &world::World) -> String {
     if let Some(txt) = &action.missing_scope_text {
         let t = txt.trim();
@@ -122,32 +196,242 @@ fn missing_scope_message(action: &world::Action, world: &world::World) -> String
     }
 }
 
+/// Flip the action's `toggle_flag`, if it has one, and return the response for the
+/// resulting state: `response` when the flag is now set, `response_off` when it was cleared.
+pub(crate) fn toggle_action_flag<'a>(
+    action: &'a world::Action,
+    flags: &mut HashSet<String>,
+) -> &'a str {
+    let Some(flag) = &action.toggle_flag else {
+        return &action.response;
+    };
+
+    if flags.remove(flag) {
+        action.response_off.as_deref().unwrap_or(&action.response)
+    } else {
+        flags.insert(flag.clone());
+        &action.response
+    }
+}
+
+/// Flag changes when `action` fires: its toggle flag, its `effects`, then each
+/// `conditional_effects` entry whose conditions held before any of these.
+/// Returns the response to show.
+pub(crate) fn apply_action_flags<'a>(
+    world: &world::World,
+    action: &'a world::Action,
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+    item_locations: &ItemLocations,
+    item_liquids: &ItemLiquids,
+) -> &'a str {
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+    let extra: Vec<&[String]> = action
+        .conditional_effects
+        .iter()
+        .filter(|ce| conditions_met(&ce.conditions, &ctx))
+        .map(|ce| ce.effects.as_slice())
+        .collect();
+
+    let response = toggle_action_flag(action, flags);
+    apply_world_effects(world, flags, &action.effects);
+    for effects in extra {
+        apply_world_effects(world, flags, effects);
+    }
+    response
+}
+
+/// Fire `action`: say its response, change flags, then move items.
+pub(crate) fn fire_action(
+    out: &mut Output,
+    world: &world::World,
+    action: &world::Action,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+) {
+    let txt = apply_action_flags(
+        world,
+        action,
+        flags,
+        turn_index,
+        item_locations,
+        item_liquids,
+    )
+    .trim();
+    if !txt.is_empty() {
+        out.say(txt);
+    }
+    apply_item_transforms(item_locations, &action.transforms);
+    apply_item_spawns(item_locations, &action.remove_items, &action.spawns);
+}
+
+/// Turn each action with a cooldown last fired, keyed by where the action lives
+/// ("<room id>::<action id>", "global::<action id>", "<npc id>::<action id>").
+pub type ActionCooldowns = HashMap<String, u64>;
+
+/// Fire `action` unless it is still cooling down from its last use, in which case
+/// say its `cooldown_text` instead. Returns true if it fired.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn fire_action_with_cooldown(
+    out: &mut Output,
+    world: &world::World,
+    action: &world::Action,
+    key: String,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+    cooldowns: &mut ActionCooldowns,
+) -> bool {
+    if let Some(cooldown) = action.cooldown_turns {
+        if let Some(&fired_at) = cooldowns.get(&key)
+            && turn_index < fired_at.saturating_add(cooldown)
+        {
+            let txt = action
+                .cooldown_text
+                .as_deref()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .unwrap_or(&world.messages.cooldown);
+            out.say(txt);
+            return false;
+        }
+        cooldowns.insert(key, turn_index);
+    }
+
+    fire_action(
+        out,
+        world,
+        action,
+        item_locations,
+        item_liquids,
+        flags,
+        turn_index,
+    );
+    true
+}
+
+/// `world.auto_open`: open a closed container for the player by firing the first room
+/// action (then global action) they could use right now whose outcome satisfies
+/// `open_conditions`, the container's conditions. Returns true if one fired; an action
+/// still cooling down says its cooldown text and leaves the container closed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn try_auto_open(
+    out: &mut Output,
+    world: &world::World,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
+    current_room_id: &str,
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+    open_conditions: &[world::Condition],
+    cooldowns: &mut ActionCooldowns,
+) -> bool {
+    if !world.auto_open {
+        return false;
+    }
+
+    let room_actions = world
+        .rooms
+        .get(current_room_id)
+        .map(|r| r.actions.as_slice())
+        .unwrap_or_default();
+
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+    let usable = room_actions
+        .iter()
+        .map(|action| (current_room_id, action))
+        .chain(world.global_actions.iter().map(|action| ("global", action)))
+        .find(|(_, action)| {
+            let scope_ok = action.scope_requirements.iter().all(|id| {
+                world.items.get(id).is_some_and(|i| item_visible(i, &ctx))
+                    && item_in_room(id, item_locations, current_room_id)
+            });
+            let carried_ok = action
+                .requires_inventory
+                .iter()
+                .chain(&action.instrument)
+                .all(|id| item_in_inventory(id, item_locations))
+                && action
+                    .requires_reachable
+                    .iter()
+                    .all(|id| item_reachable(id, world, current_room_id, &ctx));
+            if !(scope_ok && carried_ok && conditions_met(&action.conditions, &ctx)) {
+                return false;
+            }
+
+            let mut after = ctx.flags.clone();
+            apply_action_flags(
+                world,
+                action,
+                &mut after,
+                turn_index,
+                item_locations,
+                item_liquids,
+            );
+            let ctx = EvalContext {
+                flags: &after,
+                ..ctx
+            };
+            conditions_met(open_conditions, &ctx)
+        });
+
+    let Some((owner, action)) = usable else {
+        return false;
+    };
+
+    fire_action_with_cooldown(
+        out,
+        world,
+        action,
+        format!("{}::{}", owner, action.id),
+        item_locations,
+        item_liquids,
+        flags,
+        turn_index,
+        cooldowns,
+    )
+}
+
 /// Public: attempt to handle a per-room action.
+#[allow(clippy::too_many_arguments)]
 pub fn try_handle_action(
     out: &mut Output,
     room: &world::Room,
     input: &str,
+    instrument: Option<&str>,
     world: &world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     current_room_id: &str,
     flags: &mut HashSet<String>,
+    turn_index: u64,
+    cooldowns: &mut ActionCooldowns,
 ) -> bool {
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
     let (exec, msg, handled) = evaluate_actions_for_input(
         &room.actions,
         input,
+        instrument,
         world,
-        item_locations,
         current_room_id,
-        flags,
+        &ctx,
     );
 
     if let Some(action) = exec {
-        let txt = action.response.trim();
-        if !txt.is_empty() {
-            out.say(txt);
-        }
-
-        apply_effects(flags, &action.effects);
+        fire_action_with_cooldown(
+            out,
+            world,
+            action,
+            format!("{}::{}", room.id, action.id),
+            item_locations,
+            item_liquids,
+            flags,
+            turn_index,
+            cooldowns,
+        );
         return true;
     }
 
@@ -160,30 +444,41 @@ pub fn try_handle_action(
 }
 
 /// Public: attempt to handle a global action.
+#[allow(clippy::too_many_arguments)]
 pub fn try_handle_global_action(
     out: &mut Output,
     input: &str,
+    instrument: Option<&str>,
     world: &world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     current_room_id: &str,
     flags: &mut HashSet<String>,
+    turn_index: u64,
+    cooldowns: &mut ActionCooldowns,
 ) -> bool {
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
     let (exec, msg, handled) = evaluate_actions_for_input(
         &world.global_actions,
         input,
+        instrument,
         world,
-        item_locations,
         current_room_id,
-        flags,
+        &ctx,
     );
 
     if let Some(action) = exec {
-        let txt = action.response.trim();
-        if !txt.is_empty() {
-            out.say(txt);
-        }
-
-        apply_effects(flags, &action.effects);
+        fire_action_with_cooldown(
+            out,
+            world,
+            action,
+            format!("global::{}", action.id),
+            item_locations,
+            item_liquids,
+            flags,
+            turn_index,
+            cooldowns,
+        );
         return true;
     }
 
@@ -195,8 +490,41 @@ pub fn try_handle_global_action(
     handled
 }
 
+/// Public: canned replies for off-path verbs ("jump", "sing").
+/// Checked last, just before the generic fallback. The first entry (in file order)
+/// whose verb matches and whose conditions are met wins. No effects are applied.
+pub fn try_handle_easter_verb(
+    out: &mut Output,
+    input: &str,
+    world: &world::World,
+    ctx: &EvalContext,
+) -> bool {
+    let tokens = tokenize(input);
+    if tokens.is_empty() {
+        return false;
+    }
+
+    for ev in &world.easter_verbs {
+        if !ev.verbs.iter().any(|v| phrase_matches_tokens(v, &tokens)) {
+            continue;
+        }
+
+        if !conditions_met(&ev.conditions, ctx) {
+            continue;
+        }
+
+        out.say(ev.response.trim());
+        return true;
+    }
+
+    false
+}
+
 /// Core evaluator used by both per-room actions and global actions.
 ///
+/// `instrument` is the phrase after "with" in the player's input, if any.
+/// Actions that declare an instrument only match when that phrase names it.
+///
 /// Returns:
 /// - Some(action) if one executable action matches best
 /// - Some(message) if we should show a helpful blocked/ambiguous message
@@ -204,11 +532,12 @@ pub fn try_handle_global_action(
 pub(crate) fn evaluate_actions_for_input<'a>(
     actions: &'a [world::Action],
     input: &str,
+    instrument: Option<&str>,
     world: &'a world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
     current_room_id: &str,
-    flags: &HashSet<String>,
+    ctx: &EvalContext,
 ) -> (Option<&'a world::Action>, Option<String>, bool) {
+    let item_locations = ctx.item_locations;
     let tokens = tokenize(input);
     if tokens.is_empty() {
         return (None, None, false);
@@ -268,7 +597,7 @@ pub(crate) fn evaluate_actions_for_input<'a>(
 
             // 🔒 Critical fix:
             // If the scope item is not VISIBLE, do not allow this action to match at all.
-            if !item_visible(item, flags) {
+            if !item_visible(item, ctx) {
                 continue 'action_loop;
             }
 
@@ -296,8 +625,43 @@ pub(crate) fn evaluate_actions_for_input<'a>(
             }
         }
 
+        // --- Reachable requirements (optional): carried or in the room ---
+        let mut reach_ok = true;
+        let mut reach_score = 0usize;
+
+        for reach_id in &action.requires_reachable {
+            if !item_reachable(reach_id, world, current_room_id, ctx) {
+                reach_ok = false;
+            } else {
+                reach_score += 2;
+            }
+        }
+
+        // --- Instrument (optional) ---
+        // Must be named in the "with" clause and held by the player.
+        let mut instrument_named = true;
+        let mut instrument_right = true;
+        let mut instrument_held = true;
+        let mut instrument_score = 0usize;
+
+        if let Some(inst_id) = &action.instrument {
+            match (instrument, world.items.get(inst_id)) {
+                (Some(phrase), Some(inst_item)) => {
+                    if !input_mentions_item_name(inst_item, &tokenize(phrase)) {
+                        instrument_right = false;
+                    } else if item_in_inventory(inst_id, item_locations) {
+                        instrument_score += 4;
+                    } else {
+                        instrument_held = false;
+                    }
+                }
+                (Some(_), None) => instrument_right = false,
+                (None, _) => instrument_named = false,
+            }
+        }
+
         // --- Conditions ---
-        let cond_ok = conditions_met(&action.conditions, flags);
+        let cond_ok = conditions_met(&action.conditions, ctx);
 
         // Strong intent definition:
         let intent_strong = if action.scope_requirements.is_empty() {
@@ -307,10 +671,23 @@ pub(crate) fn evaluate_actions_for_input<'a>(
         };
 
         // Total score (for selecting best candidate)
-        let total_score = verb_score + noun_score + scope_score + inv_score;
+        let total_score = verb_score * VERB_WEIGHT
+            + noun_score
+            + scope_score
+            + inv_score
+            + reach_score
+            + instrument_score;
 
         // If fully executable, consider it for execution
-        if intent_strong && scope_ok && inv_ok && cond_ok {
+        if intent_strong
+            && scope_ok
+            && inv_ok
+            && reach_ok
+            && instrument_named
+            && instrument_right
+            && instrument_held
+            && cond_ok
+        {
             if total_score > best_exec_score {
                 best_exec_score = total_score;
                 best_exec.clear();
@@ -328,6 +705,32 @@ pub(crate) fn evaluate_actions_for_input<'a>(
                     ActionBlockReason::MissingInventory,
                     missing_inventory_message(action, world),
                 )
+            } else if !reach_ok {
+                (
+                    ActionBlockReason::MissingReachable,
+                    missing_reachable_message(action, world, current_room_id, ctx),
+                )
+            } else if !instrument_right {
+                (
+                    ActionBlockReason::BlockedByConditions,
+                    format!(
+                        "You can't {} it with that.",
+                        tokens.first().map(|t| t.as_str()).unwrap_or("do")
+                    ),
+                )
+            } else if !instrument_held {
+                (
+                    ActionBlockReason::MissingInventory,
+                    missing_instrument_message(action, world),
+                )
+            } else if !instrument_named {
+                (
+                    ActionBlockReason::MissingInstrument,
+                    format!(
+                        "What do you want to {} it with?",
+                        tokens.first().map(|t| t.as_str()).unwrap_or("do")
+                    ),
+                )
             } else if !scope_ok {
                 (
                     ActionBlockReason::MissingScope,
@@ -336,18 +739,20 @@ pub(crate) fn evaluate_actions_for_input<'a>(
             } else if !cond_ok {
                 (
                     ActionBlockReason::BlockedByConditions,
-                    "You can't do that right now.".to_string(),
+                    blocked_message(action),
                 )
             } else {
                 (
                     ActionBlockReason::BlockedByConditions,
-                    "You can't do that.".to_string(),
+                    world.messages.cant_do_that.clone(),
                 )
             };
 
             // Prefer: higher score; tie-break by "more specific" reasons
             let reason_rank = match reason {
-                ActionBlockReason::MissingInventory => 3,
+                ActionBlockReason::MissingInventory => 5,
+                ActionBlockReason::MissingReachable => 4,
+                ActionBlockReason::MissingInstrument => 3,
                 ActionBlockReason::MissingScope => 2,
                 ActionBlockReason::BlockedByConditions => 1,
             };
@@ -369,7 +774,7 @@ pub(crate) fn evaluate_actions_for_input<'a>(
     if best_exec.len() == 1 {
         return (Some(best_exec[0]), None, true);
     } else if best_exec.len() > 1 {
-        return (None, Some("Be more specific.".to_string()), true);
+        return (None, Some(world.messages.be_more_specific.clone()), true);
     }
 
     // No executable: return best blocked message if present
@@ -380,3 +785,144 @@ pub(crate) fn evaluate_actions_for_input<'a>(
     // No match at all
     (None, None, false)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{hall, run};
+    use crate::world::ItemLocation;
+
+    #[test]
+    fn a_longer_verb_phrase_wins_over_noun_matches() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "look_rug"
+            verbs = ["look"]
+            nouns = ["rug", "under the rug"]
+            response = "A faded rug."
+
+            [[room.action]]
+            id = "look_under"
+            verbs = ["look under"]
+            nouns = ["rug"]
+            response = "A key glints under the rug."
+            "#,
+        );
+
+        assert_eq!(
+            run(&mut game, "look under the rug"),
+            "A key glints under the rug."
+        );
+        assert_eq!(run(&mut game, "look rug"), "A faded rug.");
+    }
+
+    #[test]
+    fn a_toggle_action_flips_its_flag() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "switch_lamp"
+            verbs = ["switch"]
+            nouns = ["lamp"]
+            toggle_flag = "lamp_on"
+            response = "The lamp comes on."
+            response_off = "The lamp goes out."
+            "#,
+        );
+
+        assert_eq!(run(&mut game, "switch lamp"), "The lamp comes on.");
+        assert!(game.flags.contains("lamp_on"));
+        assert_eq!(run(&mut game, "switch lamp"), "The lamp goes out.");
+        assert!(!game.flags.contains("lamp_on"));
+    }
+
+    #[test]
+    fn an_action_moves_and_removes_items() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "break_vase"
+            verbs = ["break"]
+            nouns = ["vase"]
+            requires_inventory = ["hammer"]
+            remove_items = ["hammer"]
+            response = "The vase shatters, and a key falls out."
+
+            [[room.action.spawn]]
+            item = "key"
+            location = "room:hall"
+
+            [[item]]
+            id = "hammer"
+            name = "hammer"
+            start_location = "inventory"
+
+            [[item]]
+            id = "key"
+            name = "key"
+            start_location = "nowhere"
+            "#,
+        );
+
+        run(&mut game, "break vase");
+        assert_eq!(game.item_locations.get("hammer"), None);
+        assert_eq!(
+            game.item_locations.get("key"),
+            Some(&ItemLocation::Room("hall".into()))
+        );
+    }
+
+    #[test]
+    fn a_cooldown_blocks_until_enough_turns_pass() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "ring_bell"
+            verbs = ["ring"]
+            nouns = ["bell"]
+            cooldown_turns = 2
+            cooldown_text = "The bell is still humming."
+            response = "Dong!"
+            "#,
+        );
+
+        assert_eq!(run(&mut game, "ring bell"), "Dong!");
+        assert_eq!(run(&mut game, "ring bell"), "The bell is still humming.");
+        run(&mut game, "wait");
+        assert_eq!(run(&mut game, "ring bell"), "The bell is still humming.");
+        run(&mut game, "wait");
+        assert_eq!(run(&mut game, "ring bell"), "Dong!");
+    }
+
+    #[test]
+    fn prepositions_do_not_get_in_the_way_of_nouns() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "knock"
+            verbs = ["knock"]
+            nouns = ["door"]
+            response = "Nobody comes."
+
+            [[room.action]]
+            id = "press"
+            verbs = ["press"]
+            nouns = ["panel"]
+            response = "The panel slides away."
+
+            [[room.action]]
+            id = "turn_crank"
+            verbs = ["turn"]
+            nouns = ["crank"]
+            response = "The crank creaks round."
+            "#,
+        );
+
+        assert_eq!(run(&mut game, "knock on the door"), "Nobody comes.");
+        assert_eq!(
+            run(&mut game, "press on the panel"),
+            "The panel slides away."
+        );
+        assert_eq!(run(&mut game, "turn the crank"), "The crank creaks round.");
+    }
+}
@@ -1,36 +1,73 @@
 use std::collections::HashSet;
 
-use crate::engine::helpers::apply_effects;
+use crate::engine::helpers::{apply_world_effects, fill_message};
+use crate::engine::locations::{ItemLiquids, ItemLocations};
 use crate::engine::output::Output;
 use crate::world;
 
-/// Returns true if all conditions are satisfied.
-/// Condition syntax:
-/// - "flag" means the flag must be present
-/// - "!flag" means the flag must NOT be present
-pub fn conditions_met(conditions: &[String], flags: &HashSet<String>) -> bool {
-    for cond in conditions {
-        if let Some(name) = cond.strip_prefix('!') {
-            // Negated condition: flag must NOT be present
-            if flags.contains(name) {
-                return false;
-            }
-        } else {
-            // Positive condition: flag must be present
-            if !flags.contains(cond) {
-                return false;
-            }
+/// Game state that conditions are checked against.
+#[derive(Clone, Copy)]
+pub struct EvalContext<'a> {
+    pub flags: &'a HashSet<String>,
+    pub turn_index: u64,
+    pub item_locations: &'a ItemLocations,
+    pub item_liquids: &'a ItemLiquids,
+}
+
+impl<'a> EvalContext<'a> {
+    pub fn new(
+        flags: &'a HashSet<String>,
+        turn_index: u64,
+        item_locations: &'a ItemLocations,
+        item_liquids: &'a ItemLiquids,
+    ) -> Self {
+        EvalContext {
+            flags,
+            turn_index,
+            item_locations,
+            item_liquids,
         }
     }
-    true
+}
+
+/// Returns true if all conditions are satisfied.
+pub fn conditions_met(conditions: &[world::Condition], ctx: &EvalContext) -> bool {
+    use world::Condition;
+
+    conditions.iter().all(|cond| match cond {
+        Condition::Flag(name) => ctx.flags.contains(name),
+        Condition::NotFlag(name) => !ctx.flags.contains(name),
+        Condition::Turn(op, n) => turn_comparison_holds(*op, ctx.turn_index, *n),
+        Condition::At(item, loc) => ctx.item_locations.get(item) == Some(loc),
+        Condition::NotAt(item, loc) => ctx.item_locations.get(item) != Some(loc),
+        Condition::Liquid(item, liquid) => ctx.item_liquids.get(item) == Some(liquid),
+        Condition::NotLiquid(item, liquid) => ctx.item_liquids.get(item) != Some(liquid),
+    })
+}
+
+fn turn_comparison_holds(op: world::TurnOp, turn: u64, n: u64) -> bool {
+    use world::TurnOp;
+
+    match op {
+        TurnOp::Lt => turn < n,
+        TurnOp::Le => turn <= n,
+        TurnOp::Gt => turn > n,
+        TurnOp::Ge => turn >= n,
+        TurnOp::Eq => turn == n,
+        TurnOp::Ne => turn != n,
+    }
 }
 
 /// Evaluate and fire any global conditions that are satisfied.
 /// This may print events and apply effects (flags add/remove).
+#[allow(clippy::too_many_arguments)]
 pub fn evaluate_global_conditions(
     out: &mut Output,
     world: &world::World,
     flags: &mut HashSet<String>,
+    turn_index: u64,
+    item_locations: &ItemLocations,
+    item_liquids: &ItemLiquids,
     current_room_id: &str,
     fired: &mut HashSet<String>,
 ) {
@@ -39,7 +76,8 @@ pub fn evaluate_global_conditions(
             continue;
         }
 
-        if !conditions_met(&gc.conditions, flags) {
+        let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+        if !conditions_met(&gc.conditions, &ctx) {
             continue;
         }
 
@@ -56,10 +94,45 @@ pub fn evaluate_global_conditions(
             out.event(txt.to_string());
         }
 
-        apply_effects(flags, &gc.effects);
+        apply_world_effects(world, flags, &gc.effects);
 
         if gc.one_shot {
             fired.insert(gc.id.clone());
         }
     }
 }
+
+/// Award the points of every `[[score]]` entry whose conditions now hold for the
+/// first time, announcing each. `scored` holds the ids already awarded.
+pub fn evaluate_score_events(
+    out: &mut Output,
+    world: &world::World,
+    ctx: &EvalContext,
+    scored: &mut HashSet<String>,
+) {
+    for sc in &world.score_events {
+        if scored.contains(&sc.id) || !conditions_met(&sc.conditions, ctx) {
+            continue;
+        }
+        scored.insert(sc.id.clone());
+        if sc.points == 0 {
+            continue;
+        }
+        out.event(fill_message(
+            &world.messages.score_up,
+            &[("points", &sc.points.to_string())],
+        ));
+    }
+}
+
+/// Returns the first ending whose conditions are satisfied, if any.
+/// Endings are checked in authoring order.
+pub fn evaluate_endings<'a>(
+    world: &'a world::World,
+    ctx: &EvalContext,
+) -> Option<&'a world::Ending> {
+    world
+        .endings
+        .iter()
+        .find(|ending| conditions_met(&ending.conditions, ctx))
+}
@@ -1,11 +1,16 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-use crate::engine::conditions::conditions_met;
+use crate::engine::conditions::{EvalContext, conditions_met};
+use crate::engine::locations::ItemLocations;
 use crate::world;
 
-/// Apply a list of effects to flags.
+/// Apply a list of effects to flags, one after another in list order.
 /// - "flag"  => insert
 /// - "!flag" => remove
+///
+/// When a list names the same flag twice, the later entry wins: ["!a", "a"] leaves
+/// "a" set, ["a", "!a"] leaves it unset. Nothing else (global conditions, room
+/// re-rendering) looks at the flags until the whole list has been applied.
 pub fn apply_effects(flags: &mut HashSet<String>, effects: &[String]) {
     for eff in effects {
         if let Some(name) = eff.strip_prefix('!') {
@@ -16,28 +21,329 @@ pub fn apply_effects(flags: &mut HashSet<String>, effects: &[String]) {
     }
 }
 
+/// Apply effects as a set difference instead: every removal first, then every
+/// addition, so a flag both set and cleared by the list ends up set whatever the
+/// order it was written in.
+pub fn apply_effects_ordered(flags: &mut HashSet<String>, effects: &[String]) {
+    let (removals, additions): (Vec<&String>, Vec<&String>) =
+        effects.iter().partition(|eff| eff.starts_with('!'));
+
+    for eff in removals {
+        flags.remove(&eff[1..]);
+    }
+    for eff in additions {
+        flags.insert(eff.clone());
+    }
+}
+
+/// Apply an effects list the way `world` asks for: in list order, or removals
+/// first when the world sets `effects_clear_first`.
+pub fn apply_world_effects(world: &world::World, flags: &mut HashSet<String>, effects: &[String]) {
+    if world.effects_clear_first {
+        apply_effects_ordered(flags, effects);
+    } else {
+        apply_effects(flags, effects);
+    }
+}
+
+/// Case-insensitive comparison that also folds non-ASCII letters ("Épée" == "épée").
+/// Use this, not `eq_ignore_ascii_case`, whenever author text meets player input.
+pub fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a == b || a.to_lowercase() == b.to_lowercase()
+}
+
+/// Case-insensitive comparison of single characters, non-ASCII letters included.
+pub(crate) fn chars_eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Rewrite `input` through the world's command aliases: the longest alias matching
+/// its leading words is replaced by that alias's command, and the rest is kept
+/// ("use lever" with use => "pull" becomes "pull lever"). Chained aliases expand in
+/// turn, but each alias at most once, so a cycle cannot loop. None if nothing matched.
+pub fn expand_command_aliases(world: &world::World, input: &str) -> Option<String> {
+    let mut command = input.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut used: HashSet<&str> = HashSet::new();
+
+    loop {
+        let lower = command.to_lowercase();
+        let best = world
+            .command_aliases
+            .iter()
+            .filter(|(alias, _)| !used.contains(alias.as_str()))
+            .filter(|(alias, _)| {
+                lower == **alias
+                    || lower
+                        .strip_prefix(alias.as_str())
+                        .is_some_and(|rest| rest.starts_with(' '))
+            })
+            .max_by_key(|(alias, _)| alias.split_whitespace().count());
+
+        match best {
+            Some((alias, replacement)) => {
+                used.insert(alias);
+                let rest = lower[alias.len()..].to_string();
+                command = format!("{}{}", replacement, rest);
+            }
+            None if used.is_empty() => return None,
+            None => return Some(command),
+        }
+    }
+}
+
+/// Two-word verbs: (verb, particle, built-in command it means). An empty command
+/// keeps the verb and particle together for actions to match ("turn on").
+const COMPOUND_VERBS: [(&str, &str, &str); 5] = [
+    ("pick", "up", "take"),
+    ("turn", "on", ""),
+    ("turn", "off", ""),
+    ("switch", "on", ""),
+    ("switch", "off", ""),
+];
+
+/// Read two-word verbs as one: "pick up coin" becomes "take coin". The particle may
+/// also come last ("pick the coin up", "turn lamp on" => "turn on lamp"). A phrase
+/// the world's actions use as a verb is left for them to handle. ("put down" is
+/// covered by `floor_drop_item`.) None if nothing changed.
+pub fn expand_compound_verbs(world: &world::World, input: &str) -> Option<String> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    if words.len() < 2 {
+        return None;
+    }
+
+    for (verb, particle, command) in COMPOUND_VERBS {
+        if !words[0].eq_ignore_ascii_case(verb) {
+            continue;
+        }
+
+        let particle_at = if words[1].eq_ignore_ascii_case(particle) {
+            1
+        } else if words.len() > 2 && words[words.len() - 1].eq_ignore_ascii_case(particle) {
+            words.len() - 1
+        } else {
+            continue;
+        };
+
+        let phrase = format!("{} {}", verb, particle);
+        let head = if command.is_empty() {
+            phrase
+        } else if action_claims(world, &phrase, &words[1..]) {
+            return None;
+        } else {
+            command.to_string()
+        };
+
+        let rest = words[1..]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i + 1 != particle_at)
+            .map(|(_, w)| *w);
+        let rewritten = std::iter::once(head.as_str())
+            .chain(rest)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        return (rewritten != words.join(" ")).then_some(rewritten);
+    }
+
+    None
+}
+
+/// True if some room, global or NPC action has `verb` as a verb and either no nouns
+/// or a noun whose words all appear in `words`.
+fn action_claims(world: &world::World, verb: &str, words: &[&str]) -> bool {
+    world
+        .rooms
+        .values()
+        .flat_map(|r| &r.actions)
+        .chain(&world.global_actions)
+        .chain(world.npcs.values().flat_map(|n| &n.actions))
+        .filter(|a| a.verbs.iter().any(|v| eq_ignore_case(v.trim(), verb)))
+        .any(|a| {
+            a.nouns.is_empty()
+                || a.nouns.iter().any(|n| {
+                    n.split_whitespace()
+                        .all(|nw| words.iter().any(|w| eq_ignore_case(w, nw)))
+                })
+        })
+}
+
+/// NPC dispositions are stored as flags: "disp:<npc_id>:<disposition>".
+/// At most one is set per NPC, so conditions can test e.g. "disp:merchant:hostile".
+pub fn set_npc_disposition(flags: &mut HashSet<String>, npc_id: &str, disposition: &str) {
+    let prefix = format!("disp:{}:", npc_id);
+    flags.retain(|f| !f.starts_with(&prefix));
+    flags.insert(format!("{}{}", prefix, disposition));
+}
+
+/// Current disposition of an NPC, if one has been set.
+pub fn npc_disposition<'a>(flags: &'a HashSet<String>, npc_id: &str) -> Option<&'a str> {
+    let prefix = format!("disp:{}:", npc_id);
+    flags.iter().find_map(|f| f.strip_prefix(&prefix))
+}
+
+/// A secret exit becomes known through the flag "exit_known:<room_id>:<direction>",
+/// set when the player uses it or by any effect (e.g. a "search" action).
+pub fn exit_known_flag(room_id: &str, direction: &str) -> String {
+    format!("exit_known:{}:{}", room_id, direction)
+}
+
+/// Replace each `from` item with its `into` item at the same location.
+/// Items that aren't currently placed anywhere are left alone. Anything inside a
+/// transformed item moves into its replacement.
+pub fn apply_item_transforms(
+    item_locations: &mut ItemLocations,
+    transforms: &[world::ItemTransform],
+) {
+    for t in transforms {
+        let loc = match item_locations.remove(&t.from) {
+            Some(l) => l,
+            None => continue,
+        };
+
+        let inside: Vec<String> = item_locations
+            .ids_at(&world::ItemLocation::Item(t.from.clone()))
+            .map(str::to_string)
+            .collect();
+        for inner_id in inside {
+            item_locations.insert(inner_id, world::ItemLocation::Item(t.into.clone()));
+        }
+
+        item_locations.insert(t.into.clone(), loc);
+    }
+}
+
+/// Take each of `remove` out of play, then place each spawned item at its
+/// location (moving it there if it is already in play).
+pub fn apply_item_spawns(
+    item_locations: &mut ItemLocations,
+    remove: &[String],
+    spawns: &[world::ItemSpawn],
+) {
+    for id in remove {
+        item_locations.remove(id);
+    }
+
+    for sp in spawns {
+        item_locations.insert(sp.item.clone(), sp.location.clone());
+    }
+}
+
+/// Fill in a message template: each "{name}" becomes its value.
+pub fn fill_message(template: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
 /// Returns true if the item's *visibility* conditions are satisfied.
-pub fn item_visible(item: &world::Item, flags: &HashSet<String>) -> bool {
-    conditions_met(&item.conditions, flags)
+pub fn item_visible(item: &world::Item, ctx: &EvalContext) -> bool {
+    conditions_met(&item.conditions, ctx)
+}
+
+/// True if the item is a container that is open right now (its conditions are met).
+pub fn container_open(item: &world::Item, ctx: &EvalContext) -> bool {
+    match &item.kind {
+        world::ItemKind::Container(props) => conditions_met(&props.conditions, ctx),
+        _ => false,
+    }
 }
 
-pub fn item_in_room(
-    item_id: &str,
-    item_locations: &HashMap<String, world::ItemLocation>,
-    room_id: &str,
-) -> bool {
+pub fn item_in_room(item_id: &str, item_locations: &ItemLocations, room_id: &str) -> bool {
     match item_locations.get(item_id) {
         Some(world::ItemLocation::Room(r)) => r == room_id,
         _ => false,
     }
 }
 
-pub fn item_in_inventory(
-    item_id: &str,
-    item_locations: &HashMap<String, world::ItemLocation>,
-) -> bool {
+pub fn item_in_inventory(item_id: &str, item_locations: &ItemLocations) -> bool {
     matches!(
         item_locations.get(item_id),
         Some(world::ItemLocation::Inventory)
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{hall, hall_with, run};
+    use crate::world::ItemLocation;
+
+    fn effects(list: &[&str]) -> Vec<String> {
+        list.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn effects_apply_in_order_unless_cleared_first() {
+        let mut flags: HashSet<String> = HashSet::from(["b".to_string()]);
+        apply_effects(&mut flags, &effects(&["a", "!a", "!b", "c"]));
+        assert!(!flags.contains("a"));
+        assert!(!flags.contains("b"));
+        assert!(flags.contains("c"));
+        apply_effects(&mut flags, &effects(&["!a", "a"]));
+        assert!(flags.contains("a"));
+
+        let mut flags: HashSet<String> = HashSet::from(["b".to_string()]);
+        apply_effects_ordered(&mut flags, &effects(&["a", "!a", "!b", "c"]));
+        assert!(flags.contains("a"));
+        assert!(!flags.contains("b"));
+        assert!(flags.contains("c"));
+        apply_effects_ordered(&mut flags, &effects(&["a", "!a"]));
+        assert!(flags.contains("a"));
+    }
+
+    fn relight(world_fields: &str) -> crate::GameState {
+        hall_with(
+            &format!("start_flags = [\"lit\"]\n{}", world_fields),
+            r#"
+            [[room.action]]
+            id = "relight"
+            verbs = ["relight"]
+            response = "The lamp flickers and steadies."
+            effects = ["lit", "!lit", "!draft", "draft"]
+            "#,
+        )
+    }
+
+    #[test]
+    fn an_action_that_clears_and_sets_a_flag_follows_the_world_order() {
+        let mut game = relight("");
+        let said = run(&mut game, "relight");
+        assert!(said.contains("steadies"), "{}", said);
+        assert!(!game.flags.contains("lit"));
+        assert!(game.flags.contains("draft"));
+
+        let mut game = relight("effects_clear_first = true");
+        run(&mut game, "relight");
+        assert!(game.flags.contains("lit"));
+        assert!(game.flags.contains("draft"));
+    }
+
+    #[test]
+    fn two_word_verbs_reach_actions_and_take() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "lamp_on"
+            verbs = ["turn on"]
+            nouns = ["lamp"]
+            response = "The lamp glows."
+            effects = ["lamp_on"]
+
+            [[item]]
+            id = "coin"
+            name = "coin"
+            start_location = "room:hall"
+            "#,
+        );
+
+        assert_eq!(run(&mut game, "turn on lamp"), "The lamp glows.");
+        assert_eq!(run(&mut game, "turn lamp on"), "The lamp glows.");
+        run(&mut game, "pick up coin");
+        assert_eq!(
+            game.item_locations.get("coin"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+}
@@ -1,15 +1,78 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::engine::conditions::conditions_met;
-use crate::engine::output::Output;
+use crate::engine::actions::{ActionCooldowns, fire_action_with_cooldown, try_auto_open};
+use crate::engine::conditions::{EvalContext, conditions_met};
+use crate::engine::helpers::{
+    apply_world_effects, eq_ignore_case, exit_known_flag, fill_message, item_visible,
+};
+use crate::engine::locations::{ItemLiquids, ItemLocations};
+use crate::engine::output::{Choice, Output};
 use crate::world;
 
-use crate::engine::npcs::{NpcMatch, find_npc_by_words_scored, try_handle_examine_npc};
+use crate::engine::npcs::{
+    NpcMatch, ask_which_npc, find_npc_by_words_scored, try_handle_examine_npc,
+};
 
 enum ItemMatch<'a> {
     None,
     One(&'a world::Item),
-    Many(()),
+    /// Tied best matches, sorted by name then id.
+    Many(Vec<&'a world::Item>),
+}
+
+/// Where a verb would rather find an item when several match equally well.
+#[derive(Clone, Copy)]
+enum LocationPreference {
+    None,
+    Inventory,
+    Room,
+}
+
+/// Split an ordinal selector off the query words.
+/// Accepts a leading ordinal ("first torch", "2nd torch") or a trailing number ("torch 2").
+/// Returns the remaining words and the zero-based index, if an ordinal was present.
+fn split_ordinal(mut words: Vec<String>) -> (Vec<String>, Option<usize>) {
+    fn ordinal_value(word: &str) -> Option<usize> {
+        let n = match word {
+            "first" => 1,
+            "second" => 2,
+            "third" => 3,
+            "fourth" => 4,
+            "fifth" => 5,
+            "sixth" => 6,
+            "seventh" => 7,
+            "eighth" => 8,
+            "ninth" => 9,
+            "tenth" => 10,
+            w => {
+                let digits = w
+                    .strip_suffix("st")
+                    .or_else(|| w.strip_suffix("nd"))
+                    .or_else(|| w.strip_suffix("rd"))
+                    .or_else(|| w.strip_suffix("th"))?;
+                digits.parse::<usize>().ok()?
+            }
+        };
+        if n == 0 { None } else { Some(n - 1) }
+    }
+
+    if words.len() < 2 {
+        return (words, None);
+    }
+
+    if let Some(idx) = ordinal_value(&words[0]) {
+        words.remove(0);
+        return (words, Some(idx));
+    }
+
+    if let Some(n) = words.last().and_then(|w| w.parse::<usize>().ok())
+        && n > 0
+    {
+        words.pop();
+        return (words, Some(n - 1));
+    }
+
+    (words, None)
 }
 
 /// Find the *best* matching item by counting full-word overlaps.
@@ -17,16 +80,22 @@ enum ItemMatch<'a> {
 /// - Ties => Many (ambiguity)
 /// - Score 0 => None
 ///
+/// An ordinal in the query ("first torch", "torch 2") selects among the tied best
+/// matches, ordered by name and then id so the choice is stable.
+///
 /// `respect_conditions` controls whether `item.conditions` are enforced during matching.
 /// - true  => item must satisfy its visibility/interaction conditions
 /// - false => ignore item.conditions (useful for inventory-only operations like drop)
+///
+/// `prefer` breaks a tie (without an ordinal) when exactly one of the tied items is in
+/// the preferred place, e.g. the carried key over the one on the floor for "examine".
 fn find_item_by_words_scored<'a, F>(
     world: &'a world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
-    flags: &HashSet<String>,
+    ctx: &EvalContext,
     query: &str,
     filter: F,
     respect_conditions: bool,
+    prefer: LocationPreference,
 ) -> ItemMatch<'a>
 where
     F: Fn(&'a world::Item, &world::ItemLocation) -> bool,
@@ -37,6 +106,8 @@ where
         .map(|w| w.to_lowercase())
         .collect();
 
+    let (query_words, ordinal) = split_ordinal(query_words);
+
     if query_words.is_empty() {
         return ItemMatch::None;
     }
@@ -45,7 +116,7 @@ where
     let mut scored: Vec<(&world::Item, usize)> = Vec::new();
 
     for item in world.items.values() {
-        let loc = match item_locations.get(&item.id) {
+        let loc = match ctx.item_locations.get(&item.id) {
             Some(l) => l,
             None => continue,
         };
@@ -55,7 +126,7 @@ where
         }
 
         // Optionally respect item visibility/interaction conditions
-        if respect_conditions && !conditions_met(&item.conditions, flags) {
+        if respect_conditions && !conditions_met(&item.conditions, ctx) {
             continue;
         }
 
@@ -107,233 +178,421 @@ where
         .map(|(i, _)| i)
         .collect();
 
+    // Sort to make ordinal selection and ambiguity stable
+    best.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+
+    if let Some(idx) = ordinal {
+        return match best.get(idx) {
+            Some(item) => ItemMatch::One(item),
+            None => ItemMatch::None,
+        };
+    }
+
+    if best.len() > 1 {
+        let preferred: Vec<&world::Item> = best
+            .iter()
+            .copied()
+            .filter(|item| {
+                matches!(
+                    (prefer, ctx.item_locations.get(&item.id)),
+                    (
+                        LocationPreference::Inventory,
+                        Some(world::ItemLocation::Inventory)
+                    ) | (LocationPreference::Room, Some(world::ItemLocation::Room(_)))
+                )
+            })
+            .collect();
+
+        if preferred.len() == 1 {
+            return ItemMatch::One(preferred[0]);
+        }
+    }
+
     match best.len() {
         0 => ItemMatch::None,
         1 => ItemMatch::One(best[0]),
-        _ => {
-            // Optional: sort to make stable
-            best.sort_by(|a, b| a.name.cmp(&b.name));
-            ItemMatch::Many(())
-        }
+        _ => ItemMatch::Many(best),
     }
 }
 
 /// Convenience wrapper: default behavior (respect item.conditions)
 fn find_item<'a, F>(
     world: &'a world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
-    flags: &HashSet<String>,
+    ctx: &EvalContext,
     query: &str,
     filter: F,
 ) -> ItemMatch<'a>
 where
     F: Fn(&'a world::Item, &world::ItemLocation) -> bool,
 {
-    find_item_by_words_scored(world, item_locations, flags, query, filter, true)
+    find_item_by_words_scored(world, ctx, query, filter, true, LocationPreference::None)
 }
 
 /// Convenience wrapper: ignore item.conditions
 fn find_item_ignore_conditions<'a, F>(
     world: &'a world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
-    flags: &HashSet<String>,
+    item_locations: &ItemLocations,
     query: &str,
     filter: F,
 ) -> ItemMatch<'a>
 where
     F: Fn(&'a world::Item, &world::ItemLocation) -> bool,
 {
-    find_item_by_words_scored(world, item_locations, flags, query, filter, false)
+    // Conditions aren't checked, so no flags, turn or liquids are needed
+    let no_flags = HashSet::new();
+    let no_liquids = ItemLiquids::new();
+    find_item_by_words_scored(
+        world,
+        &EvalContext::new(&no_flags, 0, item_locations, &no_liquids),
+        query,
+        filter,
+        false,
+        LocationPreference::None,
+    )
 }
 
-pub fn handle_inventory(
+/// "examine all": one line for each visible item lying in the room, then each one
+/// carried, in file order: its name and the first line of its examine text.
+pub fn handle_examine_all(
     out: &mut Output,
     world: &world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
+    current_room_id: &str,
+    ctx: &EvalContext,
 ) {
     use world::ItemLocation;
 
-    let mut carried: Vec<&world::Item> = world
-        .items
-        .values()
-        .filter(|item| matches!(item_locations.get(&item.id), Some(ItemLocation::Inventory)))
+    let items: Vec<&world::Item> = ctx
+        .item_locations
+        .items_at(world, &ItemLocation::Room(current_room_id.to_string()))
+        .into_iter()
+        .chain(ctx.item_locations.items_at(world, &ItemLocation::Inventory))
+        .filter(|item| item_visible(item, ctx))
         .collect();
 
+    if items.is_empty() {
+        out.say(&world.messages.nothing_to_examine);
+        return;
+    }
+
+    for item in items {
+        let carried = matches!(
+            ctx.item_locations.get(&item.id),
+            Some(ItemLocation::Inventory)
+        );
+        let first_line = if item.examine_requires_inventory && !carried {
+            HOLD_TO_EXAMINE
+        } else {
+            item.examine_text
+                .trim()
+                .lines()
+                .next()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .unwrap_or("You see nothing special.")
+        };
+        out.say(format!("{}: {}", item.name, first_line));
+    }
+}
+
+pub fn handle_inventory(out: &mut Output, world: &world::World, ctx: &EvalContext) {
+    use world::{ItemKind, ItemLocation};
+
+    let mut carried = ctx.item_locations.items_at(world, &ItemLocation::Inventory);
+
     if carried.is_empty() {
-        out.say("You are carrying nothing.");
+        out.say(&world.messages.inventory_empty);
         return;
     }
 
     carried.sort_by(|a, b| a.name.cmp(&b.name));
 
-    out.say("You are carrying:");
+    out.say(&world.messages.inventory_header);
     for item in carried {
         let txt = item.inventory_text.trim();
-        if txt.is_empty() {
-            out.say(format!("  {}", item.name));
+        let mut line = if txt.is_empty() {
+            format!("  {}", item.name)
         } else {
-            out.say(format!("  {}", txt));
+            format!("  {}", txt)
+        };
+
+        // Carried containers travel with their contents; show them when open.
+        if let ItemKind::Container(props) = &item.kind
+            && conditions_met(&props.conditions, ctx)
+        {
+            let mut contents: Vec<&str> = ctx
+                .item_locations
+                .items_at(world, &ItemLocation::Item(item.id.clone()))
+                .into_iter()
+                .filter(|other| conditions_met(&other.conditions, ctx))
+                .map(|other| other.name.as_str())
+                .collect();
+
+            if !contents.is_empty() {
+                contents.sort();
+                line.push_str(&format!(" (containing: {})", contents.join(", ")));
+            }
         }
+
+        out.say(line);
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_take(
     out: &mut Output,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     world: &world::World,
     current_room_id: &str,
     target_name: &str,
     flags: &HashSet<String>,
+    turn_index: u64,
 ) {
     use world::ItemLocation;
 
     let query = target_name.trim().to_lowercase();
     if query.is_empty() {
-        out.say("Take what?");
+        out.say(&world.messages.take_what);
         return;
     }
 
-    let result = find_item(
+    // Carried items take part so "take brass key" doesn't grab the red key on the
+    // floor; room items win ties.
+    let result = find_item_by_words_scored(
         world,
-        item_locations,
-        flags,
+        &EvalContext::new(flags, turn_index, item_locations, item_liquids),
         &query,
         |_item, loc| match loc {
             ItemLocation::Room(room_id) => room_id == current_room_id,
+            ItemLocation::Inventory => true,
             _ => false,
         },
+        true,
+        LocationPreference::Room,
     );
 
     let item = match result {
         ItemMatch::None => {
-            out.say("You don't see that here.");
+            out.say(&world.messages.not_here);
             return;
         }
-        ItemMatch::Many(_) => {
-            out.say("Be more specific.");
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &query, &candidates);
             return;
         }
         ItemMatch::One(i) => i,
     };
 
+    if matches!(item_locations.get(&item.id), Some(ItemLocation::Inventory)) {
+        out.say(fill_message(
+            &world.messages.already_have,
+            &[("item", &item.name)],
+        ));
+        return;
+    }
+
     if !item.portable {
-        out.say(format!("You can't take the {}.", item.name));
+        out.say(fill_message(
+            &world.messages.cant_take,
+            &[("item", &item.name)],
+        ));
         return;
     }
 
     item_locations.insert(item.id.clone(), ItemLocation::Inventory);
-    out.say(format!("You take the {}.", item.name));
+    out.say(fill_message(&world.messages.take, &[("item", &item.name)]));
 }
 
 pub fn handle_take_all_room(
     out: &mut Output,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     world: &world::World,
     current_room_id: &str,
     flags: &HashSet<String>,
+    turn_index: u64,
 ) {
-    use world::ItemLocation;
+    use world::{ItemKind, ItemLocation};
 
-    let mut to_take: Vec<String> = Vec::new();
+    // (item, container it is taken from), in the order the world file lists them
+    let mut to_take: Vec<(&world::Item, Option<&world::Item>)> = Vec::new();
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
 
-    for item in world.items.values() {
-        let loc = match item_locations.get(&item.id) {
-            Some(l) => l,
-            None => continue,
+    for item in ctx
+        .item_locations
+        .items_at(world, &ItemLocation::Room(current_room_id.to_string()))
+    {
+        if !conditions_met(&item.conditions, &ctx) {
+            continue;
+        }
+
+        if item.portable {
+            to_take.push((item, None));
+        }
+
+        // Only containers lying loose in this room, visible and open.
+        let open = match &item.kind {
+            ItemKind::Container(props) => conditions_met(&props.conditions, &ctx),
+            _ => false,
         };
+        if !world.take_all_includes_containers || !open {
+            continue;
+        }
 
-        if let ItemLocation::Room(room_id) = loc {
-            if room_id == current_room_id
-                && conditions_met(&item.conditions, flags)
-                && item.portable
-            {
-                to_take.push(item.id.clone());
+        for inner in ctx
+            .item_locations
+            .items_at(world, &ItemLocation::Item(item.id.clone()))
+        {
+            if inner.portable && conditions_met(&inner.conditions, &ctx) {
+                to_take.push((inner, Some(item)));
             }
         }
     }
+    to_take.sort_by_key(|(item, _)| world.items.get_index_of(&item.id));
 
     if to_take.is_empty() {
-        out.say("There is nothing here you can take.");
+        out.say(&world.messages.nothing_to_take);
         return;
     }
 
-    for item_id in &to_take {
-        if let Some(item) = world.items.get(item_id) {
-            item_locations.insert(item_id.clone(), ItemLocation::Inventory);
-            out.say(format!("You take the {}.", item.name));
+    for (item, from) in &to_take {
+        item_locations.insert(item.id.clone(), ItemLocation::Inventory);
+        match from {
+            Some(container) => out.say(fill_message(
+                &world.messages.take_from,
+                &[("item", &item.name), ("container", &container.name)],
+            )),
+            None => out.say(fill_message(&world.messages.take, &[("item", &item.name)])),
+        }
+    }
+}
+
+/// "put coin on the floor", "put coin down", "put down coin", "set coin here": the
+/// item phrase when a put/place/set/leave command just means dropping it. A visible
+/// item actually called "floor" or "ground" keeps those words for itself.
+pub fn floor_drop_item<'q>(
+    verb: &str,
+    rest: &'q str,
+    world: &world::World,
+    current_room_id: &str,
+    ctx: &EvalContext,
+) -> Option<&'q str> {
+    use world::ItemLocation;
+
+    const VERBS: [&str; 4] = ["put", "place", "set", "leave"];
+    const FLOORS: [&str; 2] = ["floor", "ground"];
+
+    if !VERBS.iter().any(|v| verb.eq_ignore_ascii_case(v)) {
+        return None;
+    }
+
+    let rest = rest.trim();
+    if let Some(item) = rest.strip_prefix("down ") {
+        return Some(item.trim());
+    }
+    for suffix in [" down", " here"] {
+        if let Some(item) = rest.strip_suffix(suffix) {
+            return Some(item.trim()).filter(|i| !i.is_empty());
         }
     }
+
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    let floor_at = words.iter().rposition(|w| FLOORS.contains(w))?;
+    if floor_at + 1 != words.len() {
+        return None;
+    }
+    let mut prep_at = floor_at;
+    if prep_at > 0 && words[prep_at - 1] == "the" {
+        prep_at -= 1;
+    }
+    if prep_at < 2 || !matches!(words[prep_at - 1], "on" | "onto") {
+        return None;
+    }
+
+    let real_floor = world.items.values().any(|item| {
+        let in_scope = match ctx.item_locations.get(&item.id) {
+            Some(ItemLocation::Room(room_id)) => room_id == current_room_id,
+            Some(ItemLocation::Inventory) => true,
+            _ => false,
+        };
+        in_scope
+            && conditions_met(&item.conditions, ctx)
+            && std::iter::once(&item.name)
+                .chain(&item.aliases)
+                .flat_map(|n| n.split_whitespace())
+                .any(|w| FLOORS.iter().any(|f| eq_ignore_case(w, f)))
+    });
+    if real_floor {
+        return None;
+    }
+
+    let item_end = words[..prep_at - 1].join(" ").len();
+    Some(rest[..item_end].trim())
 }
 
 pub fn handle_drop(
     out: &mut Output,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
     world: &world::World,
     current_room_id: &str,
     target_name: &str,
-) {
+) -> Option<String> {
     use world::ItemLocation;
 
     let query = target_name.trim().to_lowercase();
     if query.is_empty() {
-        out.say("Drop what?");
-        return;
+        out.say(&world.messages.drop_what);
+        return None;
     }
 
     // Drop should NOT be blocked by item.conditions (visibility flags, etc.)
-    // We pass an empty set for flags because we're ignoring conditions anyway.
-    let dummy_flags = HashSet::new();
-
-    let result =
-        find_item_ignore_conditions(world, item_locations, &dummy_flags, &query, |_item, loc| {
-            matches!(loc, ItemLocation::Inventory)
-        });
+    let result = find_item_ignore_conditions(world, item_locations, &query, |_item, loc| {
+        matches!(loc, ItemLocation::Inventory)
+    });
 
     let item = match result {
         ItemMatch::None => {
-            out.say("You aren't carrying that.");
-            return;
+            out.say(&world.messages.not_carrying);
+            return None;
         }
-        ItemMatch::Many(_) => {
-            out.say("Be more specific.");
-            return;
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &query, &candidates);
+            return None;
         }
         ItemMatch::One(i) => i,
     };
 
+    if !item.droppable {
+        out.say(&world.messages.cant_drop);
+        return None;
+    }
+
     item_locations.insert(
         item.id.clone(),
         ItemLocation::Room(current_room_id.to_string()),
     );
-    out.say(format!("You drop the {}.", item.name));
+    out.say(fill_message(&world.messages.drop, &[("item", &item.name)]));
+    Some(item.id.clone())
 }
 
 pub fn handle_drop_all(
     out: &mut Output,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
     world: &world::World,
     current_room_id: &str,
-) {
+) -> Vec<String> {
     use world::ItemLocation;
 
-    let mut to_drop: Vec<String> = Vec::new();
-
-    for item in world.items.values() {
-        let loc = match item_locations.get(&item.id) {
-            Some(l) => l,
-            None => continue,
-        };
-
-        if let ItemLocation::Inventory = loc {
-            if item.portable {
-                to_drop.push(item.id.clone());
-            }
-        }
-    }
+    let to_drop: Vec<String> = item_locations
+        .items_at(world, &ItemLocation::Inventory)
+        .into_iter()
+        .filter(|item| item.portable && item.droppable)
+        .map(|item| item.id.clone())
+        .collect();
 
     if to_drop.is_empty() {
-        out.say("You aren't carrying anything you can drop.");
-        return;
+        out.say(&world.messages.nothing_to_drop);
+        return to_drop;
     }
 
     for item_id in &to_drop {
@@ -342,19 +601,101 @@ pub fn handle_drop_all(
                 item_id.clone(),
                 ItemLocation::Room(current_room_id.to_string()),
             );
-            out.say(format!("You drop the {}.", item.name));
+            out.say(fill_message(&world.messages.drop, &[("item", &item.name)]));
+        }
+    }
+
+    to_drop
+}
+
+/// Fire the current room's `on_drop` reactions for items just dropped there.
+/// At most one trigger fires per dropped item: the first (in file order) for that
+/// item that hasn't already fired as a one-shot.
+pub fn fire_drop_triggers(
+    out: &mut Output,
+    world: &world::World,
+    room: &world::Room,
+    dropped: &[String],
+    flags: &mut HashSet<String>,
+    fired_drop_triggers: &mut HashSet<String>,
+) {
+    for item_id in dropped {
+        for (idx, trigger) in room.on_drop.iter().enumerate() {
+            if &trigger.item != item_id {
+                continue;
+            }
+
+            let key = format!("{}::{}::{}", room.id, trigger.item, idx);
+            if trigger.one_shot && fired_drop_triggers.contains(&key) {
+                continue;
+            }
+
+            let txt = trigger.response.trim();
+            if !txt.is_empty() {
+                out.say(txt);
+            }
+            apply_world_effects(world, flags, &trigger.effects);
+
+            if trigger.one_shot {
+                fired_drop_triggers.insert(key);
+            }
+            break;
+        }
+    }
+}
+
+/// `[[on_take]]`: react to items that just entered the inventory. Each trigger fires
+/// once per game, the first time its item is taken while its conditions hold.
+#[allow(clippy::too_many_arguments)]
+pub fn fire_take_triggers(
+    out: &mut Output,
+    world: &world::World,
+    taken: &[String],
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+    item_locations: &ItemLocations,
+    item_liquids: &ItemLiquids,
+    fired_take_triggers: &mut HashSet<String>,
+) {
+    for item_id in taken {
+        for (idx, trigger) in world.on_take.iter().enumerate() {
+            if &trigger.item != item_id {
+                continue;
+            }
+
+            let key = format!("{}::{}", trigger.item, idx);
+            if fired_take_triggers.contains(&key)
+                || !conditions_met(
+                    &trigger.conditions,
+                    &EvalContext::new(flags, turn_index, item_locations, item_liquids),
+                )
+            {
+                continue;
+            }
+
+            let txt = trigger.response.trim();
+            if !txt.is_empty() {
+                out.say(txt);
+            }
+            apply_world_effects(world, flags, &trigger.effects);
+            fired_take_triggers.insert(key);
+            break;
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_take_from_container(
     out: &mut Output,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     world: &world::World,
     current_room_id: &str,
     item_name: &str,
     container_name: &str,
-    flags: &HashSet<String>,
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+    cooldowns: &mut ActionCooldowns,
 ) {
     use world::{ItemKind, ItemLocation};
 
@@ -362,19 +703,18 @@ pub fn handle_take_from_container(
     let container_query = container_name.trim().to_lowercase();
 
     if item_query.is_empty() {
-        out.say("Take what?");
+        out.say(&world.messages.take_what);
         return;
     }
     if container_query.is_empty() {
-        out.say("Take it from where?");
+        out.say(&world.messages.take_from_where);
         return;
     }
 
     // Find the container (must be visible)
     let container_result = find_item(
         world,
-        item_locations,
-        flags,
+        &EvalContext::new(flags, turn_index, item_locations, item_liquids),
         &container_query,
         |candidate, loc| {
             matches!(candidate.kind, ItemKind::Container(_))
@@ -388,33 +728,48 @@ pub fn handle_take_from_container(
 
     let (container, props) = match container_result {
         ItemMatch::None => {
-            out.say("You don't see any container like that here.");
+            out.say(&world.messages.no_container);
             return;
         }
-        ItemMatch::Many(_) => {
-            out.say("Be more specific about which container.");
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &container_query, &candidates);
             return;
         }
         ItemMatch::One(it) => {
             if let ItemKind::Container(ref props) = it.kind {
                 (it, props)
             } else {
-                out.say("That isn't a container.");
+                out.say(&world.messages.not_container);
                 return;
             }
         }
     };
 
-    if !props.conditions.is_empty() && !conditions_met(&props.conditions, flags) {
-        out.say(format!("{}", props.closed_text.trim()));
+    if !props.conditions.is_empty()
+        && !conditions_met(
+            &props.conditions,
+            &EvalContext::new(flags, turn_index, item_locations, item_liquids),
+        )
+        && !try_auto_open(
+            out,
+            world,
+            item_locations,
+            item_liquids,
+            current_room_id,
+            flags,
+            turn_index,
+            &props.conditions,
+            cooldowns,
+        )
+    {
+        out.say(props.closed_text.trim().to_string());
         return;
     }
 
     // Find the item inside (must be visible)
     let item_result = find_item(
         world,
-        item_locations,
-        flags,
+        &EvalContext::new(flags, turn_index, item_locations, item_liquids),
         &item_query,
         |_candidate, loc| match loc {
             ItemLocation::Item(parent_id) => parent_id == &container.id,
@@ -424,41 +779,48 @@ pub fn handle_take_from_container(
 
     let item = match item_result {
         ItemMatch::None => {
-            out.say(format!(
-                "You don't see anything like that in the {}.",
-                container.name
+            out.say(fill_message(
+                &world.messages.not_in_container,
+                &[("container", &container.name)],
             ));
             return;
         }
-        ItemMatch::Many(_) => {
-            out.say("Be more specific about what to take.");
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &item_query, &candidates);
             return;
         }
         ItemMatch::One(i) => i,
     };
 
     if !item.portable {
-        out.say(format!("You can't take the {}.", item.name));
+        out.say(fill_message(
+            &world.messages.cant_take,
+            &[("item", &item.name)],
+        ));
         return;
     }
 
     item_locations.insert(item.id.clone(), ItemLocation::Inventory);
-    out.say(format!(
-        "You take the {} from the {}.",
-        item.name, container.name
+    out.say(fill_message(
+        &world.messages.take_from,
+        &[("item", &item.name), ("container", &container.name)],
     ));
 }
 
 /// Give an item in your inventory to an NPC in the current room.
+#[allow(clippy::too_many_arguments)]
 pub fn handle_give_to_npc(
     out: &mut Output,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     world: &world::World,
     npc_locations: &HashMap<String, String>,
     current_room_id: &str,
     item_name: &str,
     npc_name: &str,
     flags: &mut HashSet<String>,
+    turn_index: u64,
+    cooldowns: &mut ActionCooldowns,
 ) -> bool {
     use world::ItemLocation;
 
@@ -466,89 +828,210 @@ pub fn handle_give_to_npc(
     let npc_query = npc_name.trim().to_lowercase();
 
     if item_query.is_empty() && npc_query.is_empty() {
-        out.say("Give what to whom?");
+        out.say(&world.messages.give_what_to_whom);
         return true;
     }
     if item_query.is_empty() {
-        out.say("Give what?");
+        out.say(&world.messages.give_what);
         return true;
     }
     if npc_query.is_empty() {
-        out.say("Give it to whom?");
+        out.say(&world.messages.give_to_whom);
         return true;
     }
 
-    let npc_match =
-        find_npc_by_words_scored(world, npc_locations, flags, current_room_id, &npc_query);
+    let npc_match = find_npc_by_words_scored(
+        world,
+        npc_locations,
+        &EvalContext::new(flags, turn_index, item_locations, item_liquids),
+        current_room_id,
+        &npc_query,
+    );
 
     let npc = match npc_match {
         NpcMatch::None => {
-            out.say("You don't see anyone like that here.");
+            out.say(&world.messages.no_npc);
             return true;
         }
-        NpcMatch::Many(_) => {
-            out.say("Be more specific.");
+        NpcMatch::Many(candidates) => {
+            ask_which_npc(out, world, &npc_query, &candidates);
             return true;
         }
         NpcMatch::One(n) => n,
     };
 
+    // "give all to guard": each portable carried item in turn
+    if item_query == "all" {
+        let carried: Vec<&world::Item> = item_locations
+            .items_at(world, &ItemLocation::Inventory)
+            .into_iter()
+            .filter(|item| item.portable)
+            .collect();
+        if carried.is_empty() {
+            out.say(&world.messages.nothing_to_give);
+        }
+        for item in carried {
+            give_item_to_npc(
+                out,
+                item_locations,
+                item_liquids,
+                world,
+                npc,
+                item,
+                flags,
+                turn_index,
+                cooldowns,
+            );
+        }
+        return true;
+    }
+
     let item_result =
-        find_item_ignore_conditions(world, item_locations, flags, &item_query, |_item, loc| {
+        find_item_ignore_conditions(world, item_locations, &item_query, |_item, loc| {
             matches!(loc, ItemLocation::Inventory)
         });
 
     let item = match item_result {
         ItemMatch::None => {
-            out.say("You aren't carrying that.");
+            out.say(&world.messages.not_carrying);
             return true;
         }
-        ItemMatch::Many(_) => {
-            out.say("Be more specific.");
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &item_query, &candidates);
             return true;
         }
         ItemMatch::One(i) => i,
     };
 
+    give_item_to_npc(
+        out,
+        item_locations,
+        item_liquids,
+        world,
+        npc,
+        item,
+        flags,
+        turn_index,
+        cooldowns,
+    );
+    true
+}
+
+/// Hand one carried item to an NPC: a matching NPC action, its want-list, or else
+/// the NPC simply holds it.
+#[allow(clippy::too_many_arguments)]
+fn give_item_to_npc(
+    out: &mut Output,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
+    world: &world::World,
+    npc: &world::Npc,
+    item: &world::Item,
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+    cooldowns: &mut ActionCooldowns,
+) {
+    use world::ItemLocation;
+
     if !item.portable {
-        out.say(format!("You can't give away the {}.", item.name));
-        return true;
+        out.say(fill_message(
+            &world.messages.cant_give,
+            &[("item", &item.name)],
+        ));
+        return;
     }
 
     // Try NPC-specific actions first (e.g., bribe) by looking for an action that requires this item.
+    // It fires like "bribe guard" would, cooldown included; while cooling down the item stays put.
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
     if let Some(action) = npc.actions.iter().find(|a| {
         a.requires_inventory.iter().any(|req| req == &item.id)
-            && conditions_met(&a.conditions, flags)
+            && conditions_met(&a.conditions, &ctx)
     }) {
-        let txt = action.response.trim();
-        if !txt.is_empty() {
-            out.say(txt);
+        if !fire_action_with_cooldown(
+            out,
+            world,
+            action,
+            format!("{}::{}", npc.id, action.id),
+            item_locations,
+            item_liquids,
+            flags,
+            turn_index,
+            cooldowns,
+        ) {
+            return;
         }
-        crate::engine::helpers::apply_effects(flags, &action.effects);
 
         // Consume the item by removing its location entry; prevents taking it back.
-        item_locations.remove(&item.id);
-        return true;
+        // An action with keep_items leaves it with the NPC instead.
+        if action.keep_items {
+            item_locations.insert(item.id.clone(), ItemLocation::Npc(npc.id.clone()));
+        } else {
+            item_locations.remove(&item.id);
+        }
+        return;
+    }
+
+    // An NPC with a want-list only accepts the items on it.
+    if !npc.wants.is_empty() {
+        let want = npc.wants.iter().find(|w| {
+            w.item == item.id
+                && conditions_met(
+                    &w.conditions,
+                    &EvalContext::new(flags, turn_index, item_locations, item_liquids),
+                )
+        });
+
+        let Some(want) = want else {
+            out.say(fill_message(
+                &world.messages.npc_refuses,
+                &[("item", &item.name), ("npc", &npc.name)],
+            ));
+            return;
+        };
+
+        if want.consumed {
+            item_locations.remove(&item.id);
+        } else {
+            item_locations.insert(item.id.clone(), ItemLocation::Npc(npc.id.clone()));
+        }
+
+        let txt = want.response.trim();
+        if txt.is_empty() {
+            out.say(fill_message(
+                &world.messages.give,
+                &[("item", &item.name), ("npc", &npc.name)],
+            ));
+        } else {
+            out.say(txt);
+        }
+        apply_world_effects(world, flags, &want.effects);
+        return;
     }
 
     // Default give: move item to NPC
     item_locations.insert(item.id.clone(), ItemLocation::Npc(npc.id.clone()));
-    out.say(format!("You give the {} to {}.", item.name, npc.name));
-    true
+    out.say(fill_message(
+        &world.messages.give,
+        &[("item", &item.name), ("npc", &npc.name)],
+    ));
 }
 
 /// Take an item from an NPC in the current room.
 /// Returns true if the command was handled (including error messages).
 /// Returns false if no matching NPC is in scope, allowing other handlers to try.
+#[allow(clippy::too_many_arguments)]
 pub fn handle_take_from_npc(
     out: &mut Output,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     world: &world::World,
     npc_locations: &HashMap<String, String>,
     current_room_id: &str,
     item_name: &str,
     npc_name: &str,
     flags: &HashSet<String>,
+    turn_index: u64,
 ) -> bool {
     use world::ItemLocation;
 
@@ -556,106 +1039,109 @@ pub fn handle_take_from_npc(
     let npc_query = npc_name.trim().to_lowercase();
 
     if item_query.is_empty() && npc_query.is_empty() {
-        out.say("Take what from whom?");
+        out.say(&world.messages.take_what_from_whom);
         return true;
     }
     if item_query.is_empty() {
-        out.say("Take what?");
+        out.say(&world.messages.take_what);
         return true;
     }
     if npc_query.is_empty() {
-        out.say("Take it from whom?");
+        out.say(&world.messages.take_from_whom);
         return true;
     }
 
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
     let npc_match =
-        find_npc_by_words_scored(world, npc_locations, flags, current_room_id, &npc_query);
+        find_npc_by_words_scored(world, npc_locations, &ctx, current_room_id, &npc_query);
 
     let npc = match npc_match {
         NpcMatch::None => return false, // let other handlers try (e.g., containers)
-        NpcMatch::Many(_) => {
-            out.say("Be more specific.");
+        NpcMatch::Many(candidates) => {
+            ask_which_npc(out, world, &npc_query, &candidates);
             return true;
         }
         NpcMatch::One(n) => n,
     };
 
-    let item_result = find_item(
-        world,
-        item_locations,
-        flags,
-        &item_query,
-        |_item, loc| match loc {
-            ItemLocation::Npc(holder_id) => holder_id == &npc.id,
-            _ => false,
-        },
-    );
+    let item_result = find_item(world, &ctx, &item_query, |_item, loc| match loc {
+        ItemLocation::Npc(holder_id) => holder_id == &npc.id,
+        _ => false,
+    });
 
     let item = match item_result {
         ItemMatch::None => {
-            out.say(format!("{} doesn't have that.", npc.name));
+            out.say(fill_message(
+                &world.messages.npc_lacks,
+                &[("npc", &npc.name)],
+            ));
             return true;
         }
-        ItemMatch::Many(_) => {
-            out.say("Be more specific.");
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &item_query, &candidates);
             return true;
         }
         ItemMatch::One(i) => i,
     };
 
     if !item.portable {
-        out.say(format!("You can't take the {}.", item.name));
+        out.say(fill_message(
+            &world.messages.cant_take,
+            &[("item", &item.name)],
+        ));
         return true;
     }
 
     item_locations.insert(item.id.clone(), ItemLocation::Inventory);
-    out.say(format!("You take the {} from {}.", item.name, npc.name));
+    out.say(fill_message(
+        &world.messages.take_from_npc,
+        &[("item", &item.name), ("npc", &npc.name)],
+    ));
     true
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_take_all_from_container(
     out: &mut Output,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     world: &world::World,
     current_room_id: &str,
     container_name: &str,
-    flags: &HashSet<String>,
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+    cooldowns: &mut ActionCooldowns,
 ) {
     use world::{ItemKind, ItemLocation};
 
     let container_query = container_name.trim().to_lowercase();
     if container_query.is_empty() {
-        out.say("Take all from where?");
+        out.say(&world.messages.take_all_from_where);
         return;
     }
 
-    let container_match = find_item(
-        world,
-        item_locations,
-        flags,
-        &container_query,
-        |candidate, loc| {
-            let in_scope = match loc {
-                ItemLocation::Room(room_id) => room_id == current_room_id,
-                ItemLocation::Inventory => true,
-                _ => false,
-            };
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+    let container_match = find_item(world, &ctx, &container_query, |candidate, loc| {
+        let in_scope = match loc {
+            ItemLocation::Room(room_id) => room_id == current_room_id,
+            ItemLocation::Inventory => true,
+            _ => false,
+        };
 
-            if !in_scope {
-                return false;
-            }
+        if !in_scope {
+            return false;
+        }
 
-            matches!(candidate.kind, ItemKind::Container(_))
-        },
-    );
+        matches!(candidate.kind, ItemKind::Container(_))
+    });
 
     let container = match container_match {
         ItemMatch::None => {
-            out.say("You don't see any container like that here.");
+            out.say(&world.messages.no_container);
             return;
         }
-        ItemMatch::Many(_) => {
-            out.say("Be more specific about which container.");
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &container_query, &candidates);
             return;
         }
         ItemMatch::One(c) => c,
@@ -666,33 +1152,38 @@ pub fn handle_take_all_from_container(
         _ => unreachable!(),
     };
 
-    if !props.conditions.is_empty() && !conditions_met(&props.conditions, flags) {
+    if !props.conditions.is_empty()
+        && !conditions_met(&props.conditions, &ctx)
+        && !try_auto_open(
+            out,
+            world,
+            item_locations,
+            item_liquids,
+            current_room_id,
+            flags,
+            turn_index,
+            &props.conditions,
+            cooldowns,
+        )
+    {
         out.say(props.closed_text.trim());
         return;
     }
 
-    let mut to_take: Vec<String> = Vec::new();
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
 
-    for item in world.items.values() {
-        let loc = match item_locations.get(&item.id) {
-            Some(l) => l,
-            None => continue,
-        };
-
-        if let ItemLocation::Item(parent_id) = loc {
-            if parent_id == &container.id
-                && conditions_met(&item.conditions, flags)
-                && item.portable
-            {
-                to_take.push(item.id.clone());
-            }
-        }
-    }
+    let to_take: Vec<String> = ctx
+        .item_locations
+        .items_at(world, &ItemLocation::Item(container.id.clone()))
+        .into_iter()
+        .filter(|item| conditions_met(&item.conditions, &ctx) && item.portable)
+        .map(|item| item.id.clone())
+        .collect();
 
     if to_take.is_empty() {
-        out.say(format!(
-            "There is nothing in the {} you can take.",
-            container.name
+        out.say(fill_message(
+            &world.messages.nothing_inside,
+            &[("container", &container.name)],
         ));
         return;
     }
@@ -700,22 +1191,25 @@ pub fn handle_take_all_from_container(
     for item_id in &to_take {
         if let Some(item) = world.items.get(item_id) {
             item_locations.insert(item_id.clone(), ItemLocation::Inventory);
-            out.say(format!(
-                "You take the {} from the {}.",
-                item.name, container.name
+            out.say(fill_message(
+                &world.messages.take_from,
+                &[("item", &item.name), ("container", &container.name)],
             ));
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn try_handle_container_store(
     out: &mut Output,
     verb: &str,
     rest: &str,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     world: &world::World,
     current_room_id: &str,
     flags: &mut HashSet<String>,
+    turn_index: u64,
 ) -> bool {
     use world::{ItemKind, ItemLocation};
 
@@ -726,9 +1220,17 @@ pub fn try_handle_container_store(
 
     // 1) Is there ANY visible container in scope that supports this verb?
     let mut any_container_supports = false;
-
+    let mut preps: Vec<String> = vec![
+        "in".to_string(),
+        "into".to_string(),
+        "inside".to_string(),
+        "on".to_string(),
+        "onto".to_string(),
+    ];
+
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
     for c in world.items.values() {
-        let loc = match item_locations.get(&c.id) {
+        let loc = match ctx.item_locations.get(&c.id) {
             Some(l) => l,
             None => continue,
         };
@@ -744,7 +1246,7 @@ pub fn try_handle_container_store(
         }
 
         // Container itself must be visible
-        if !conditions_met(&c.conditions, flags) {
+        if !conditions_met(&c.conditions, &ctx) {
             continue;
         }
 
@@ -753,9 +1255,12 @@ pub fn try_handle_container_store(
             _ => continue,
         };
 
-        if props.verbs.iter().any(|v| v.eq_ignore_ascii_case(&verb_l)) {
+        if props.verbs.iter().any(|v| eq_ignore_case(v, &verb_l)) {
             any_container_supports = true;
-            break;
+            let prep = props.prep.trim().to_lowercase();
+            if !prep.is_empty() && !preps.contains(&prep) {
+                preps.push(prep);
+            }
         }
     }
 
@@ -765,121 +1270,588 @@ pub fn try_handle_container_store(
 
     let query = rest.trim().to_lowercase();
     if query.is_empty() {
-        out.say(format!("What do you want to {}?", verb_l));
+        out.say(fill_message(
+            &world.messages.store_what,
+            &[("verb", &verb_l)],
+        ));
+        return true;
+    }
+
+    // "coin in bag" => item "coin", container "bag". Without a preposition both
+    // lookups use the whole phrase. Splitting keeps a carried container from
+    // competing with the item being stored.
+    let (item_query, container_query) = split_on_prep(&query, &preps);
+
+    if item_query == "all" && container_query != item_query {
+        store_all(
+            out,
+            &verb_l,
+            container_query,
+            item_locations,
+            item_liquids,
+            world,
+            current_room_id,
+            flags,
+            turn_index,
+        );
         return true;
     }
 
     // 2) Find carried item mentioned in rest (ignore conditions for inventory matching)
-    let item_match = find_item_ignore_conditions(
-        world,
-        item_locations,
-        &HashSet::new(),
-        &query,
-        |_it, loc| matches!(loc, ItemLocation::Inventory),
-    );
+    let item_match = find_item_ignore_conditions(world, item_locations, item_query, |_it, loc| {
+        matches!(loc, ItemLocation::Inventory)
+    });
 
     let item = match item_match {
         ItemMatch::None => {
-            out.say("You aren't carrying anything like that.");
+            out.say(&world.messages.not_carrying_any);
             return true;
         }
-        ItemMatch::Many(_) => {
-            out.say(format!(
-                "Be more specific about what you want to {}.",
-                verb_l
-            ));
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, item_query, &candidates);
             return true;
         }
         ItemMatch::One(i) => i,
     };
 
     if !item.portable {
-        out.say(format!("You can't {} the {}.", verb_l, item.name));
+        out.say(fill_message(
+            &world.messages.cant_store,
+            &[("verb", &verb_l), ("item", &item.name)],
+        ));
         return true;
     }
 
     // 3) Find a container in scope that matches query and supports verb (must be visible)
-    let cont_match = find_item(world, item_locations, flags, &query, |candidate, loc| {
-        let in_scope = match loc {
-            ItemLocation::Room(room_id) => room_id == current_room_id,
-            ItemLocation::Inventory => true,
-            _ => false,
-        };
-
-        if !in_scope {
-            return false;
-        }
-
-        let props = match &candidate.kind {
-            ItemKind::Container(p) => p,
-            _ => return false,
-        };
-
-        props.verbs.iter().any(|v| v.eq_ignore_ascii_case(&verb_l))
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+    let cont_match = find_item(world, &ctx, container_query, |candidate, loc| {
+        accepts_store(candidate, loc, current_room_id, &verb_l)
     });
 
     let container = match cont_match {
         ItemMatch::None => {
-            out.say(format!(
-                "Where do you want to {} the {}?",
-                verb_l, item.name
-            ));
+            // List every accepting container in scope so the player knows the options
+            let mut candidates: Vec<&world::Item> = world
+                .items
+                .values()
+                .filter(|c| c.id != item.id && conditions_met(&c.conditions, &ctx))
+                .filter(|c| {
+                    item_locations
+                        .get(&c.id)
+                        .map(|loc| accepts_store(c, loc, current_room_id, &verb_l))
+                        .unwrap_or(false)
+                })
+                .collect();
+            candidates.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+
+            if candidates.is_empty() {
+                out.say(fill_message(
+                    &world.messages.store_where,
+                    &[("verb", &verb_l), ("item", &item.name)],
+                ));
+            } else {
+                out.say(fill_message(
+                    &world.messages.store_where_which,
+                    &[
+                        ("verb", &verb_l),
+                        ("item", &item.name),
+                        ("choices", &container_choices(&candidates)),
+                    ],
+                ));
+            }
             return true;
         }
-        ItemMatch::Many(_) => {
-            out.say(format!(
-                "Be more specific about where you want to {} it.",
-                verb_l
+        ItemMatch::Many(candidates) => {
+            out.say(fill_message(
+                &world.messages.store_where_which,
+                &[
+                    ("verb", &verb_l),
+                    ("item", &item.name),
+                    ("choices", &container_choices(&candidates)),
+                ],
             ));
             return true;
         }
         ItemMatch::One(c) => c,
     };
 
+    if container.id == item.id || is_inside(item_locations, &container.id, &item.id) {
+        out.say(fill_message(
+            &world.messages.store_in_itself,
+            &[("verb", &verb_l), ("item", &item.name)],
+        ));
+        return true;
+    }
+
     let props = match &container.kind {
         ItemKind::Container(p) => p,
         _ => unreachable!(),
     };
 
-    if !props.conditions.is_empty() && !conditions_met(&props.conditions, flags) {
-        out.say(format!("{}", props.closed_text.trim()));
+    if !props.conditions.is_empty() && !conditions_met(&props.conditions, &ctx) {
+        out.say(props.closed_text.trim().to_string());
+        return true;
+    }
+
+    if !conditions_met(&props.use_conditions, &ctx) {
+        out.say(use_blocked_message(world, container, props));
         return true;
     }
 
     // 5) Capacity
     if let Some(cap) = props.capacity {
-        let mut count = 0usize;
-        for loc in item_locations.values() {
-            if let ItemLocation::Item(parent_id) = loc {
-                if parent_id == &container.id {
-                    count += 1;
-                }
-            }
-        }
+        let count = item_locations
+            .ids_at(&ItemLocation::Item(container.id.clone()))
+            .count();
         if count >= cap {
-            out.say(format!("The {} is full.", container.name));
+            out.say(fill_message(
+                &world.messages.container_full,
+                &[("container", &container.name)],
+            ));
             return true;
         }
     }
 
     // 6) Move item into container
     item_locations.insert(item.id.clone(), ItemLocation::Item(container.id.clone()));
-
-    out.say(format!(
-        "You {} the {} {} the {}.",
-        verb_l, item.name, props.prep, container.name
+    out.say(fill_message(
+        &world.messages.store,
+        &[
+            ("verb", &verb_l),
+            ("item", &item.name),
+            ("prep", &props.prep),
+            ("container", &container.name),
+        ],
     ));
 
-    // 7) Completion check
-    check_container_completion(out, world, item_locations, flags, &container.id);
+    true
+}
+
+/// "put all in chest": every portable carried item, in file order, until the
+/// container is full.
+#[allow(clippy::too_many_arguments)]
+fn store_all(
+    out: &mut Output,
+    verb: &str,
+    container_query: &str,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
+    world: &world::World,
+    current_room_id: &str,
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+) {
+    use world::{ItemKind, ItemLocation};
+
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+    let cont_match = find_item(world, &ctx, container_query, |candidate, loc| {
+        accepts_store(candidate, loc, current_room_id, verb)
+    });
+
+    let container = match cont_match {
+        ItemMatch::None => {
+            out.say(fill_message(
+                &world.messages.store_all_where,
+                &[("verb", verb)],
+            ));
+            return;
+        }
+        ItemMatch::Many(candidates) => {
+            out.say(fill_message(
+                &world.messages.store_all_which,
+                &[("verb", verb), ("choices", &container_choices(&candidates))],
+            ));
+            return;
+        }
+        ItemMatch::One(c) => c,
+    };
+
+    let props = match &container.kind {
+        ItemKind::Container(p) => p,
+        _ => unreachable!(),
+    };
+
+    if !props.conditions.is_empty() && !conditions_met(&props.conditions, &ctx) {
+        out.say(props.closed_text.trim().to_string());
+        return;
+    }
+
+    if !conditions_met(&props.use_conditions, &ctx) {
+        out.say(use_blocked_message(world, container, props));
+        return;
+    }
+
+    let carried: Vec<&world::Item> = item_locations
+        .items_at(world, &ItemLocation::Inventory)
+        .into_iter()
+        .filter(|item| {
+            item.portable
+                && item.id != container.id
+                && !is_inside(item_locations, &container.id, &item.id)
+        })
+        .collect();
+
+    if carried.is_empty() {
+        out.say(fill_message(
+            &world.messages.nothing_to_store,
+            &[
+                ("verb", verb),
+                ("prep", &props.prep),
+                ("container", &container.name),
+            ],
+        ));
+        return;
+    }
+
+    let inside = ItemLocation::Item(container.id.clone());
+    for item in carried {
+        if let Some(cap) = props.capacity
+            && item_locations.ids_at(&inside).count() >= cap
+        {
+            out.say(fill_message(
+                &world.messages.container_full,
+                &[("container", &container.name)],
+            ));
+            break;
+        }
+
+        item_locations.insert(item.id.clone(), ItemLocation::Item(container.id.clone()));
+        out.say(fill_message(
+            &world.messages.store,
+            &[
+                ("verb", verb),
+                ("item", &item.name),
+                ("prep", &props.prep),
+                ("container", &container.name),
+            ],
+        ));
+    }
+}
+
+/// Shown when an open container won't take items yet (use_conditions unmet).
+fn use_blocked_message(
+    world: &world::World,
+    container: &world::Item,
+    props: &world::ContainerProps,
+) -> String {
+    match props.use_blocked_text.as_deref().map(str::trim) {
+        Some(t) if !t.is_empty() => t.to_string(),
+        _ => fill_message(
+            &world.messages.container_unusable,
+            &[("container", &container.name)],
+        ),
+    }
+}
+
+/// True if `candidate` is an in-scope container that accepts `verb`.
+fn accepts_store(
+    candidate: &world::Item,
+    loc: &world::ItemLocation,
+    current_room_id: &str,
+    verb: &str,
+) -> bool {
+    let in_scope = match loc {
+        world::ItemLocation::Room(room_id) => room_id == current_room_id,
+        world::ItemLocation::Inventory => true,
+        _ => false,
+    };
+
+    if !in_scope {
+        return false;
+    }
+
+    match &candidate.kind {
+        world::ItemKind::Container(p) => p.verbs.iter().any(|v| eq_ignore_case(v, verb)),
+        _ => false,
+    }
+}
+
+/// "examine north": describe an available exit matching the query by direction or verb.
+/// Returns false if no exit matches.
+fn examine_exit(
+    out: &mut Output,
+    world: &world::World,
+    current_room_id: &str,
+    query: &str,
+    ctx: &EvalContext,
+) -> bool {
+    let Some(room) = world.rooms.get(current_room_id) else {
+        return false;
+    };
+
+    let exit = room.exits.iter().find(|e| {
+        conditions_met(&e.conditions, ctx)
+            && (!e.secret || ctx.flags.contains(&exit_known_flag(&room.id, &e.direction)))
+            && (eq_ignore_case(&e.direction, query)
+                || e.verbs.iter().any(|v| eq_ignore_case(v.trim(), query)))
+    });
+
+    let Some(exit) = exit else {
+        return false;
+    };
+
+    match exit.description.as_deref().map(str::trim) {
+        Some(txt) if !txt.is_empty() => out.say(txt),
+        _ => out.say(format!("You see a way leading {}.", exit.direction)),
+    }
+    true
+}
+
+/// "examine me": the world's player description (plus matching states), then what's
+/// worn and carried, then health when the world tracks it.
+fn examine_self(
+    out: &mut Output,
+    world: &world::World,
+    ctx: &EvalContext,
+    worn_items: &HashSet<String>,
+    player_hp: Option<u64>,
+) {
+    let mut desc = world
+        .player_desc
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .unwrap_or("You look as well as can be expected.")
+        .to_string();
+
+    for state in &world.player_desc_states {
+        let txt = state.text.trim();
+        if !txt.is_empty() && conditions_met(&state.conditions, ctx) {
+            desc.push(' ');
+            desc.push_str(txt);
+        }
+    }
+    out.say(desc);
+
+    let (mut worn, mut carried): (Vec<&world::Item>, Vec<&world::Item>) = world
+        .items
+        .values()
+        .filter(|item| {
+            matches!(
+                ctx.item_locations.get(&item.id),
+                Some(world::ItemLocation::Inventory)
+            )
+        })
+        .partition(|item| worn_items.contains(&item.id));
+
+    if !worn.is_empty() {
+        worn.sort_by(|a, b| a.name.cmp(&b.name));
+        let names: Vec<&str> = worn.iter().map(|item| item.name.as_str()).collect();
+        out.say(fill_message(
+            &world.messages.wearing,
+            &[("items", &names.join(", "))],
+        ));
+    }
+
+    if !carried.is_empty() {
+        carried.sort_by(|a, b| a.name.cmp(&b.name));
+        let names: Vec<&str> = carried.iter().map(|item| item.name.as_str()).collect();
+        out.say(format!("You are carrying: {}.", names.join(", ")));
+    }
+
+    if let (Some(hp), Some(max)) = (player_hp, world.player_max_hp) {
+        out.say(fill_message(
+            &world.messages.health,
+            &[("hp", &hp.to_string()), ("max", &max.to_string())],
+        ));
+    }
+}
+
+/// "wear <item>" / "put on <item>" and "take off <item>" / "remove <item>" for
+/// wearable items the player carries. Returns false for anything else, so actions
+/// with these verbs still work.
+pub fn try_handle_wear(
+    out: &mut Output,
+    verb: &str,
+    rest: &str,
+    world: &world::World,
+    ctx: &EvalContext,
+    worn_items: &mut HashSet<String>,
+) -> bool {
+    let verb_l = verb.to_lowercase();
+    let (wear, query) = match verb_l.as_str() {
+        "wear" | "don" => (true, rest),
+        "put" => match rest.strip_prefix("on ") {
+            Some(q) => (true, q),
+            None => return false,
+        },
+        "remove" | "doff" => (false, rest),
+        "take" => match rest.strip_prefix("off ") {
+            Some(q) => (false, q),
+            None => return false,
+        },
+        _ => return false,
+    };
+    let query = query.trim();
+    if query.is_empty() {
+        return false;
+    }
+
+    // Taking off only looks at what's worn, so "remove" on anything else reaches actions
+    let item_match = find_item(world, ctx, query, |candidate, loc| {
+        candidate.wearable
+            && matches!(loc, world::ItemLocation::Inventory)
+            && (wear || worn_items.contains(&candidate.id))
+    });
+    let item = match item_match {
+        ItemMatch::None => return false,
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, query, &candidates);
+            return true;
+        }
+        ItemMatch::One(item) => item,
+    };
 
+    let message = if !wear {
+        worn_items.remove(&item.id);
+        &world.messages.take_off
+    } else if worn_items.insert(item.id.clone()) {
+        &world.messages.wear
+    } else {
+        &world.messages.already_wearing
+    };
+    out.say(fill_message(message, &[("item", &item.name)]));
     true
 }
 
-pub fn check_container_completion(
+/// Ask which of the tied items the player meant ("Which do you mean: the red key, the
+/// brass key?", or the world's `ambiguity_text`) and keep them as a choice the next
+/// command can settle ("the brass key", "2"). Same-named items get ordinals ("the first torch").
+fn ask_which(out: &mut Output, world: &world::World, query: &str, candidates: &[&world::Item]) {
+    let names: Vec<&str> = candidates.iter().map(|c| c.name.as_str()).collect();
+    ask_which_by_name(out, world, query, &names);
+}
+
+/// `ask_which` for anything with a display name, NPCs included.
+pub(crate) fn ask_which_by_name(
+    out: &mut Output,
+    world: &world::World,
+    query: &str,
+    names: &[&str],
+) {
+    const ORDINALS: [&str; 10] = [
+        "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+        "tenth",
+    ];
+
+    // What to type to get each candidate, e.g. "brass key" or "first torch"
+    let phrases: Vec<String> = names
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            let same_name = |o: &&&str| eq_ignore_case(o, name);
+            if names.iter().filter(same_name).count() < 2 {
+                return name.to_string();
+            }
+            // Position among the items sharing this name, which is what "first torch" selects
+            let nth = names[..idx].iter().filter(same_name).count();
+            match ORDINALS.get(nth) {
+                Some(ord) => format!("{} {}", ord, name),
+                None => format!("{} {}", name, nth + 1),
+            }
+        })
+        .collect();
+
+    let list = phrases
+        .iter()
+        .map(|p| format!("the {}", p))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    out.say(match world.ambiguity_text.as_deref().map(str::trim) {
+        Some(t) if !t.is_empty() => t.replace("{candidates}", &list),
+        _ => format!("Which do you mean: {}?", list),
+    });
+
+    out.choice = Some(Choice {
+        query: query.to_string(),
+        options: phrases.iter().map(|p| p.to_lowercase()).collect(),
+    });
+}
+
+/// "in the box", "in the box or on the shelf", "in the box, on the shelf, or in the bag"
+fn container_choices(candidates: &[&world::Item]) -> String {
+    let phrases: Vec<String> = candidates
+        .iter()
+        .map(|c| match &c.kind {
+            world::ItemKind::Container(p) => format!("{} the {}", p.prep, c.name),
+            _ => format!("the {}", c.name),
+        })
+        .collect();
+
+    match phrases.len() {
+        0 => String::new(),
+        1 => phrases[0].clone(),
+        2 => format!("{} or {}", phrases[0], phrases[1]),
+        n => format!("{}, or {}", phrases[..n - 1].join(", "), phrases[n - 1]),
+    }
+}
+
+/// Split "<item> <prep> <container>" on the first known preposition.
+/// Falls back to using the whole query for both halves.
+fn split_on_prep<'q>(query: &'q str, preps: &[String]) -> (&'q str, &'q str) {
+    let mut best: Option<(usize, usize)> = None;
+
+    for prep in preps {
+        let needle = format!(" {} ", prep);
+        if let Some(idx) = query.find(&needle)
+            && best.map(|(b, _)| idx < b).unwrap_or(true)
+        {
+            best = Some((idx, needle.len()));
+        }
+    }
+
+    match best {
+        Some((idx, len)) => {
+            let item_part = query[..idx].trim();
+            let container_part = query[idx + len..].trim();
+            if item_part.is_empty() || container_part.is_empty() {
+                (query, query)
+            } else {
+                (item_part, container_part)
+            }
+        }
+        None => (query, query),
+    }
+}
+
+/// True if `item_id` sits (directly or nested) inside `container_id`.
+fn is_inside(item_locations: &ItemLocations, item_id: &str, container_id: &str) -> bool {
+    let mut current = item_id;
+    // Bounded walk up the container chain (guards against accidental cycles)
+    for _ in 0..=item_locations.len() {
+        match item_locations.get(current) {
+            Some(world::ItemLocation::Item(parent_id)) => {
+                if parent_id == container_id {
+                    return true;
+                }
+                current = parent_id;
+            }
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Complete every container that now holds all of its complete_when items, however
+/// they got there (put, give, action transforms and spawns, decay). Runs once per step.
+pub fn evaluate_container_completion(
+    out: &mut Output,
+    world: &world::World,
+    item_locations: &ItemLocations,
+    flags: &mut HashSet<String>,
+) {
+    for container in world.items.values() {
+        check_container_completion(out, world, item_locations, flags, &container.id);
+    }
+}
+
+/// Set the container's complete_flag (and say its complete_text) once every item
+/// in its complete_when is inside it. Does nothing if it is already complete.
+fn check_container_completion(
     out: &mut Output,
     world: &world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
+    item_locations: &ItemLocations,
     flags: &mut HashSet<String>,
     container_id: &str,
 ) {
@@ -920,133 +1892,1629 @@ pub fn check_container_completion(
     if let Some(text) = &props.complete_text {
         let trimmed = text.trim();
         if !trimmed.is_empty() {
-            out.say(trimmed);
+            out.event(trimmed);
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_examine(
     out: &mut Output,
     world: &world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
     npc_locations: &HashMap<String, String>,
     current_room_id: &str,
     target_name: &str,
-    flags: &HashSet<String>,
+    ctx: &EvalContext,
+    worn_items: &HashSet<String>,
+    player_hp: Option<u64>,
 ) {
-    use world::{ItemKind, ItemLocation};
+    use world::ItemLocation;
 
-    let query = target_name.trim().to_lowercase();
+    let mut query = target_name.trim().to_lowercase();
     if query.is_empty() {
-        out.say("Examine what?");
+        out.say(&world.messages.examine_what);
+        return;
+    }
+
+    // "examine box deep" lists nested container contents too
+    let mut deep = world.deep_examine;
+    if let Some(stripped) = query.strip_suffix(" deep") {
+        query = stripped.trim().to_string();
+        deep = true;
+    }
+
+    if matches!(query.as_str(), "me" | "self" | "myself" | "yourself") {
+        examine_self(out, world, ctx, worn_items, player_hp);
         return;
     }
 
+    // "guard's sword", "sword of the guard": only what that NPC holds
+    let possessive = possessive_npc(world, npc_locations, current_room_id, &query, ctx);
+
     // Prefer NPC examine in-room
-    if try_handle_examine_npc(
-        out,
-        item_locations,
-        world,
-        npc_locations,
-        current_room_id,
-        &query,
-        flags,
-    ) {
+    if possessive.is_none()
+        && try_handle_examine_npc(out, world, npc_locations, current_room_id, &query, ctx)
+    {
+        return;
+    }
+
+    if let Some((npc, item_query)) = possessive {
+        let held = find_item_by_words_scored(
+            world,
+            ctx,
+            item_query,
+            |item, loc| {
+                matches!(loc, ItemLocation::Npc(holder) if *holder == npc.id)
+                    && conditions_met(&item.conditions, ctx)
+            },
+            false,
+            LocationPreference::Inventory,
+        );
+        match held {
+            ItemMatch::None => out.say(format!("The {} isn't holding that.", npc.name)),
+            ItemMatch::Many(candidates) => ask_which(out, world, item_query, &candidates),
+            ItemMatch::One(item) => say_examine_text(out, world, ctx, item, deep),
+        }
         return;
     }
 
-    // Prefer items in inventory (respect conditions)
-    let inv_match = find_item_ignore_conditions(
+    // Carried items (whatever their conditions), visible room items and items held by
+    // NPCs here; carried ones win ties.
+    let item_match = find_item_by_words_scored(
         world,
-        item_locations,
-        &HashSet::new(),
+        ctx,
         &query,
-        |_item, loc| matches!(loc, ItemLocation::Inventory),
+        |item, loc| match loc {
+            ItemLocation::Inventory => true,
+            ItemLocation::Room(room_id) => {
+                room_id == current_room_id && conditions_met(&item.conditions, ctx)
+            }
+            ItemLocation::Npc(holder_id) => {
+                // Only if NPC is in the room
+                npc_locations
+                    .get(holder_id)
+                    .map(|r| r == current_room_id)
+                    .unwrap_or(false)
+                    && conditions_met(&item.conditions, ctx)
+            }
+            _ => false,
+        },
+        false,
+        LocationPreference::Inventory,
     );
 
-    let item = match inv_match {
-        ItemMatch::Many(_) => {
-            out.say("Be more specific.");
+    let item = match item_match {
+        ItemMatch::None => {
+            if !examine_exit(out, world, current_room_id, &query, ctx) {
+                out.say("You see nothing like that here.");
+            }
+            return;
+        }
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &query, &candidates);
             return;
         }
-        ItemMatch::One(i) => Some(i),
-        ItemMatch::None => None,
+        ItemMatch::One(i) => i,
     };
 
-    // If not in inventory, look in the room
-    let item = match item {
-        Some(i) => i,
-        None => {
-            let room_match = find_item(
-                world,
-                item_locations,
-                flags,
-                &query,
-                |_item, loc| match loc {
-                    ItemLocation::Room(room_id) => room_id == current_room_id,
-                    ItemLocation::Npc(holder_id) => {
-                        // Only if NPC is in the room
-                        npc_locations
-                            .get(holder_id)
-                            .map(|r| r == current_room_id)
-                            .unwrap_or(false)
+    say_examine_text(out, world, ctx, item, deep);
+}
+
+/// Split "guard's sword" or "sword of the guard" into the NPC it names and the item
+/// words. None unless that part names exactly one NPC here, so items such as
+/// "captain's log" or "bottle of wine" are still found as usual.
+fn possessive_npc<'w, 'q>(
+    world: &'w world::World,
+    npc_locations: &HashMap<String, String>,
+    current_room_id: &str,
+    query: &'q str,
+    ctx: &EvalContext,
+) -> Option<(&'w world::Npc, &'q str)> {
+    let (npc_query, item_query) = match query.split_once("'s ") {
+        Some((npc, item)) => (npc, item),
+        None => {
+            let (item, npc) = query.rsplit_once(" of ")?;
+            (npc.trim().strip_prefix("the ").unwrap_or(npc), item)
+        }
+    };
+
+    let item_query = item_query.trim();
+    if item_query.is_empty() {
+        return None;
+    }
+
+    match find_npc_by_words_scored(world, npc_locations, ctx, current_room_id, npc_query) {
+        NpcMatch::One(npc) => Some((npc, item_query)),
+        _ => None,
+    }
+}
+
+fn say_examine_text(
+    out: &mut Output,
+    world: &world::World,
+    ctx: &EvalContext,
+    item: &world::Item,
+    deep: bool,
+) {
+    use world::ItemLocation;
+
+    if !can_examine_closely(out, ctx.item_locations, item) {
+        return;
+    }
+
+    let mut txt = item.examine_text.trim().to_string();
+    if txt.is_empty() {
+        txt = fill_message(&world.messages.nothing_special, &[("item", &item.name)]);
+        if !item.portable {
+            txt.push_str(" It's firmly in place.");
+        }
+    }
+    if item.inscription.is_some() {
+        txt.push_str(" Something is written on it.");
+    }
+    if let Some(ItemLocation::Npc(holder_id)) = ctx.item_locations.get(&item.id)
+        && let Some(holder) = world.npcs.get(holder_id)
+    {
+        txt.push_str(&format!(" (held by the {})", holder.name));
+    }
+    out.say(txt);
+
+    if item.holds_liquid {
+        say_liquid_contents(out, item, ctx.item_liquids);
+    }
+
+    say_container_contents(out, world, ctx, item, deep);
+}
+
+/// "read <item>": the inscription of a carried or visible item that has one. Returns
+/// false for anything else so room actions can still handle "read".
+pub fn try_handle_read(
+    out: &mut Output,
+    verb: &str,
+    rest: &str,
+    world: &world::World,
+    current_room_id: &str,
+    ctx: &EvalContext,
+) -> bool {
+    use world::ItemLocation;
+
+    if !verb.eq_ignore_ascii_case("read") {
+        return false;
+    }
+
+    let query = rest.trim().to_lowercase();
+    if query.is_empty() {
+        return false;
+    }
+
+    let item_match = find_item_by_words_scored(
+        world,
+        ctx,
+        &query,
+        |item, loc| {
+            item.inscription.is_some()
+                && match loc {
+                    ItemLocation::Inventory => true,
+                    ItemLocation::Room(room_id) => {
+                        room_id == current_room_id && conditions_met(&item.conditions, ctx)
                     }
                     _ => false,
-                },
-            );
-
-            match room_match {
-                ItemMatch::None => {
-                    out.say("You see nothing like that here.");
-                    return;
                 }
-                ItemMatch::Many(_) => {
-                    out.say("Be more specific.");
-                    return;
+        },
+        false,
+        LocationPreference::Inventory,
+    );
+
+    let item = match item_match {
+        ItemMatch::None => return false,
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &query, &candidates);
+            return true;
+        }
+        ItemMatch::One(i) => i,
+    };
+
+    if let Some(text) = &item.inscription {
+        out.say(text.trim());
+    }
+    true
+}
+
+/// "look in <item>": just what a container or liquid holder has inside, without its
+/// examine text. Returns false for anything else so room actions can still use it.
+pub fn try_handle_look_in(
+    out: &mut Output,
+    verb: &str,
+    rest: &str,
+    world: &world::World,
+    current_room_id: &str,
+    ctx: &EvalContext,
+) -> bool {
+    use world::{ItemKind, ItemLocation};
+
+    if !(verb.eq_ignore_ascii_case("look") || verb.eq_ignore_ascii_case("l")) {
+        return false;
+    }
+
+    let rest = rest.trim().to_lowercase();
+    let query = match ["in ", "inside ", "into "]
+        .iter()
+        .find_map(|prep| rest.strip_prefix(prep))
+    {
+        Some(q) if !q.trim().is_empty() => q.trim().to_string(),
+        _ => return false,
+    };
+
+    let item_match = find_item_by_words_scored(
+        world,
+        ctx,
+        &query,
+        |item, loc| {
+            let can_hold = item.holds_liquid || matches!(item.kind, ItemKind::Container(_));
+            can_hold
+                && match loc {
+                    ItemLocation::Inventory => true,
+                    ItemLocation::Room(room_id) => {
+                        room_id == current_room_id && conditions_met(&item.conditions, ctx)
+                    }
+                    _ => false,
                 }
-                ItemMatch::One(i) => i,
+        },
+        false,
+        LocationPreference::Inventory,
+    );
+
+    let item = match item_match {
+        ItemMatch::None => return false,
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &query, &candidates);
+            return true;
+        }
+        ItemMatch::One(i) => i,
+    };
+
+    if !can_examine_closely(out, ctx.item_locations, item) {
+        return true;
+    }
+
+    if item.holds_liquid {
+        say_liquid_contents(out, item, ctx.item_liquids);
+    }
+
+    say_container_contents(out, world, ctx, item, world.deep_examine);
+
+    true
+}
+
+/// Said for an item with examine_requires_inventory that isn't carried.
+const HOLD_TO_EXAMINE: &str = "You'd need to pick it up to look closer.";
+
+/// False (after saying so) if the item has examine_requires_inventory and isn't carried.
+fn can_examine_closely(
+    out: &mut Output,
+    item_locations: &ItemLocations,
+    item: &world::Item,
+) -> bool {
+    if item.examine_requires_inventory
+        && !matches!(
+            item_locations.get(&item.id),
+            Some(world::ItemLocation::Inventory)
+        )
+    {
+        out.say(HOLD_TO_EXAMINE);
+        return false;
+    }
+    true
+}
+
+fn say_liquid_contents(out: &mut Output, item: &world::Item, item_liquids: &ItemLiquids) {
+    match item_liquids.get(&item.id) {
+        Some(liquid) => out.say(format!("The {} contains {}.", item.name, liquid)),
+        None => out.say(format!("The {} is empty.", item.name)),
+    }
+}
+
+/// What a container holds ("Inside it you see: ..."), or its closed text.
+fn say_container_contents(
+    out: &mut Output,
+    world: &world::World,
+    ctx: &EvalContext,
+    item: &world::Item,
+    deep: bool,
+) {
+    use world::{ItemKind, ItemLocation};
+
+    let props = match &item.kind {
+        ItemKind::Container(props) => props,
+        _ => return,
+    };
+
+    if !props.conditions.is_empty() && !conditions_met(&props.conditions, ctx) {
+        out.say(props.closed_text.trim().to_string());
+        return;
+    }
+
+    if let Some(open_text) = &props.open_text {
+        out.say(open_text.trim());
+    }
+
+    let mut contents: Vec<&world::Item> = ctx
+        .item_locations
+        .items_at(world, &ItemLocation::Item(item.id.clone()))
+        .into_iter()
+        .filter(|other| conditions_met(&other.conditions, ctx))
+        .collect();
+
+    if contents.is_empty() {
+        out.say("It is currently empty.");
+    } else {
+        contents.sort_by(|a, b| a.name.cmp(&b.name));
+        let list = contents
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        out.say(format!("Inside it you see: {}.", list));
+
+        if deep {
+            for inner in contents {
+                say_nested_contents(out, world, ctx, inner, 1);
             }
         }
+    }
+}
+
+/// Deep examine: list what's inside `container` (if it is one), indented by depth,
+/// then recurse. Closed containers say so without revealing their contents.
+fn say_nested_contents(
+    out: &mut Output,
+    world: &world::World,
+    ctx: &EvalContext,
+    container: &world::Item,
+    depth: usize,
+) {
+    use world::{ItemKind, ItemLocation};
+
+    // Guard against runaway nesting
+    if depth > 8 {
+        return;
+    }
+
+    let props = match &container.kind {
+        ItemKind::Container(p) => p,
+        _ => return,
     };
 
-    let txt = item.examine_text.trim();
-    if txt.is_empty() {
-        out.say(format!("You see nothing special about the {}.", item.name));
+    let indent = "  ".repeat(depth);
+
+    if !conditions_met(&props.conditions, ctx) {
+        out.say(format!("{}The {} is closed.", indent, container.name));
+        return;
+    }
+
+    let mut contents: Vec<&world::Item> = world
+        .items
+        .values()
+        .filter(|other| {
+            matches!(
+                ctx.item_locations.get(&other.id),
+                Some(ItemLocation::Item(parent_id)) if parent_id == &container.id
+            ) && conditions_met(&other.conditions, ctx)
+        })
+        .collect();
+
+    if contents.is_empty() {
+        out.say(format!("{}The {} is empty.", indent, container.name));
+        return;
+    }
+
+    contents.sort_by(|a, b| a.name.cmp(&b.name));
+    let list = contents
+        .iter()
+        .map(|i| i.name.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+    out.say(format!(
+        "{}{} the {}: {}.",
+        indent,
+        capitalize(&props.prep),
+        container.name,
+        list
+    ));
+
+    for inner in contents {
+        say_nested_contents(out, world, ctx, inner, depth + 1);
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Liquid verbs for items with `holds_liquid`:
+/// - "fill <item>", "fill <item> from <source>"
+/// - "empty <item>", "pour <item>", "pour out <item>"
+/// - "drink <item>", "drink from <item>" (also works on a liquid source)
+///
+/// Returns false when nothing liquid-related matches, so room/global actions
+/// still get a chance at the same verb.
+#[allow(clippy::too_many_arguments)]
+pub fn try_handle_liquid(
+    out: &mut Output,
+    verb: &str,
+    rest: &str,
+    world: &world::World,
+    item_locations: &ItemLocations,
+    item_liquids: &mut ItemLiquids,
+    changed_liquids: &mut HashSet<String>,
+    current_room_id: &str,
+    flags: &HashSet<String>,
+    turn_index: u64,
+) -> bool {
+    use world::ItemLocation;
+
+    let verb_l = verb.to_lowercase();
+    if !matches!(verb_l.as_str(), "fill" | "empty" | "pour" | "drink") {
+        return false;
+    }
+
+    let mut query = rest.trim().to_lowercase();
+    for lead in ["out ", "from "] {
+        if let Some(stripped) = query.strip_prefix(lead) {
+            query = stripped.trim().to_string();
+        }
+    }
+
+    // "fill bottle from fountain"
+    let (holder_query, source_query) = if verb_l == "fill" {
+        match [" from ", " at ", " with "]
+            .iter()
+            .find_map(|sep| query.find(sep).map(|idx| (idx, sep.len())))
+        {
+            Some((idx, len)) => (
+                query[..idx].trim().to_string(),
+                Some(query[idx + len..].trim().to_string()),
+            ),
+            None => (query.clone(), None),
+        }
     } else {
-        out.say(txt);
+        (query.clone(), None)
+    };
+
+    if holder_query.is_empty() {
+        return false;
     }
 
-    if let ItemKind::Container(props) = &item.kind {
-        if !props.conditions.is_empty() && !conditions_met(&props.conditions, flags) {
-            out.say(format!("{}", props.closed_text.trim()));
-            return;
+    let in_reach = |loc: &ItemLocation| match loc {
+        ItemLocation::Inventory => true,
+        ItemLocation::Room(room_id) => room_id == current_room_id,
+        _ => false,
+    };
+
+    let holder = match find_item_by_words_scored(
+        world,
+        &EvalContext::new(flags, turn_index, item_locations, item_liquids),
+        &holder_query,
+        |it, loc| it.holds_liquid && in_reach(loc),
+        true,
+        LocationPreference::Inventory,
+    ) {
+        ItemMatch::One(i) => i,
+        ItemMatch::Many(candidates) => {
+            ask_which(out, world, &holder_query, &candidates);
+            return true;
         }
+        ItemMatch::None => {
+            // "drink from fountain" drinks straight from a source
+            if verb_l == "drink"
+                && let ItemMatch::One(source) = find_item(
+                    world,
+                    &EvalContext::new(flags, turn_index, item_locations, item_liquids),
+                    &holder_query,
+                    |it, loc| it.liquid_source.is_some() && in_reach(loc),
+                )
+                && let Some(liquid) = &source.liquid_source
+            {
+                out.say(format!(
+                    "You drink some {} from the {}.",
+                    liquid, source.name
+                ));
+                return true;
+            }
+            return false;
+        }
+    };
 
-        let mut contents: Vec<&world::Item> = Vec::new();
+    match verb_l.as_str() {
+        "fill" => {
+            if let Some(current) = item_liquids.get(&holder.id) {
+                out.say(format!("The {} already contains {}.", holder.name, current));
+                return true;
+            }
 
-        for other in world.items.values() {
-            let loc = match item_locations.get(&other.id) {
-                Some(l) => l,
-                None => continue,
+            let room_liquid = world
+                .rooms
+                .get(current_room_id)
+                .and_then(|r| r.liquid_source.as_deref());
+
+            let (liquid, from_name) = match &source_query {
+                Some(sq) => {
+                    match find_item(
+                        world,
+                        &EvalContext::new(flags, turn_index, item_locations, item_liquids),
+                        sq,
+                        |it, loc| it.liquid_source.is_some() && in_reach(loc),
+                    ) {
+                        ItemMatch::One(src) => (
+                            src.liquid_source.clone().unwrap_or_default(),
+                            Some(src.name.clone()),
+                        ),
+                        ItemMatch::Many(candidates) => {
+                            ask_which(out, world, sq, &candidates);
+                            return true;
+                        }
+                        ItemMatch::None => match room_liquid {
+                            Some(l) if sq.split_whitespace().any(|w| w == l) => {
+                                (l.to_string(), None)
+                            }
+                            _ => {
+                                out.say(format!("You can't fill the {} from that.", holder.name));
+                                return true;
+                            }
+                        },
+                    }
+                }
+                None => {
+                    let mut sources: Vec<&world::Item> = world
+                        .items
+                        .values()
+                        .filter(|it| {
+                            it.liquid_source.is_some()
+                                && conditions_met(
+                                    &it.conditions,
+                                    &EvalContext::new(
+                                        flags,
+                                        turn_index,
+                                        item_locations,
+                                        item_liquids,
+                                    ),
+                                )
+                                && item_locations.get(&it.id).map(in_reach).unwrap_or(false)
+                        })
+                        .collect();
+                    sources.sort_by(|a, b| a.name.cmp(&b.name));
+
+                    match (sources.len(), room_liquid) {
+                        (0, Some(l)) => (l.to_string(), None),
+                        (1, None) => (
+                            sources[0].liquid_source.clone().unwrap_or_default(),
+                            Some(sources[0].name.clone()),
+                        ),
+                        (0, None) => {
+                            out.say(format!(
+                                "There's nothing here to fill the {} from.",
+                                holder.name
+                            ));
+                            return true;
+                        }
+                        _ => {
+                            out.say(format!("Fill the {} from what?", holder.name));
+                            return true;
+                        }
+                    }
+                }
             };
 
-            if let ItemLocation::Item(parent_id) = loc {
-                if parent_id == &item.id && conditions_met(&other.conditions, flags) {
-                    contents.push(other);
-                }
+            item_liquids.insert(holder.id.clone(), liquid.clone());
+            changed_liquids.insert(holder.id.clone());
+            match from_name {
+                Some(src) => out.say(format!(
+                    "You fill the {} with {} from the {}.",
+                    holder.name, liquid, src
+                )),
+                None => out.say(format!("You fill the {} with {}.", holder.name, liquid)),
             }
         }
+        "drink" => match item_liquids.remove(&holder.id) {
+            Some(liquid) => {
+                changed_liquids.insert(holder.id.clone());
+                out.say(format!("You drink the {}.", liquid));
+            }
+            None => out.say(format!("The {} is empty.", holder.name)),
+        },
+        _ => match item_liquids.remove(&holder.id) {
+            Some(liquid) => {
+                changed_liquids.insert(holder.id.clone());
+                out.say(format!(
+                    "You pour the {} out of the {}.",
+                    liquid, holder.name
+                ));
+            }
+            None => out.say(format!("The {} is already empty.", holder.name)),
+        },
+    }
 
-        if contents.is_empty() {
-            out.say("It is currently empty.");
-        } else {
-            contents.sort_by(|a, b| a.name.cmp(&b.name));
-            let list = contents
-                .iter()
-                .map(|i| i.name.as_str())
-                .collect::<Vec<&str>>()
-                .join(", ");
-            out.say(format!("Inside it you see: {}.", list));
+    true
+}
+
+/// Decay perishable items whose age (in turns since they appeared) has reached
+/// their `decays_after` limit. A decaying item is replaced in place by its
+/// `decays_into` item, or removed from the world if it has none. The decay text
+/// is shown only when the player can see it happen (carried or in this room).
+pub fn evaluate_item_decay(
+    out: &mut Output,
+    world: &world::World,
+    item_locations: &mut ItemLocations,
+    item_spawn_turns: &mut HashMap<String, u64>,
+    current_room_id: &str,
+    turn_index: u64,
+) {
+    // Perishables placed by other means (e.g. action transforms or spawns) start
+    // aging now; ones taken out of play forget their age
+    item_spawn_turns.retain(|id, _| item_locations.contains_key(id));
+    for item in world.items.values() {
+        if item.decays_after.is_some() && item_locations.contains_key(&item.id) {
+            item_spawn_turns
+                .entry(item.id.clone())
+                .or_insert(turn_index);
+        }
+    }
+
+    let mut due: Vec<&world::Item> = world
+        .items
+        .values()
+        .filter(|item| {
+            let limit = match item.decays_after {
+                Some(n) => n,
+                None => return false,
+            };
+            if !item_locations.contains_key(&item.id) {
+                return false;
+            }
+            let born = item_spawn_turns
+                .get(&item.id)
+                .copied()
+                .unwrap_or(turn_index);
+            turn_index.saturating_sub(born) >= limit
+        })
+        .collect();
+
+    due.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for item in due {
+        let loc = match item_locations.remove(&item.id) {
+            Some(l) => l,
+            None => continue,
+        };
+        item_spawn_turns.remove(&item.id);
+
+        let noticed = location_near_player(&loc, item_locations, current_room_id);
+
+        if let Some(into) = &item.decays_into {
+            item_locations.insert(into.clone(), loc);
+            item_spawn_turns.insert(into.clone(), turn_index);
+        }
+
+        if noticed && let Some(text) = &item.decay_text {
+            out.event(text.trim());
+        }
+    }
+}
+
+/// True if something at `loc` is carried by the player or lies in the current room,
+/// directly or inside a container that is.
+fn location_near_player(
+    loc: &world::ItemLocation,
+    item_locations: &ItemLocations,
+    current_room_id: &str,
+) -> bool {
+    use world::ItemLocation;
+
+    let mut loc = loc;
+    // Bounded walk up the container chain (guards against accidental cycles)
+    for _ in 0..=item_locations.len() {
+        match loc {
+            ItemLocation::Inventory => return true,
+            ItemLocation::Room(room_id) => return room_id == current_room_id,
+            ItemLocation::Npc(_) => return false,
+            ItemLocation::Item(parent_id) => match item_locations.get(parent_id) {
+                Some(parent_loc) => loc = parent_loc,
+                None => return false,
+            },
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{game, hall, hall_with, run};
+    use crate::world::ItemLocation;
+
+    #[test]
+    fn an_ordinal_picks_between_same_named_items() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "torch_a"
+            name = "torch"
+            examine_text = "A short torch."
+            start_location = "room:hall"
+
+            [[item]]
+            id = "torch_b"
+            name = "torch"
+            examine_text = "A long torch."
+            start_location = "room:hall"
+            "#,
+        );
+
+        assert!(run(&mut game, "examine second torch").contains("A long torch."));
+        assert!(run(&mut game, "examine torch 1").contains("A short torch."));
+        run(&mut game, "take 2nd torch");
+        assert_eq!(
+            game.item_locations.get("torch_b"),
+            Some(&ItemLocation::Inventory)
+        );
+        assert_eq!(
+            game.item_locations.get("torch_a"),
+            Some(&ItemLocation::Room("hall".into()))
+        );
+    }
+
+    #[test]
+    fn ties_list_the_candidates() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "brass_key"
+            name = "brass key"
+            start_location = "room:hall"
+
+            [[item]]
+            id = "iron_key"
+            name = "iron key"
+            start_location = "room:hall"
+            "#,
+        );
+
+        let expected = "Which do you mean: the brass key, the iron key?";
+        assert_eq!(run(&mut game, "take key"), expected);
+        assert_eq!(run(&mut game, "examine key"), expected);
+    }
+
+    #[test]
+    fn an_answer_to_which_picks_by_number_or_name() {
+        let keys = r#"
+            [[item]]
+            id = "brass_key"
+            name = "brass key"
+            start_location = "room:hall"
+
+            [[item]]
+            id = "iron_key"
+            name = "iron key"
+            start_location = "room:hall"
+        "#;
+
+        let mut game = hall(keys);
+        run(&mut game, "take key");
+        run(&mut game, "2");
+        assert_eq!(
+            game.item_locations.get("iron_key"),
+            Some(&ItemLocation::Inventory)
+        );
+
+        let mut game = hall(keys);
+        run(&mut game, "take key");
+        run(&mut game, "brass");
+        assert_eq!(
+            game.item_locations.get("brass_key"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+
+    #[test]
+    fn ties_break_by_where_the_item_is_per_verb() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "my_coin"
+            name = "coin"
+            examine_text = "Your lucky coin."
+            start_location = "inventory"
+
+            [[item]]
+            id = "floor_coin"
+            name = "coin"
+            examine_text = "A dusty coin."
+            start_location = "room:hall"
+            "#,
+        );
+
+        assert!(run(&mut game, "examine coin").contains("Your lucky coin."));
+        run(&mut game, "take coin");
+        assert_eq!(
+            game.item_locations.get("floor_coin"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+
+    #[test]
+    fn accented_names_match_lowercased_input() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "sword"
+            name = "ÉPÉE"
+            start_location = "room:hall"
+            "#,
+        );
+
+        run(&mut game, "take épée");
+        assert_eq!(
+            game.item_locations.get("sword"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+
+    #[test]
+    fn take_all_reaches_into_open_containers_only() {
+        let mut game = hall_with(
+            "take_all_includes_containers = true",
+            r#"
+            [[item]]
+            id = "torch"
+            name = "torch"
+            start_location = "room:hall"
+
+            [[item]]
+            id = "table"
+            name = "table"
+            start_location = "room:hall"
+            portable = false
+            kind = "container"
+            prep = "on"
+
+            [[item]]
+            id = "cup"
+            name = "cup"
+            start_location = "item:table"
+
+            [[item]]
+            id = "chest"
+            name = "chest"
+            start_location = "room:hall"
+            portable = false
+            kind = "container"
+            container_conditions = ["chest_open"]
+
+            [[item]]
+            id = "gold"
+            name = "gold"
+            start_location = "item:chest"
+            "#,
+        );
+
+        let said = run(&mut game, "take all");
+        assert!(said.contains("torch"));
+        assert!(said.contains("cup"));
+        assert!(!said.contains("gold"));
+        assert_eq!(
+            game.item_locations.get("cup"),
+            Some(&ItemLocation::Inventory)
+        );
+        assert_eq!(
+            game.item_locations.get("gold"),
+            Some(&ItemLocation::Item("chest".into()))
+        );
+    }
+
+    #[test]
+    fn decay_into_a_container_completes_it() {
+        let mut game = game(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "cellar"
+
+            [[room]]
+            id = "cellar"
+            name = "Cellar"
+            desc = "A cellar."
+
+            [[item]]
+            id = "jar"
+            name = "jar"
+            start_location = "room:cellar"
+            portable = false
+            kind = "container"
+            complete_when = ["vinegar"]
+            complete_flag = "soured"
+            complete_text = "The jar holds proper vinegar now."
+
+            [[item]]
+            id = "wine"
+            name = "wine"
+            start_location = "item:jar"
+            decays_after = 1
+            decays_into = "vinegar"
+
+            [[item]]
+            id = "vinegar"
+            name = "vinegar"
+            start_location = "nowhere"
+            "#,
+        );
+
+        let mut said = String::new();
+        for _ in 0..3 {
+            said.push_str(&run(&mut game, "wait"));
+        }
+        assert!(said.contains("The jar holds proper vinegar now."));
+        assert!(game.flags.contains("soured"));
+    }
+
+    #[test]
+    fn food_rots_after_its_turns() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "bread"
+            name = "bread"
+            start_location = "inventory"
+            decays_after = 2
+            decays_into = "mouldy_bread"
+            decay_text = "The bread has gone mouldy."
+
+            [[item]]
+            id = "mouldy_bread"
+            name = "spoiled bread"
+            start_location = "nowhere"
+            "#,
+        );
+
+        assert!(!run(&mut game, "wait").contains("mouldy"));
+        assert!(run(&mut game, "wait").contains("The bread has gone mouldy."));
+        assert_eq!(game.item_locations.get("bread"), None);
+        assert_eq!(
+            game.item_locations.get("mouldy_bread"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+
+    const BAG: &str = r#"
+        [[item]]
+        id = "bag"
+        name = "bag"
+        start_location = "room:hall"
+        kind = "container"
+        capacity = 1
+
+        [[item]]
+        id = "coin"
+        name = "coin"
+        start_location = "item:bag"
+
+        [[item]]
+        id = "pebble"
+        name = "pebble"
+        start_location = "inventory"
+    "#;
+
+    #[test]
+    fn contents_travel_with_a_carried_container() {
+        let mut game = game(&format!(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = "A hall."
+
+            [[room.exit]]
+            direction = "north"
+            target = "yard"
+
+            [[room]]
+            id = "yard"
+            name = "Yard"
+            desc = "A yard."
+
+            {}
+            "#,
+            BAG
+        ));
+
+        run(&mut game, "take bag");
+        run(&mut game, "north");
+        assert!(run(&mut game, "inventory").contains("coin"));
+        run(&mut game, "drop bag");
+        assert_eq!(
+            game.item_locations.get("bag"),
+            Some(&ItemLocation::Room("yard".into()))
+        );
+        assert_eq!(
+            game.item_locations.get("coin"),
+            Some(&ItemLocation::Item("bag".into()))
+        );
+        run(&mut game, "take coin from bag");
+        assert_eq!(
+            game.item_locations.get("coin"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+
+    #[test]
+    fn a_carried_container_keeps_its_capacity() {
+        let mut game = hall(BAG);
+        run(&mut game, "take bag");
+        assert!(run(&mut game, "put pebble in bag").contains("full"));
+        assert_eq!(
+            game.item_locations.get("pebble"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+
+    const NESTED: &str = r#"
+        [[item]]
+        id = "box"
+        name = "box"
+        start_location = "room:hall"
+        kind = "container"
+
+        [[item]]
+        id = "pouch"
+        name = "pouch"
+        start_location = "item:box"
+        kind = "container"
+
+        [[item]]
+        id = "coin"
+        name = "coin"
+        start_location = "item:pouch"
+    "#;
+
+    #[test]
+    fn deep_examine_lists_nested_contents() {
+        let mut game = hall(NESTED);
+        let said = run(&mut game, "examine box");
+        assert!(said.contains("pouch"));
+        assert!(!said.contains("coin"));
+
+        let said = run(&mut game, "examine box deep");
+        assert!(said.contains("pouch"));
+        assert!(said.contains("coin"));
+
+        let mut game = hall_with("deep_examine = true", NESTED);
+        assert!(run(&mut game, "examine box").contains("coin"));
+    }
+
+    #[test]
+    fn examine_finds_what_an_npc_here_holds() {
+        let mut game = hall(
+            r#"
+            [[npc]]
+            id = "merchant"
+            name = "merchant"
+            start_room = "hall"
+
+            [[item]]
+            id = "sword"
+            name = "sword"
+            examine_text = "A gleaming sword."
+            start_location = "npc:merchant"
+            "#,
+        );
+
+        assert_eq!(
+            run(&mut game, "examine sword"),
+            "A gleaming sword. (held by the merchant)"
+        );
+    }
+
+    #[test]
+    fn look_in_lists_only_the_contents() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "chest"
+            name = "chest"
+            examine_text = "An oak chest."
+            start_location = "room:hall"
+            portable = false
+            kind = "container"
+
+            [[item]]
+            id = "coin"
+            name = "coin"
+            start_location = "item:chest"
+            "#,
+        );
+
+        let examined = run(&mut game, "examine chest");
+        assert!(examined.contains("An oak chest."));
+        assert!(examined.contains("coin"));
+        assert_eq!(run(&mut game, "look in chest"), "Inside it you see: coin.");
+    }
+
+    #[test]
+    fn auto_open_runs_the_action_that_opens_the_container() {
+        let mut game = hall_with(
+            "auto_open = true",
+            r#"
+            [[room.action]]
+            id = "unlock_chest"
+            verbs = ["unlock"]
+            nouns = ["chest"]
+            requires_inventory = ["key"]
+            response = "You unlock the chest."
+            effects = ["chest_open"]
+
+            [[item]]
+            id = "key"
+            name = "key"
+            start_location = "inventory"
+
+            [[item]]
+            id = "chest"
+            name = "chest"
+            start_location = "room:hall"
+            portable = false
+            kind = "container"
+            container_conditions = ["chest_open"]
+            container_closed_text = "The chest is locked."
+
+            [[item]]
+            id = "gold"
+            name = "gold"
+            start_location = "item:chest"
+            "#,
+        );
+
+        let said = run(&mut game, "take gold from chest");
+        assert!(said.contains("You unlock the chest."));
+        assert_eq!(
+            game.item_locations.get("gold"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+
+    #[test]
+    fn auto_open_respects_cooldowns_and_covers_take_all() {
+        let mut game = hall_with(
+            "auto_open = true",
+            r#"
+            [[room.action]]
+            id = "unlock_chest"
+            verbs = ["unlock"]
+            nouns = ["chest"]
+            cooldown_turns = 2
+            cooldown_text = "The lock is still stiff."
+            response = "You unlock the chest."
+            effects = ["chest_open"]
+
+            [[room.action]]
+            id = "lock_chest"
+            verbs = ["lock"]
+            nouns = ["chest"]
+            response = "You lock the chest."
+            effects = ["!chest_open"]
+
+            [[item]]
+            id = "chest"
+            name = "chest"
+            start_location = "room:hall"
+            portable = false
+            kind = "container"
+            container_conditions = ["chest_open"]
+            container_closed_text = "The chest is locked."
+
+            [[item]]
+            id = "gold"
+            name = "gold"
+            start_location = "item:chest"
+            "#,
+        );
+
+        run(&mut game, "unlock chest");
+        run(&mut game, "lock chest");
+        let said = run(&mut game, "take all from chest");
+        assert!(said.contains("The lock is still stiff."));
+        assert!(said.contains("The chest is locked."));
+
+        run(&mut game, "wait");
+        run(&mut game, "wait");
+        assert!(run(&mut game, "take all from chest").contains("You unlock the chest."));
+        assert_eq!(
+            game.item_locations.get("gold"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+
+    #[test]
+    fn many_items_stay_where_they_were_moved() {
+        let items: String = (0..200)
+            .map(|i| {
+                format!(
+                    "[[item]]\nid = \"pebble{i}\"\nname = \"pebble{i}\"\nstart_location = \"room:hall\"\n"
+                )
+            })
+            .collect();
+        let mut game = hall(&items);
+
+        for i in (0..200).step_by(3) {
+            run(&mut game, &format!("take pebble{i}"));
+        }
+        run(&mut game, "drop pebble0");
+
+        for i in 0..200 {
+            let expected = if i % 3 == 0 && i != 0 {
+                ItemLocation::Inventory
+            } else {
+                ItemLocation::Room("hall".into())
+            };
+            assert_eq!(
+                game.item_locations.get(&format!("pebble{i}")),
+                Some(&expected)
+            );
         }
+        assert_eq!(
+            game.item_locations
+                .ids_at(&ItemLocation::Room("hall".into()))
+                .count(),
+            200 - 66
+        );
+    }
+
+    #[test]
+    fn put_all_stops_when_the_container_is_full() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "chest"
+            name = "chest"
+            start_location = "room:hall"
+            portable = false
+            kind = "container"
+            capacity = 2
+
+            [[item]]
+            id = "apple"
+            name = "apple"
+            start_location = "inventory"
+
+            [[item]]
+            id = "pear"
+            name = "pear"
+            start_location = "inventory"
+
+            [[item]]
+            id = "plum"
+            name = "plum"
+            start_location = "inventory"
+            "#,
+        );
+
+        let said = run(&mut game, "put all in chest");
+        assert!(said.contains("apple"));
+        assert!(said.contains("pear"));
+        assert!(said.contains("The chest is full."));
+        assert_eq!(
+            game.item_locations.get("plum"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+
+    #[test]
+    fn examine_me_describes_the_player_and_their_things() {
+        let mut game = hall_with(
+            r#"player_desc = "You look tired.""#,
+            r#"
+            [[item]]
+            id = "cloak"
+            name = "cloak"
+            start_location = "inventory"
+            "#,
+        );
+
+        let said = run(&mut game, "examine me");
+        assert!(said.contains("You look tired."));
+        assert!(said.contains("cloak"));
+    }
+
+    #[test]
+    fn examine_me_lists_worn_items_and_health() {
+        let mut game = hall_with(
+            r#"player_description = "A weary traveller."
+            player_max_hp = 10"#,
+            r#"
+            [[item]]
+            id = "cloak"
+            name = "cloak"
+            start_location = "inventory"
+            wearable = true
+
+            [[item]]
+            id = "lamp"
+            name = "lamp"
+            start_location = "inventory"
+            "#,
+        );
+
+        assert_eq!(run(&mut game, "wear cloak"), "You put on the cloak.");
+        assert_eq!(
+            run(&mut game, "examine me"),
+            "A weary traveller.\nYou are wearing: cloak.\nYou are carrying: lamp.\nHealth: 10/10."
+        );
+
+        assert_eq!(run(&mut game, "take off cloak"), "You take off the cloak.");
+        run(&mut game, "wear cloak");
+        run(&mut game, "drop cloak");
+        assert!(game.worn_items.is_empty());
+    }
+
+    #[test]
+    fn storing_waits_for_the_use_conditions() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "power"
+            verbs = ["flip"]
+            nouns = ["switch"]
+            response = "The machine hums."
+            effects = ["powered"]
+
+            [[item]]
+            id = "slot"
+            name = "slot"
+            start_location = "room:hall"
+            portable = false
+            kind = "container"
+            container_use_conditions = ["powered"]
+            container_use_blocked_text = "The slot is dead."
+
+            [[item]]
+            id = "card"
+            name = "card"
+            start_location = "inventory"
+            "#,
+        );
+
+        assert_eq!(run(&mut game, "put card in slot"), "The slot is dead.");
+        run(&mut game, "flip switch");
+        run(&mut game, "put card in slot");
+        assert_eq!(
+            game.item_locations.get("card"),
+            Some(&ItemLocation::Item("slot".into()))
+        );
+    }
+
+    #[test]
+    fn drop_all_keeps_undroppable_items() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "amulet"
+            name = "amulet"
+            start_location = "inventory"
+            droppable = false
+
+            [[item]]
+            id = "rock"
+            name = "rock"
+            start_location = "inventory"
+            "#,
+        );
+
+        run(&mut game, "drop all");
+        assert_eq!(
+            game.item_locations.get("amulet"),
+            Some(&ItemLocation::Inventory)
+        );
+        assert_eq!(
+            game.item_locations.get("rock"),
+            Some(&ItemLocation::Room("hall".into()))
+        );
+    }
+
+    #[test]
+    fn a_spawn_into_a_container_completes_it() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "conjure"
+            verbs = ["wave"]
+            nouns = ["wand"]
+            response = "A gem appears."
+
+            [[room.action.spawn]]
+            item = "gem"
+            location = "item:altar"
+
+            [[item]]
+            id = "altar"
+            name = "altar"
+            start_location = "room:hall"
+            portable = false
+            kind = "container"
+            complete_when = ["gem"]
+            complete_flag = "altar_done"
+            complete_text = "The altar glows."
+
+            [[item]]
+            id = "gem"
+            name = "gem"
+            start_location = "nowhere"
+            "#,
+        );
+
+        assert!(run(&mut game, "wave wand").contains("The altar glows."));
+        assert!(game.flags.contains("altar_done"));
+    }
+
+    #[test]
+    fn some_items_must_be_held_to_examine() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "locket"
+            name = "locket"
+            examine_text = "A tiny portrait inside."
+            start_location = "room:hall"
+            examine_requires_inventory = true
+            "#,
+        );
+
+        assert_eq!(
+            run(&mut game, "examine locket"),
+            "You'd need to pick it up to look closer."
+        );
+        run(&mut game, "take locket");
+        assert!(run(&mut game, "examine locket").contains("A tiny portrait inside."));
+    }
+
+    #[test]
+    fn read_shows_an_inscription() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "ring"
+            name = "ring"
+            examine_text = "A gold ring."
+            inscription = "One ring to find them."
+            start_location = "inventory"
+            "#,
+        );
+
+        assert!(run(&mut game, "examine ring").contains("Something is written on it."));
+        assert_eq!(run(&mut game, "read ring"), "One ring to find them.");
+    }
+
+    #[test]
+    fn conditions_can_test_a_liquid() {
+        let mut game = hall(
+            r#"
+            [[item]]
+            id = "bottle"
+            name = "bottle"
+            start_location = "inventory"
+            holds_liquid = true
+
+            [[item]]
+            id = "fountain"
+            name = "fountain"
+            start_location = "room:hall"
+            portable = false
+            liquid_source = "water"
+
+            [[global_condition]]
+            id = "full"
+            conditions = ["liquid:bottle:water"]
+            one_shot = true
+            response = "The bottle is heavy now."
+            "#,
+        );
+
+        assert!(run(&mut game, "examine bottle").contains("The bottle is empty."));
+        assert!(run(&mut game, "fill bottle from fountain").contains("The bottle is heavy now."));
+        assert_eq!(game.item_liquid("bottle"), Some("water"));
+        assert!(!game.flags.contains("liquid:bottle:water"));
+        run(&mut game, "empty bottle");
+        assert_eq!(game.item_liquid("bottle"), None);
+    }
+
+    #[test]
+    fn an_effect_cannot_change_what_an_item_holds() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "spill"
+            verbs = ["wave"]
+            nouns = ["arms"]
+            effects = ["!liquid:bottle:water"]
+            response = "You wave your arms."
+
+            [[item]]
+            id = "bottle"
+            name = "bottle"
+            start_location = "inventory"
+            holds_liquid = true
+            start_liquid = "water"
+            "#,
+        );
+
+        run(&mut game, "wave arms");
+        assert_eq!(game.item_liquid("bottle"), Some("water"));
+    }
+
+    #[test]
+    fn dropping_bait_fires_the_room_trigger() {
+        let mut game = hall(
+            r#"
+            [[room.on_drop]]
+            item = "bait"
+            response = "A fish snaps at the bait!"
+            effects = ["fish_lured"]
+
+            [[item]]
+            id = "bait"
+            name = "bait"
+            start_location = "inventory"
+            "#,
+        );
+
+        assert!(run(&mut game, "drop bait").contains("A fish snaps at the bait!"));
+        assert!(game.flags.contains("fish_lured"));
+    }
+
+    #[test]
+    fn an_exit_can_be_examined() {
+        let mut game = game(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = "A hall."
+
+            [[room.exit]]
+            direction = "north"
+            target = "yard"
+            description = "Daylight spills through an archway."
+
+            [[room.exit]]
+            direction = "south"
+            target = "yard"
+
+            [[room]]
+            id = "yard"
+            name = "Yard"
+            desc = "A yard."
+            "#,
+        );
+
+        assert_eq!(
+            run(&mut game, "examine north"),
+            "Daylight spills through an archway."
+        );
+        assert_eq!(
+            run(&mut game, "examine south"),
+            "You see a way leading south."
+        );
+        assert_eq!(game.current_room_id, "hall");
     }
 }
@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet, hash_map};
+
+use crate::world;
+use crate::world::ItemLocation;
+
+/// Where every item in play is, plus the reverse index (place => items there).
+/// Both are updated on every move, so listing a room, the inventory or a container
+/// doesn't scan every item in the world. Moved items are also noted until
+/// `take_moved` collects them, so a turn can tell what moved without a full diff.
+#[derive(Default, Clone)]
+pub struct ItemLocations {
+    by_item: HashMap<String, ItemLocation>,
+    by_place: HashMap<ItemLocation, HashSet<String>>,
+    moved: HashSet<String>,
+}
+
+impl ItemLocations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, item_id: &str) -> Option<&ItemLocation> {
+        self.by_item.get(item_id)
+    }
+
+    pub fn contains_key(&self, item_id: &str) -> bool {
+        self.by_item.contains_key(item_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_item.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_item.is_empty()
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, String, ItemLocation> {
+        self.by_item.iter()
+    }
+
+    pub fn values(&self) -> hash_map::Values<'_, String, ItemLocation> {
+        self.by_item.values()
+    }
+
+    /// Place an item, moving it if it is already somewhere. Returns where it was.
+    pub fn insert(&mut self, item_id: String, loc: ItemLocation) -> Option<ItemLocation> {
+        self.by_place
+            .entry(loc.clone())
+            .or_default()
+            .insert(item_id.clone());
+        let previous = self.by_item.insert(item_id.clone(), loc.clone());
+        if let Some(prev) = &previous
+            && *prev != loc
+        {
+            self.unindex(&item_id, prev);
+        }
+        if previous.as_ref() != Some(&loc) {
+            self.moved.insert(item_id);
+        }
+        previous
+    }
+
+    /// Take an item out of play. Returns where it was.
+    pub fn remove(&mut self, item_id: &str) -> Option<ItemLocation> {
+        let previous = self.by_item.remove(item_id)?;
+        self.unindex(item_id, &previous);
+        self.moved.insert(item_id.to_string());
+        Some(previous)
+    }
+
+    /// Ids of the items placed, moved or removed since the last call.
+    pub fn take_moved(&mut self) -> HashSet<String> {
+        std::mem::take(&mut self.moved)
+    }
+
+    /// Ids of the items directly at `place` (not inside containers there), in no
+    /// particular order.
+    pub fn ids_at(&self, place: &ItemLocation) -> impl Iterator<Item = &str> {
+        self.by_place
+            .get(place)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// The items directly at `place`, in the order the world file lists them.
+    pub fn items_at<'w>(
+        &self,
+        world: &'w world::World,
+        place: &ItemLocation,
+    ) -> Vec<&'w world::Item> {
+        let mut found: Vec<(usize, &world::Item)> = self
+            .ids_at(place)
+            .filter_map(|id| world.items.get_full(id))
+            .map(|(idx, _, item)| (idx, item))
+            .collect();
+        found.sort_by_key(|(idx, _)| *idx);
+        found.into_iter().map(|(_, item)| item).collect()
+    }
+
+    fn unindex(&mut self, item_id: &str, place: &ItemLocation) {
+        if let Some(ids) = self.by_place.get_mut(place) {
+            ids.remove(item_id);
+            if ids.is_empty() {
+                self.by_place.remove(place);
+            }
+        }
+    }
+}
+
+/// The liquid each holder has in it right now (item id => liquid). Empty holders
+/// have no entry.
+pub type ItemLiquids = HashMap<String, String>;
+
+impl<'a> IntoIterator for &'a ItemLocations {
+    type Item = (&'a String, &'a ItemLocation);
+    type IntoIter = hash_map::Iter<'a, String, ItemLocation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.by_item.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ItemLocations;
+    use crate::world::ItemLocation;
+
+    #[test]
+    fn moves_are_noted_until_taken() {
+        let mut locs = ItemLocations::new();
+        locs.insert("key".into(), ItemLocation::Inventory);
+        locs.insert("coin".into(), ItemLocation::Room("hall".into()));
+        locs.take_moved();
+
+        locs.insert("key".into(), ItemLocation::Inventory);
+        assert!(locs.take_moved().is_empty());
+
+        locs.insert("key".into(), ItemLocation::Room("hall".into()));
+        locs.remove("coin");
+        let moved = locs.take_moved();
+        assert!(moved.contains("key") && moved.contains("coin"));
+        assert!(locs.take_moved().is_empty());
+    }
+}
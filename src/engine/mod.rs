@@ -2,23 +2,48 @@ mod actions;
 mod conditions;
 mod helpers;
 mod items;
+mod locations;
 mod movement;
 mod npcs;
 mod output;
 mod render;
+mod renderer;
+mod rng;
 
-pub use actions::{try_handle_action, try_handle_global_action};
+pub use actions::{
+    ActionCooldowns, try_handle_action, try_handle_easter_verb, try_handle_global_action,
+};
+
+pub use conditions::{
+    EvalContext, evaluate_endings, evaluate_global_conditions, evaluate_score_events,
+};
 
-pub use conditions::evaluate_global_conditions;
+pub use helpers::{
+    apply_effects, apply_effects_ordered, apply_world_effects, container_open, eq_ignore_case,
+    expand_command_aliases, expand_compound_verbs, fill_message, item_visible, npc_disposition,
+    set_npc_disposition,
+};
 
 pub use items::{
-    handle_drop, handle_drop_all, handle_examine, handle_give_to_npc, handle_inventory,
-    handle_take, handle_take_all_from_container, handle_take_all_room, handle_take_from_container,
-    handle_take_from_npc, try_handle_container_store,
+    evaluate_container_completion, evaluate_item_decay, fire_drop_triggers, fire_take_triggers,
+    floor_drop_item, handle_drop, handle_drop_all, handle_examine, handle_examine_all,
+    handle_give_to_npc, handle_inventory, handle_take, handle_take_all_from_container,
+    handle_take_all_room, handle_take_from_container, handle_take_from_npc,
+    try_handle_container_store, try_handle_liquid, try_handle_look_in, try_handle_read,
+    try_handle_wear,
 };
 
-pub use movement::try_handle_movement;
-pub use output::{Output, OutputBlock};
-pub use render::{render_room, room_depends_on_any_flag};
+pub use locations::{ItemLiquids, ItemLocations};
+pub use movement::{find_route, parse_movement_sequence, try_handle_movement};
+pub use output::{Choice, Output, OutputBlock};
+pub use render::{
+    RoomDetail, render_affordances, render_room, render_room_detail, room_depends_on_any_flag,
+};
+pub use renderer::{AnsiRenderer, OutputRenderer, TerminalRenderer};
+pub use rng::{FnvRng, Rng, SeededRng};
 
-pub use npcs::{handle_talk_to_npc, roam_npcs_after_player_move, try_handle_npc_action};
+pub use npcs::{
+    drop_npc_items, evaluate_npc_deaths, fire_npc_enter_triggers, handle_talk_to_npc,
+    is_npc_pronoun, npc_id_for_query, npc_present, roam_npcs_for_turn, try_handle_call,
+    try_handle_npc_action,
+};
@@ -1,10 +1,182 @@
-use std::collections::{HashMap, HashSet};
-
-use crate::engine::conditions::conditions_met;
-use crate::engine::helpers::apply_effects;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::engine::actions::phrase_match_score;
+use crate::engine::conditions::{EvalContext, conditions_met};
+use crate::engine::helpers::{
+    apply_world_effects, chars_eq_ignore_case, eq_ignore_case, exit_known_flag, fill_message,
+    npc_disposition,
+};
+use crate::engine::locations::{ItemLiquids, ItemLocations};
 use crate::engine::output::Output;
+use crate::engine::rng::Rng;
 use crate::world;
 
+/// Upper bound on the number of moves a single compound movement command may take.
+pub const MAX_MOVE_REPEAT: usize = 10;
+
+/// Parse a movement-only command into a sequence of single moves.
+///
+/// Supported forms:
+/// - chained directions: "n n e", "go north east"
+/// - repeats: "go north twice", "north 3", "n 3 times"
+///
+/// Apart from a leading "go"/"walk"/"run" and repeat counts, every word must be the
+/// direction (or its first letter) of an exit in the room that move starts from:
+/// `room` for the first, then wherever the exits before it lead. Exit verbs don't
+/// count, and input an action here claims ("climb up the rope") is left alone.
+/// Returns the exits' directions, or None unless at least two moves result, so single
+/// moves keep going through `try_handle_movement`. Capped at `MAX_MOVE_REPEAT` moves.
+pub fn parse_movement_sequence(
+    world: &world::World,
+    room: &world::Room,
+    npc_locations: &HashMap<String, String>,
+    cmd: &str,
+) -> Option<Vec<String>> {
+    let tokens: Vec<String> = cmd.split_whitespace().map(|t| t.to_lowercase()).collect();
+
+    if tokens.is_empty() || action_leads_input(world, room, npc_locations, &room.id, &tokens) {
+        return None;
+    }
+
+    let mut moves: Vec<String> = Vec::new();
+    let mut idx = 0;
+
+    if let Some(first) = tokens.first()
+        && matches!(first.as_str(), "go" | "walk" | "run")
+    {
+        idx = 1;
+    }
+
+    while idx < tokens.len() {
+        let tok = &tokens[idx];
+        idx += 1;
+
+        let count = match tok.as_str() {
+            "once" => 1,
+            "twice" => 2,
+            "thrice" => 3,
+            t => match t.parse::<usize>() {
+                Ok(n) => n,
+                Err(_) => {
+                    moves.push(tok.clone());
+                    continue;
+                }
+            },
+        };
+
+        // "3 times"
+        if tokens.get(idx).map(|t| t == "times").unwrap_or(false) {
+            idx += 1;
+        }
+
+        let last = moves.last()?.clone();
+        for _ in 1..count.min(MAX_MOVE_REPEAT) {
+            moves.push(last.clone());
+        }
+
+        if moves.len() >= MAX_MOVE_REPEAT {
+            break;
+        }
+    }
+
+    moves.truncate(MAX_MOVE_REPEAT);
+    if moves.len() < 2 {
+        return None;
+    }
+
+    // Follow the exits to check each word against the room it would be used in
+    let mut at = room;
+    let mut directions: Vec<String> = Vec::new();
+    for word in &moves {
+        let exit = exit_for_direction_word(at, word)?;
+        directions.push(exit.direction.clone());
+        at = world.rooms.get(&exit.target)?;
+    }
+    Some(directions)
+}
+
+/// The exit of `room` whose direction is `word`, or whose direction alone starts with
+/// `word` when it is a single letter ("n" for north).
+fn exit_for_direction_word<'a>(room: &'a world::Room, word: &str) -> Option<&'a world::Exit> {
+    if let Some(exit) = room
+        .exits
+        .iter()
+        .find(|exit| eq_ignore_case(&exit.direction, word))
+    {
+        return Some(exit);
+    }
+
+    let mut chars = word.chars();
+    let (Some(letter), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    let mut by_letter = room.exits.iter().filter(|exit| {
+        exit.direction
+            .chars()
+            .next()
+            .is_some_and(|c| chars_eq_ignore_case(c, letter))
+    });
+    match (by_letter.next(), by_letter.next()) {
+        (Some(exit), None) => Some(exit),
+        _ => None,
+    }
+}
+
+/// "goto": directions for the shortest walk from `from` to `to` over exits that are
+/// open now, passing only through `known_rooms`. Secret exits count once known.
+/// None if there is no such walk.
+pub fn find_route(
+    world: &world::World,
+    ctx: &EvalContext,
+    from: &str,
+    to: &str,
+    known_rooms: &HashSet<String>,
+) -> Option<Vec<String>> {
+    // room id => (previous room id, direction taken from it)
+    let mut came_from: HashMap<&str, (&str, &str)> = HashMap::new();
+    let mut queue: VecDeque<&str> = VecDeque::from([from]);
+
+    while let Some(room_id) = queue.pop_front() {
+        if room_id == to {
+            let mut route: Vec<String> = Vec::new();
+            let mut at = to;
+            while let Some((prev, dir)) = came_from.get(at) {
+                route.push(dir.to_string());
+                at = prev;
+            }
+            route.reverse();
+            return Some(route);
+        }
+
+        let room = match world.rooms.get(room_id) {
+            Some(r) => r,
+            None => continue,
+        };
+
+        for exit in &room.exits {
+            let target = exit.target.as_str();
+            if target == from
+                || came_from.contains_key(target)
+                || !known_rooms.contains(target)
+                || !conditions_met(&exit.conditions, ctx)
+                || (exit.secret
+                    && !ctx
+                        .flags
+                        .contains(&exit_known_flag(room_id, &exit.direction)))
+            {
+                continue;
+            }
+            came_from.insert(target, (room_id, exit.direction.as_str()));
+            queue.push_back(target);
+        }
+    }
+
+    None
+}
+
+/// Move the player through the exit `cmd` names, if any. On a move, `turn_cost` is
+/// set to the number of turns the exit takes.
+#[allow(clippy::too_many_arguments)]
 pub fn try_handle_movement(
     out: &mut Output,
     current_room_id: &mut String,
@@ -13,7 +185,12 @@ pub fn try_handle_movement(
     cmd: &str,
     npc_locations: &HashMap<String, String>,
     flags: &mut HashSet<String>,
+    turn_index: u64,
+    item_locations: &ItemLocations,
+    item_liquids: &ItemLiquids,
     attempt_seed: u64,
+    rng: &mut dyn Rng,
+    turn_cost: &mut u64,
 ) -> bool {
     let tokens: Vec<String> = cmd.split_whitespace().map(|t| t.to_lowercase()).collect();
 
@@ -21,10 +198,18 @@ pub fn try_handle_movement(
         return false;
     }
 
+    if action_leads_input(world, room, npc_locations, current_room_id, &tokens) {
+        return false;
+    }
+
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+
     // Helper: is this exit currently available?
-    let exit_available = |e: &world::Exit| conditions_met(&e.conditions, flags);
+    let exit_available = |e: &world::Exit| conditions_met(&e.conditions, &ctx);
 
-    // 1) Exact whole-token matches anywhere in the command
+    // 1) Whole-word matches anywhere in the command. Multi-word verbs
+    // ("go through archway") need every word present; longer phrases win.
+    let mut best_score = 0usize;
     let mut matches: Vec<&world::Exit> = Vec::new();
 
     for exit in &room.exits {
@@ -32,12 +217,33 @@ pub fn try_handle_movement(
             continue;
         }
 
-        let hit = tokens.iter().any(|tok| {
-            exit.direction.eq_ignore_ascii_case(tok)
-                || exit.verbs.iter().any(|v| v.eq_ignore_ascii_case(tok))
-        });
+        let dir_score = if tokens
+            .iter()
+            .any(|tok| eq_ignore_case(&exit.direction, tok))
+        {
+            1
+        } else {
+            0
+        };
+
+        let verb_score = exit
+            .verbs
+            .iter()
+            .filter(|v| exit_verb_usable(exit, v, &ctx))
+            .map(|v| phrase_match_score(v, &tokens))
+            .max()
+            .unwrap_or(0);
 
-        if hit {
+        let score = dir_score.max(verb_score);
+        if score == 0 {
+            continue;
+        }
+
+        if score > best_score {
+            best_score = score;
+            matches.clear();
+            matches.push(exit);
+        } else if score == best_score {
             matches.push(exit);
         }
     }
@@ -46,21 +252,22 @@ pub fn try_handle_movement(
         if let Some(block) = movement_blocked_by_npc(
             world,
             npc_locations,
-            flags,
+            &ctx,
             current_room_id,
             matches[0],
             attempt_seed,
+            rng,
         ) {
             out.say(block.message);
             if let Some(text) = block.attack_text {
                 out.say(text);
             }
             if !block.attack_effects.is_empty() {
-                apply_effects(flags, &block.attack_effects);
+                apply_world_effects(world, flags, &block.attack_effects);
             }
             return true;
         }
-        return do_move(out, current_room_id, world, matches[0]);
+        return do_move(out, current_room_id, world, matches[0], flags, turn_cost);
     } else if matches.len() > 1 {
         let dirs_list = matches
             .iter()
@@ -99,23 +306,21 @@ pub fn try_handle_movement(
             .direction
             .chars()
             .next()
-            .map(|c| {
-                abbrev_chars
-                    .iter()
-                    .any(|ac| ac.to_ascii_lowercase() == c.to_ascii_lowercase())
-            })
+            .map(|c| abbrev_chars.iter().any(|ac| chars_eq_ignore_case(*ac, c)))
             .unwrap_or(false);
 
-        let hit_verb = exit.verbs.iter().any(|v| {
-            v.chars()
-                .next()
-                .map(|c| {
-                    abbrev_chars
-                        .iter()
-                        .any(|ac| ac.to_ascii_lowercase() == c.to_ascii_lowercase())
-                })
-                .unwrap_or(false)
-        });
+        // Multi-word verbs are phrases, not abbreviable words
+        let hit_verb = exit
+            .verbs
+            .iter()
+            .filter(|v| !v.trim().contains(' ') && exit_verb_usable(exit, v, &ctx))
+            .any(|v| {
+                v.trim()
+                    .chars()
+                    .next()
+                    .map(|c| abbrev_chars.iter().any(|ac| chars_eq_ignore_case(*ac, c)))
+                    .unwrap_or(false)
+            });
 
         if hit_dir || hit_verb {
             abbrev_matches.push(exit);
@@ -128,21 +333,29 @@ pub fn try_handle_movement(
             if let Some(block) = movement_blocked_by_npc(
                 world,
                 npc_locations,
-                flags,
+                &ctx,
                 current_room_id,
                 abbrev_matches[0],
                 attempt_seed,
+                rng,
             ) {
                 out.say(block.message);
                 if let Some(text) = block.attack_text {
                     out.say(text);
                 }
                 if !block.attack_effects.is_empty() {
-                    apply_effects(flags, &block.attack_effects);
+                    apply_world_effects(world, flags, &block.attack_effects);
                 }
                 true
             } else {
-                do_move(out, current_room_id, world, abbrev_matches[0])
+                do_move(
+                    out,
+                    current_room_id,
+                    world,
+                    abbrev_matches[0],
+                    flags,
+                    turn_cost,
+                )
             }
         }
         _ => {
@@ -160,11 +373,69 @@ pub fn try_handle_movement(
     }
 }
 
+/// True for "press down on the lever" or "turn up the dial": the first word is no
+/// exit's direction or verb here, but is the verb of a room, global or NPC action
+/// here whose noun the input names. A direction word later on is then only a
+/// preposition, and the input is left to the actions.
+fn action_leads_input(
+    world: &world::World,
+    room: &world::Room,
+    npc_locations: &HashMap<String, String>,
+    current_room_id: &str,
+    tokens: &[String],
+) -> bool {
+    let first = tokens[0].as_str();
+    let leads_exit = matches!(first, "go" | "walk" | "run")
+        || room.exits.iter().any(|exit| {
+            eq_ignore_case(&exit.direction, first)
+                || exit.verbs.iter().any(|v| {
+                    v.split_whitespace()
+                        .next()
+                        .is_some_and(|w| eq_ignore_case(w, first))
+                })
+        });
+    if leads_exit {
+        return false;
+    }
+
+    let npc_actions = world
+        .npcs
+        .values()
+        .filter(|npc| npc_locations.get(&npc.id).map(String::as_str) == Some(current_room_id))
+        .flat_map(|npc| &npc.actions);
+
+    room.actions
+        .iter()
+        .chain(&world.global_actions)
+        .chain(npc_actions)
+        .any(|action| {
+            action.verbs.iter().any(|v| {
+                v.split_whitespace()
+                    .next()
+                    .is_some_and(|w| eq_ignore_case(w, first))
+                    && phrase_match_score(v, tokens) > 0
+            }) && action
+                .nouns
+                .iter()
+                .any(|n| phrase_match_score(n, tokens) > 0)
+        })
+}
+
+/// False if `verb` is one of the exit's gated verbs and its conditions aren't met.
+fn exit_verb_usable(exit: &world::Exit, verb: &str, ctx: &EvalContext) -> bool {
+    exit.verb_conditions
+        .iter()
+        .filter(|vc| eq_ignore_case(vc.verb.trim(), verb.trim()))
+        .all(|vc| conditions_met(&vc.conditions, ctx))
+}
+
 fn do_move(
     out: &mut Output,
     current_room_id: &mut String,
     world: &world::World,
     exit: &world::Exit,
+    flags: &mut HashSet<String>,
+    turn_cost: &mut u64,
 ) -> bool {
     if !world.rooms.contains_key(&exit.target) {
         out.say(format!(
@@ -173,8 +444,19 @@ fn do_move(
         ));
         return true;
     }
-    out.say(format!("You go {}.", exit.direction));
+    match exit.travel_text.as_deref().map(str::trim) {
+        Some(txt) if !txt.is_empty() => out.say(txt),
+        _ => out.say(fill_message(
+            &world.messages.go,
+            &[("direction", &exit.direction)],
+        )),
+    }
+    if exit.secret {
+        flags.insert(exit_known_flag(current_room_id, &exit.direction));
+    }
     *current_room_id = exit.target.clone();
+    apply_world_effects(world, flags, &exit.arrival_effects);
+    *turn_cost = exit.turn_cost.unwrap_or(1);
     true
 }
 
@@ -187,13 +469,27 @@ struct BlockOutcome {
 fn movement_blocked_by_npc(
     world: &world::World,
     npc_locations: &HashMap<String, String>,
-    flags: &HashSet<String>,
+    ctx: &EvalContext,
     current_room_id: &str,
     attempted_exit: &world::Exit,
     attempt_seed: u64,
+    rng: &mut dyn Rng,
 ) -> Option<BlockOutcome> {
-    for npc in world.npcs.values() {
-        if !npc.block_movement {
+    // Candidates are tried in a fixed order so the same NPC blocks every time
+    let mut npcs: Vec<&world::Npc> = world.npcs.values().collect();
+    npcs.sort_by(|a, b| {
+        b.block_priority
+            .cmp(&a.block_priority)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    for npc in npcs {
+        // A hostile NPC blocks (and may attack) even if not authored as a blocker;
+        // a friendly one never does.
+        let disposition = npc_disposition(ctx.flags, &npc.id);
+        let hostile = disposition == Some("hostile");
+
+        if (!npc.block_movement && !hostile) || disposition == Some("friendly") {
             continue;
         }
 
@@ -207,24 +503,24 @@ fn movement_blocked_by_npc(
         }
 
         // NPC must be visible and any block-specific conditions must be satisfied.
-        if !conditions_met(&npc.conditions, flags) {
+        if !conditions_met(&npc.conditions, ctx) {
             continue;
         }
 
-        if !npc.block_conditions.is_empty() && !conditions_met(&npc.block_conditions, flags) {
+        if !npc.block_conditions.is_empty() && !conditions_met(&npc.block_conditions, ctx) {
             continue;
         }
 
         // If block_exits is non-empty, require match with attempted exit direction or verbs.
         if !npc.block_exits.is_empty() {
-            let dir = attempted_exit.direction.to_ascii_lowercase();
-            let mut matches_exit = npc.block_exits.iter().any(|b| b.eq_ignore_ascii_case(&dir));
+            let dir = &attempted_exit.direction;
+            let mut matches_exit = npc.block_exits.iter().any(|b| eq_ignore_case(b, dir));
 
             if !matches_exit {
                 matches_exit = attempted_exit
                     .verbs
                     .iter()
-                    .any(|v| npc.block_exits.iter().any(|b| b.eq_ignore_ascii_case(v)));
+                    .any(|v| npc.block_exits.iter().any(|b| eq_ignore_case(b, v)));
             }
 
             if !matches_exit {
@@ -238,8 +534,9 @@ fn movement_blocked_by_npc(
         };
 
         // Optional attack
-        let (attack_text, attack_effects) = if npc.foe && npc.attack_chance_percent > 0 {
-            let roll = stable_roll_percent(attempt_seed, &npc.id);
+        let (attack_text, attack_effects) = if (npc.foe || hostile) && npc.attack_chance_percent > 0
+        {
+            let roll = rng.roll_percent(attempt_seed, &npc.id);
             if roll < npc.attack_chance_percent as u64 {
                 let text = npc
                     .attack_text
@@ -271,16 +568,320 @@ fn movement_blocked_by_npc(
     None
 }
 
-fn stable_roll_percent(turn_index: u64, npc_id: &str) -> u64 {
-    // 0..=99 deterministic per turn/NPC; not cryptographic.
-    stable_hash_u64(turn_index, npc_id) % 100
-}
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::engine::Rng;
+    use crate::testing::{game, run};
+
+    const HILL: &str = r#"
+        [world]
+        id = "t"
+        name = "T"
+        start_room = "foot"
+
+        [[room]]
+        id = "foot"
+        name = "Foot of the Hill"
+        desc = "A rope hangs from the cliff."
+
+        [[room.exit]]
+        direction = "north"
+        target = "slope"
+
+        [[room.exit]]
+        direction = "up"
+        target = "ledge"
+
+        [[room.action]]
+        id = "climb_rope"
+        verbs = ["climb"]
+        nouns = ["rope"]
+        response = "You swing on the rope a while."
+
+        [[room]]
+        id = "slope"
+        name = "Slope"
+        desc = "A slope."
+
+        [[room.exit]]
+        direction = "north"
+        target = "summit"
+
+        [[room]]
+        id = "summit"
+        name = "Summit"
+        desc = "The top."
+
+        [[room]]
+        id = "ledge"
+        name = "Ledge"
+        desc = "A ledge."
+    "#;
+
+    #[test]
+    fn chains_directions_along_the_route() {
+        let mut game = game(HILL);
+        run(&mut game, "n n");
+        assert_eq!(game.current_room_id, "summit");
+
+        let mut game = crate::testing::game(HILL);
+        run(&mut game, "go north twice");
+        assert_eq!(game.current_room_id, "summit");
+    }
+
+    #[test]
+    fn words_the_rooms_do_not_accept_are_not_chained() {
+        // Nowhere has a west exit, so this is a single move north
+        let mut game = game(HILL);
+        run(&mut game, "north west");
+        assert_eq!(game.current_room_id, "slope");
+
+        // The ledge has no exits, so "up twice" is one move up
+        let mut game = crate::testing::game(HILL);
+        run(&mut game, "up twice");
+        assert_eq!(game.current_room_id, "ledge");
+    }
+
+    #[test]
+    fn actions_see_the_input_first() {
+        let mut game = game(HILL);
+        let said = run(&mut game, "climb up rope");
+        assert!(said.contains("You swing on the rope a while."));
+        assert_eq!(game.current_room_id, "foot");
+    }
+
+    #[test]
+    fn an_exit_phrase_needs_all_its_words() {
+        let mut game = game(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "court"
+
+            [[room]]
+            id = "court"
+            name = "Court"
+            desc = "An archway leads east."
+
+            [[room.exit]]
+            direction = "east"
+            target = "garden"
+            verbs = ["go through archway"]
+
+            [[room]]
+            id = "garden"
+            name = "Garden"
+            desc = "A garden."
+            "#,
+        );
+
+        run(&mut game, "go through");
+        assert_eq!(game.current_room_id, "court");
+        run(&mut game, "go through the archway");
+        assert_eq!(game.current_room_id, "garden");
+    }
+
+    const GATE: &str = r#"
+        [world]
+        id = "t"
+        name = "T"
+        start_room = "gate"
+
+        [[room]]
+        id = "gate"
+        name = "Gate"
+        desc = "A gate."
+
+        [[room.exit]]
+        direction = "north"
+        target = "keep"
+
+        [[room]]
+        id = "keep"
+        name = "Keep"
+        desc = "A keep."
+    "#;
+
+    #[test]
+    fn the_highest_priority_blocker_speaks() {
+        let mut game = game(&format!(
+            r#"{}
+            [[npc]]
+            id = "a_guard"
+            name = "guard"
+            start_room = "gate"
+            block_movement = true
+            block_text = "The guard bars the gate."
+
+            [[npc]]
+            id = "b_captain"
+            name = "captain"
+            start_room = "gate"
+            block_movement = true
+            block_priority = 1
+            block_text = "The captain raises a hand."
+            "#,
+            GATE
+        ));
+        for _ in 0..3 {
+            assert_eq!(run(&mut game, "north"), "The captain raises a hand.");
+        }
+
+        // Equal priorities: the lowest id blocks
+        let mut game = crate::testing::game(&format!(
+            r#"{}
+            [[npc]]
+            id = "b_guard"
+            name = "guard"
+            start_room = "gate"
+            block_movement = true
+            block_text = "The guard bars the gate."
+
+            [[npc]]
+            id = "a_porter"
+            name = "porter"
+            start_room = "gate"
+            block_movement = true
+            block_text = "The porter shakes his head."
+            "#,
+            GATE
+        ));
+        for _ in 0..3 {
+            assert_eq!(run(&mut game, "north"), "The porter shakes his head.");
+        }
+    }
+
+    /// Counts the rolls asked of it; always rolls high, so nobody moves.
+    struct CountingRng(Rc<Cell<u32>>);
+
+    impl Rng for CountingRng {
+        fn roll_percent(&mut self, _seed: u64, _key: &str) -> u64 {
+            self.0.set(self.0.get() + 1);
+            99
+        }
+    }
+
+    #[test]
+    fn a_slow_exit_gives_npcs_a_roll_per_turn() {
+        let world = r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "bank"
+
+            [[room]]
+            id = "bank"
+            name = "Bank"
+            desc = "A river bank."
+
+            [[room.exit]]
+            direction = "east"
+            target = "far_bank"
+            turn_cost = 3
+
+            [[room.exit]]
+            direction = "west"
+            target = "meadow"
+
+            [[room]]
+            id = "far_bank"
+            name = "Far Bank"
+            desc = "The far bank."
+
+            [[room]]
+            id = "meadow"
+            name = "Meadow"
+            desc = "A meadow."
+
+            [[npc]]
+            id = "heron"
+            name = "heron"
+            start_room = "meadow"
+            roam_enabled = true
+            roam_rooms = ["meadow", "far_bank"]
+            roam_chance_percent = 50
+        "#;
+
+        let rolls = |dir: &str| {
+            let count = Rc::new(Cell::new(0));
+            let mut game = game(world);
+            game.rng = Box::new(CountingRng(Rc::clone(&count)));
+            run(&mut game, dir);
+            (game.turn_index, count.get())
+        };
+
+        assert_eq!(rolls("west"), (1, 1));
+        assert_eq!(rolls("east"), (3, 3));
+    }
 
-fn stable_hash_u64(turn_index: u64, s: &str) -> u64 {
-    let mut h = 1469598103934665603u64 ^ turn_index;
-    for b in s.as_bytes() {
-        h ^= *b as u64;
-        h = h.wrapping_mul(1099511628211u64);
+    #[test]
+    fn goto_walks_through_rooms_and_stops_when_blocked() {
+        let world = r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = "A hall."
+
+            [[room.exit]]
+            direction = "east"
+            target = "gallery"
+
+            [[room]]
+            id = "gallery"
+            name = "Long Gallery"
+            desc = "A gallery."
+
+            [[room.exit]]
+            direction = "west"
+            target = "hall"
+
+            [[room.exit]]
+            direction = "east"
+            target = "study"
+
+            [[room]]
+            id = "study"
+            name = "Study"
+            desc = "A study."
+
+            [[room.exit]]
+            direction = "west"
+            target = "gallery"
+
+            [[npc]]
+            id = "guard"
+            name = "guard"
+            start_room = "gallery"
+            conditions = ["alarm"]
+            block_movement = true
+            block_exits = ["east"]
+        "#;
+
+        let mut game = game(world);
+        run(&mut game, "east");
+        run(&mut game, "east");
+        run(&mut game, "west");
+        run(&mut game, "west");
+        assert_eq!(game.current_room_id, "hall");
+
+        run(&mut game, "goto study");
+        assert_eq!(game.current_room_id, "study");
+        assert_eq!(game.turn_index, 6);
+
+        run(&mut game, "goto hall");
+        game.flags.insert("alarm".into());
+        let said = run(&mut game, "goto study");
+        assert!(said.contains("guard blocks your way"));
+        assert!(said.contains("You stop after 1 of 2 moves."));
+        assert_eq!(game.current_room_id, "gallery");
     }
-    h
 }
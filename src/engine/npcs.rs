@@ -1,31 +1,36 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::engine::actions::evaluate_actions_for_input;
-use crate::engine::conditions::conditions_met;
-use crate::engine::helpers::apply_effects;
+use crate::engine::actions::{
+    ActionCooldowns, evaluate_actions_for_input, fire_action_with_cooldown,
+};
+use crate::engine::conditions::{EvalContext, conditions_met};
+use crate::engine::helpers::{apply_world_effects, fill_message, set_npc_disposition};
+use crate::engine::items::ask_which_by_name;
+use crate::engine::locations::{ItemLiquids, ItemLocations};
 use crate::engine::output::Output;
+use crate::engine::rng::Rng;
 use crate::world;
 use crate::world::ItemLocation;
 
 pub enum NpcMatch<'a> {
     None,
     One(&'a world::Npc),
-    Many(()),
+    Many(Vec<&'a world::Npc>), // tied candidates, by name
 }
 
 fn tokenize(input: &str) -> Vec<String> {
     input.split_whitespace().map(|t| t.to_lowercase()).collect()
 }
 
-fn npc_visible(npc: &world::Npc, flags: &HashSet<String>) -> bool {
-    conditions_met(&npc.conditions, flags)
+fn npc_visible(npc: &world::Npc, ctx: &EvalContext) -> bool {
+    conditions_met(&npc.conditions, ctx)
 }
 
 /// Basic full-word overlap scoring on name + aliases (same spirit as items)
 pub(crate) fn find_npc_by_words_scored<'a>(
     world: &'a world::World,
     npc_locations: &HashMap<String, String>,
-    flags: &HashSet<String>,
+    ctx: &EvalContext,
     current_room_id: &str,
     query: &str,
 ) -> NpcMatch<'a> {
@@ -51,33 +56,11 @@ pub(crate) fn find_npc_by_words_scored<'a>(
             continue;
         }
 
-        if !npc_visible(npc, flags) {
+        if !npc_visible(npc, ctx) {
             continue;
         }
 
-        let mut all_words: Vec<String> = Vec::new();
-        all_words.extend(
-            npc.name
-                .split_whitespace()
-                .filter(|w| !w.is_empty())
-                .map(|w| w.to_lowercase()),
-        );
-        for alias in &npc.aliases {
-            all_words.extend(
-                alias
-                    .split_whitespace()
-                    .filter(|w| !w.is_empty())
-                    .map(|w| w.to_lowercase()),
-            );
-        }
-
-        let mut score = 0usize;
-        for qw in &query_words {
-            if all_words.iter().any(|nw| nw == qw) {
-                score += 1;
-            }
-        }
-
+        let score = npc_word_score(npc, &query_words);
         if score > 0 {
             scored.push((npc, score));
         }
@@ -98,21 +81,108 @@ pub(crate) fn find_npc_by_words_scored<'a>(
         1 => NpcMatch::One(best[0]),
         _ => {
             best.sort_by(|a, b| a.name.cmp(&b.name));
-            NpcMatch::Many(())
+            NpcMatch::Many(best)
         }
     }
 }
 
+/// Ask which of the tied NPCs the player meant, like items do.
+pub(crate) fn ask_which_npc(
+    out: &mut Output,
+    world: &world::World,
+    query: &str,
+    candidates: &[&world::Npc],
+) {
+    let names: Vec<&str> = candidates.iter().map(|n| n.name.as_str()).collect();
+    ask_which_by_name(out, world, query, &names);
+}
+
+/// The words of `input` that name any of `candidates`, e.g. "guard" in "hit the guard".
+fn npc_words_in(input: &str, candidates: &[&world::Npc]) -> String {
+    tokenize(input)
+        .into_iter()
+        .filter(|w| {
+            candidates
+                .iter()
+                .any(|npc| npc_word_score(npc, std::slice::from_ref(w)) > 0)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How many of `query_words` appear in the NPC's name or aliases.
+fn npc_word_score(npc: &world::Npc, query_words: &[String]) -> usize {
+    let mut all_words: Vec<String> = Vec::new();
+    all_words.extend(
+        npc.name
+            .split_whitespace()
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase()),
+    );
+    for alias in &npc.aliases {
+        all_words.extend(
+            alias
+                .split_whitespace()
+                .filter(|w| !w.is_empty())
+                .map(|w| w.to_lowercase()),
+        );
+    }
+
+    query_words
+        .iter()
+        .filter(|qw| all_words.iter().any(|nw| nw == *qw))
+        .count()
+}
+
+/// Id of the one visible NPC in the current room that `query` names, if any.
+pub fn npc_id_for_query(
+    world: &world::World,
+    npc_locations: &HashMap<String, String>,
+    ctx: &EvalContext,
+    current_room_id: &str,
+    query: &str,
+) -> Option<String> {
+    match find_npc_by_words_scored(world, npc_locations, ctx, current_room_id, query) {
+        NpcMatch::One(npc) => Some(npc.id.clone()),
+        _ => None,
+    }
+}
+
+/// True if the NPC is in the current room and visible.
+pub fn npc_present(
+    world: &world::World,
+    npc_locations: &HashMap<String, String>,
+    ctx: &EvalContext,
+    current_room_id: &str,
+    npc_id: &str,
+) -> bool {
+    world.npcs.get(npc_id).is_some_and(|npc| {
+        npc_locations.get(npc_id).map(String::as_str) == Some(current_room_id)
+            && npc_visible(npc, ctx)
+    })
+}
+
+/// True if `query` ("him", "to her", ...) refers back to the last NPC mentioned.
+pub fn is_npc_pronoun(query: &str) -> bool {
+    let words: Vec<String> = tokenize(query).into_iter().filter(|w| w != "to").collect();
+    matches!(words.as_slice(), [w] if ["him", "her", "them", "it"].contains(&w.as_str()))
+}
+
 /// Try to handle NPC interactions using the existing Action evaluator.
 /// This triggers only when the input mentions the NPC (via name word overlap).
+#[allow(clippy::too_many_arguments)]
 pub fn try_handle_npc_action(
     out: &mut Output,
     input: &str,
+    instrument: Option<&str>,
     world: &world::World,
-    item_locations: &mut HashMap<String, world::ItemLocation>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
     npc_locations: &HashMap<String, String>,
     current_room_id: &str,
     flags: &mut HashSet<String>,
+    turn_index: u64,
+    cooldowns: &mut ActionCooldowns,
 ) -> bool {
     let tokens = tokenize(input);
     if tokens.is_empty() {
@@ -120,12 +190,13 @@ pub fn try_handle_npc_action(
     }
 
     // Find which NPC the player is addressing in this room.
-    let npc_match = find_npc_by_words_scored(world, npc_locations, flags, current_room_id, input);
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+    let npc_match = find_npc_by_words_scored(world, npc_locations, &ctx, current_room_id, input);
 
     let npc = match npc_match {
         NpcMatch::None => return false,
-        NpcMatch::Many(_) => {
-            out.say("Be more specific.");
+        NpcMatch::Many(candidates) => {
+            ask_which_npc(out, world, &npc_words_in(input, &candidates), &candidates);
             return true;
         }
         NpcMatch::One(n) => n,
@@ -135,23 +206,35 @@ pub fn try_handle_npc_action(
     let (exec, msg, handled) = evaluate_actions_for_input(
         &npc.actions,
         input,
+        instrument,
         world,
-        item_locations,
         current_room_id,
-        flags,
+        &ctx,
     );
 
     if let Some(action) = exec {
-        let txt = action.response.trim();
-        if !txt.is_empty() {
-            out.say(txt);
+        if !fire_action_with_cooldown(
+            out,
+            world,
+            action,
+            format!("{}::{}", npc.id, action.id),
+            item_locations,
+            item_liquids,
+            flags,
+            turn_index,
+            cooldowns,
+        ) {
+            return true;
         }
-        apply_effects(flags, &action.effects);
 
-        // Consume required inventory items by removing their location entries entirely.
-        // This prevents taking them back after a successful NPC action (e.g., bribe).
+        // Required inventory items are used up, so they can't be taken back after a
+        // successful NPC action (e.g., bribe), unless the action lets the NPC keep them.
         for req in &action.requires_inventory {
-            item_locations.remove(req);
+            if action.keep_items {
+                item_locations.insert(req.clone(), ItemLocation::Npc(npc.id.clone()));
+            } else {
+                item_locations.remove(req);
+            }
         }
 
         return true;
@@ -168,24 +251,23 @@ pub fn try_handle_npc_action(
 /// Examine NPCs in the current room.
 pub fn try_handle_examine_npc(
     out: &mut Output,
-    item_locations: &HashMap<String, ItemLocation>,
     world: &world::World,
     npc_locations: &HashMap<String, String>,
     current_room_id: &str,
     target_name: &str,
-    flags: &HashSet<String>,
+    ctx: &EvalContext,
 ) -> bool {
     let query = target_name.trim().to_lowercase();
     if query.is_empty() {
         return false;
     }
 
-    let npc_match = find_npc_by_words_scored(world, npc_locations, flags, current_room_id, &query);
+    let npc_match = find_npc_by_words_scored(world, npc_locations, ctx, current_room_id, &query);
 
     let npc = match npc_match {
         NpcMatch::None => return false,
-        NpcMatch::Many(_) => {
-            out.say("Be more specific.");
+        NpcMatch::Many(candidates) => {
+            ask_which_npc(out, world, &query, &candidates);
             return true;
         }
         NpcMatch::One(n) => n,
@@ -193,20 +275,21 @@ pub fn try_handle_examine_npc(
 
     let txt = npc.examine_text.trim();
     if txt.is_empty() {
-        out.say(format!("You see nothing special about {}.", npc.name));
+        out.say(fill_message(
+            &world.messages.npc_nothing_special,
+            &[("npc", &npc.name)],
+        ));
     } else {
         out.say(txt);
     }
 
     // List visible items held by this NPC.
-    let mut held: Vec<&world::Item> = Vec::new();
-    for item in world.items.values() {
-        if let Some(ItemLocation::Npc(holder)) = item_locations.get(&item.id) {
-            if holder == &npc.id && conditions_met(&item.conditions, flags) {
-                held.push(item);
-            }
-        }
-    }
+    let mut held: Vec<&world::Item> = ctx
+        .item_locations
+        .items_at(world, &ItemLocation::Npc(npc.id.clone()))
+        .into_iter()
+        .filter(|item| conditions_met(&item.conditions, ctx))
+        .collect();
 
     if !held.is_empty() {
         held.sort_by(|a, b| a.name.cmp(&b.name));
@@ -223,6 +306,7 @@ pub fn try_handle_examine_npc(
 
 /// Simple dialogue handler: triggers the first matching dialogue entry for the NPC.
 /// Returns true if handled (even if no dialogue available), false if no NPC match.
+#[allow(clippy::too_many_arguments)]
 pub fn handle_talk_to_npc(
     out: &mut Output,
     world: &world::World,
@@ -230,32 +314,40 @@ pub fn handle_talk_to_npc(
     current_room_id: &str,
     target_name: &str,
     flags: &mut HashSet<String>,
+    turn_index: u64,
+    item_locations: &ItemLocations,
+    item_liquids: &ItemLiquids,
     fired_dialogues: &mut HashSet<String>,
 ) -> bool {
     let query = target_name.trim().to_lowercase();
     if query.is_empty() {
-        out.say("Talk to whom?");
+        out.say(&world.messages.talk_to_whom);
         return true;
     }
 
-    let npc_match = find_npc_by_words_scored(world, npc_locations, flags, current_room_id, &query);
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+    let npc_match = find_npc_by_words_scored(world, npc_locations, &ctx, current_room_id, &query);
 
     let npc = match npc_match {
         NpcMatch::None => return false,
-        NpcMatch::Many(_) => {
-            out.say("Be more specific.");
+        NpcMatch::Many(candidates) => {
+            ask_which_npc(out, world, &query, &candidates);
             return true;
         }
         NpcMatch::One(n) => n,
     };
 
     if npc.dialogue.is_empty() {
-        out.say(format!("{} has nothing to say.", npc.name));
+        out.say(fill_message(
+            &world.messages.npc_nothing_to_say,
+            &[("npc", &npc.name)],
+        ));
         return true;
     }
 
     for dlg in &npc.dialogue {
-        if !conditions_met(&dlg.conditions, flags) {
+        let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+        if !conditions_met(&dlg.conditions, &ctx) {
             continue;
         }
 
@@ -268,7 +360,11 @@ pub fn handle_talk_to_npc(
         if !txt.is_empty() {
             out.say(txt);
         }
-        apply_effects(flags, &dlg.effects);
+        apply_world_effects(world, flags, &dlg.effects);
+
+        if let Some(disp) = &dlg.sets_disposition {
+            set_npc_disposition(flags, &npc.id, disp);
+        }
 
         if dlg.one_shot {
             fired_dialogues.insert(key);
@@ -277,21 +373,74 @@ pub fn handle_talk_to_npc(
         return true;
     }
 
-    out.say(format!("{} has nothing new to say.", npc.name));
+    out.say(fill_message(
+        &world.messages.npc_nothing_new,
+        &[("npc", &npc.name)],
+    ));
     true
 }
 
-/// Deterministic roaming after a successful player move.
-/// - Called ONLY when the player actually changes rooms.
-/// - Uses (turn_index, npc_id) to pick whether the NPC moves and to which allowed room.
+/// The player has just walked into `current_room_id`: fire every `on_player_enter`
+/// entry of the visible NPCs here whose conditions hold and that hasn't fired yet,
+/// in file order. Responses are events, shown after the room.
+#[allow(clippy::too_many_arguments)]
+pub fn fire_npc_enter_triggers(
+    out: &mut Output,
+    world: &world::World,
+    npc_locations: &HashMap<String, String>,
+    current_room_id: &str,
+    flags: &mut HashSet<String>,
+    turn_index: u64,
+    item_locations: &ItemLocations,
+    item_liquids: &ItemLiquids,
+    fired_enter_triggers: &mut HashSet<String>,
+) {
+    for npc in world.npcs.values() {
+        let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+        if npc_locations.get(&npc.id).map(String::as_str) != Some(current_room_id)
+            || !conditions_met(&npc.conditions, &ctx)
+        {
+            continue;
+        }
+
+        for trigger in &npc.on_player_enter {
+            let key = format!("{}::{}", npc.id, trigger.id);
+            let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+            if fired_enter_triggers.contains(&key) || !conditions_met(&trigger.conditions, &ctx) {
+                continue;
+            }
+
+            let txt = trigger.response.trim();
+            if !txt.is_empty() {
+                out.event(txt);
+            }
+            apply_world_effects(world, flags, &trigger.effects);
+            fired_enter_triggers.insert(key);
+        }
+    }
+}
+
+/// Deterministic roaming for a turn that passed.
+/// - Called once per turn: after each turn of a successful move (slow exits take
+///   several) and after a "wait", never for commands that take no turn.
+/// - Rolls for (turn_index, npc_id) decide whether the NPC moves and
+///   to which allowed room.
 /// - No output; appearance is handled naturally by room rendering.
-pub fn roam_npcs_after_player_move(
+/// - Items in `carries_on_roam` lying in the room left behind are picked up;
+///   whatever the NPC holds goes with it.
+pub fn roam_npcs_for_turn(
     world: &world::World,
     npc_locations: &mut HashMap<String, String>,
+    item_locations: &mut ItemLocations,
     flags: &HashSet<String>,
     turn_index: u64,
+    rng: &mut dyn Rng,
 ) {
-    for npc in world.npcs.values() {
+    // Stable order so seeded generators give reproducible results
+    let mut npcs: Vec<&world::Npc> = world.npcs.values().collect();
+    npcs.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for npc in npcs {
         let roam = match &npc.roam {
             Some(r) if r.enabled && !r.allowed_rooms.is_empty() && r.chance_percent > 0 => r,
             _ => continue,
@@ -301,39 +450,472 @@ pub fn roam_npcs_after_player_move(
         // visibility is handled at render time.
         let _ = flags; // explicit: we don't need flags here today
 
-        let roll = deterministic_roll_percent(turn_index, &npc.id);
+        let roll = rng.roll_percent(turn_index, &npc.id);
         if roll >= roam.chance_percent as u64 {
             continue;
         }
 
-        let idx = deterministic_index(turn_index, &npc.id, roam.allowed_rooms.len());
+        let idx = rng.pick_index(turn_index, &npc.id, roam.allowed_rooms.len());
         let target_room = roam.allowed_rooms[idx].clone();
 
         // Only move if target exists (author error safe-guard)
-        if world.rooms.contains_key(&target_room) {
-            npc_locations.insert(npc.id.clone(), target_room);
+        if !world.rooms.contains_key(&target_room) {
+            continue;
+        }
+
+        move_npc(npc, npc_locations, item_locations, &target_room);
+    }
+}
+
+/// Put an NPC in `target_room`, taking its carries_on_roam items from the room it leaves.
+fn move_npc(
+    npc: &world::Npc,
+    npc_locations: &mut HashMap<String, String>,
+    item_locations: &mut ItemLocations,
+    target_room: &str,
+) {
+    if let Some(left_room) = npc_locations.insert(npc.id.clone(), target_room.to_string())
+        && left_room != target_room
+    {
+        for item_id in &npc.carries_on_roam {
+            if matches!(item_locations.get(item_id), Some(ItemLocation::Room(r)) if *r == left_room)
+            {
+                item_locations.insert(item_id.clone(), ItemLocation::Npc(npc.id.clone()));
+            }
         }
     }
 }
 
-fn deterministic_roll_percent(turn_index: u64, npc_id: &str) -> u64 {
-    // 0..=99
-    (stable_hash_u64(turn_index, npc_id) % 100) as u64
+/// "call <npc>" / "shout [for <npc>]": NPCs with responds_to_call answer from the rooms
+/// the current room's open exits lead to, and come over if comes_when_called. A bare
+/// "shout" reaches every such NPC. Runs after actions, so authors can still give
+/// "call" or "shout" their own meaning.
+#[allow(clippy::too_many_arguments)]
+pub fn try_handle_call(
+    out: &mut Output,
+    input: &str,
+    world: &world::World,
+    npc_locations: &mut HashMap<String, String>,
+    item_locations: &mut ItemLocations,
+    item_liquids: &ItemLiquids,
+    current_room_id: &str,
+    flags: &HashSet<String>,
+    turn_index: u64,
+) -> bool {
+    let words = tokenize(input);
+    let Some((verb, rest)) = words.split_first() else {
+        return false;
+    };
+    if !["call", "shout", "yell"].contains(&verb.as_str()) {
+        return false;
+    }
+    let query_words: Vec<String> = rest
+        .iter()
+        .filter(|w| !["to", "for", "the", "out"].contains(&w.as_str()))
+        .cloned()
+        .collect();
+
+    let ctx = EvalContext::new(flags, turn_index, item_locations, item_liquids);
+    if !query_words.is_empty()
+        && let NpcMatch::One(npc) =
+            find_npc_by_words_scored(world, npc_locations, &ctx, current_room_id, &rest.join(" "))
+    {
+        out.say(format!("The {} is right here.", npc.name));
+        return true;
+    }
+
+    let adjacent: HashSet<&str> = world
+        .rooms
+        .get(current_room_id)
+        .map(|room| {
+            room.exits
+                .iter()
+                .filter(|exit| conditions_met(&exit.conditions, &ctx))
+                .map(|exit| exit.target.as_str())
+                .filter(|target| *target != current_room_id)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Stable order so several answers always come in the same order
+    let mut nearby: Vec<(&world::Npc, usize)> = world
+        .npcs
+        .values()
+        .filter(|npc| {
+            npc_locations
+                .get(&npc.id)
+                .is_some_and(|room| adjacent.contains(room.as_str()))
+                && npc_visible(npc, &ctx)
+        })
+        .map(|npc| (npc, npc_word_score(npc, &query_words)))
+        .filter(|(_, score)| query_words.is_empty() || *score > 0)
+        .collect();
+    let best = nearby.iter().map(|(_, s)| *s).max().unwrap_or(0);
+    nearby.retain(|(_, s)| *s == best);
+    nearby.sort_by(|a, b| a.0.id.cmp(&b.0.id));
+
+    let answering: Vec<&world::Npc> = match nearby.as_slice() {
+        [] => Vec::new(),
+        [(npc, _)] => vec![*npc],
+        _ if query_words.is_empty() => nearby.iter().map(|(npc, _)| *npc).collect(),
+        _ => {
+            let candidates: Vec<&world::Npc> = nearby.iter().map(|(npc, _)| *npc).collect();
+            ask_which_npc(out, world, &query_words.join(" "), &candidates);
+            return true;
+        }
+    };
+    let answering: Vec<&world::Npc> = answering
+        .into_iter()
+        .filter(|npc| npc.responds_to_call)
+        .collect();
+
+    if answering.is_empty() {
+        out.say(&world.messages.no_answer);
+        return true;
+    }
+
+    for npc in answering {
+        let text = match (&npc.call_text, npc.comes_when_called) {
+            (Some(text), _) => text.trim().to_string(),
+            (None, true) => format!("The {} comes at your call.", npc.name),
+            (None, false) => format!("The {} calls back from nearby.", npc.name),
+        };
+        out.say(text);
+        if npc.comes_when_called {
+            move_npc(npc, npc_locations, item_locations, current_room_id);
+        }
+    }
+    true
 }
 
-fn deterministic_index(turn_index: u64, npc_id: &str, len: usize) -> usize {
-    if len == 0 {
-        return 0;
+/// An NPC dies when any effect sets the flag "dead:<npc_id>".
+/// Dead NPCs leave the world and drop what they carried into the room they were in.
+pub fn evaluate_npc_deaths(
+    out: &mut Output,
+    world: &world::World,
+    flags: &HashSet<String>,
+    item_locations: &mut ItemLocations,
+    npc_locations: &mut HashMap<String, String>,
+    current_room_id: &str,
+) {
+    let mut dead: Vec<&String> = npc_locations
+        .keys()
+        .filter(|id| flags.contains(&format!("dead:{}", id)))
+        .collect();
+    dead.sort();
+    let dead: Vec<String> = dead.into_iter().cloned().collect();
+
+    for npc_id in dead {
+        if let Some(room_id) = npc_locations.remove(&npc_id) {
+            drop_npc_items(
+                out,
+                world,
+                item_locations,
+                &npc_id,
+                &room_id,
+                current_room_id,
+            );
+        }
     }
-    (stable_hash_u64(turn_index.wrapping_add(999), npc_id) % (len as u64)) as usize
 }
 
-fn stable_hash_u64(turn_index: u64, s: &str) -> u64 {
-    // Simple stable hash: not cryptographic, just deterministic.
-    let mut h = 1469598103934665603u64 ^ turn_index;
-    for b in s.as_bytes() {
-        h ^= *b as u64;
-        h = h.wrapping_mul(1099511628211u64);
+/// Move everything an NPC holds into `room_id`, announcing it if the player is there.
+pub fn drop_npc_items(
+    out: &mut Output,
+    world: &world::World,
+    item_locations: &mut ItemLocations,
+    npc_id: &str,
+    room_id: &str,
+    current_room_id: &str,
+) {
+    let mut dropped = item_locations.items_at(world, &ItemLocation::Npc(npc_id.to_string()));
+    for item in &dropped {
+        item_locations.insert(item.id.clone(), ItemLocation::Room(room_id.to_string()));
+    }
+
+    if dropped.is_empty() || room_id != current_room_id {
+        return;
+    }
+
+    dropped.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    let names = dropped
+        .iter()
+        .map(|i| i.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let npc_name = world
+        .npcs
+        .get(npc_id)
+        .map(|n| n.name.as_str())
+        .unwrap_or(npc_id);
+    out.event(format!("The {} drops: {}.", npc_name, names));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::Rng;
+    use crate::testing::{game, hall, run};
+    use crate::world::ItemLocation;
+
+    /// Rolls whatever it was told to, in order; 0 once the script runs out.
+    struct ScriptedRng(Vec<u64>);
+
+    impl Rng for ScriptedRng {
+        fn roll_percent(&mut self, _seed: u64, _key: &str) -> u64 {
+            if self.0.is_empty() {
+                0
+            } else {
+                self.0.remove(0)
+            }
+        }
+    }
+
+    #[test]
+    fn a_low_roll_sends_an_npc_roaming() {
+        let mut game = game(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "gate"
+
+            [[room]]
+            id = "gate"
+            name = "Gate"
+            desc = "A gate."
+
+            [[room]]
+            id = "yard"
+            name = "Yard"
+            desc = "A yard."
+
+            [[npc]]
+            id = "guard"
+            name = "guard"
+            start_room = "gate"
+            roam_enabled = true
+            roam_rooms = ["yard"]
+            roam_chance_percent = 1
+            "#,
+        );
+
+        game.rng = Box::new(ScriptedRng(vec![99]));
+        run(&mut game, "wait");
+        assert_eq!(game.npc_locations["guard"], "gate");
+
+        run(&mut game, "wait");
+        assert_eq!(game.npc_locations["guard"], "yard");
+    }
+
+    /// The hall, with a yard to the north, and `rest` after the yard.
+    fn hall_and_yard(rest: &str) -> crate::GameState {
+        game(&format!(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = "A hall."
+
+            [[room.exit]]
+            direction = "north"
+            target = "yard"
+
+            [[room]]
+            id = "yard"
+            name = "Yard"
+            desc = "A yard."
+
+            [[room.exit]]
+            direction = "south"
+            target = "hall"
+
+            {}
+            "#,
+            rest
+        ))
+    }
+
+    #[test]
+    fn a_rude_remark_turns_an_npc_into_a_blocker() {
+        let mut game = hall_and_yard(
+            r#"
+            [[npc]]
+            id = "merchant"
+            name = "merchant"
+            start_room = "hall"
+            disposition = "friendly"
+
+            [[npc.dialogue]]
+            id = "insult"
+            response = "The merchant's face darkens."
+            sets_disposition = "hostile"
+            "#,
+        );
+
+        run(&mut game, "talk to merchant");
+        assert_eq!(game.npc_disposition("merchant"), Some("hostile"));
+        assert!(run(&mut game, "north").contains("blocks your way"));
+        assert_eq!(game.current_room_id, "hall");
+    }
+
+    #[test]
+    fn a_dead_npc_drops_what_it_carried() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "slay"
+            verbs = ["slay"]
+            nouns = ["guard"]
+            response = "The guard falls."
+            effects = ["dead:guard"]
+
+            [[npc]]
+            id = "guard"
+            name = "guard"
+            start_room = "hall"
+
+            [[item]]
+            id = "key"
+            name = "key"
+            start_location = "npc:guard"
+            "#,
+        );
+
+        assert!(run(&mut game, "slay guard").contains("The guard drops: key."));
+        assert_eq!(
+            game.item_locations.get("key"),
+            Some(&ItemLocation::Room("hall".into()))
+        );
+        assert!(!game.npc_locations.contains_key("guard"));
+    }
+
+    #[test]
+    fn a_pronoun_stands_for_the_last_npc_named() {
+        let mut game = hall_and_yard(
+            r#"
+            [[npc]]
+            id = "guard"
+            name = "guard"
+            start_room = "hall"
+
+            [[npc.dialogue]]
+            id = "hello"
+            response = "The guard nods."
+
+            [[item]]
+            id = "coin"
+            name = "coin"
+            start_location = "inventory"
+            "#,
+        );
+
+        run(&mut game, "talk to the guard");
+        run(&mut game, "give coin to him");
+        assert_eq!(
+            game.item_locations.get("coin"),
+            Some(&ItemLocation::Npc("guard".into()))
+        );
+
+        game.npc_locations.insert("guard".into(), "yard".into());
+        assert_eq!(run(&mut game, "talk to him"), "They're not here anymore.");
+    }
+
+    #[test]
+    fn npcs_here_are_described_with_their_idle_line() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "alarm"
+            verbs = ["pull"]
+            nouns = ["alarm"]
+            response = "A bell clangs."
+            effects = ["alarm"]
+
+            [[npc]]
+            id = "guard"
+            name = "guard"
+            start_room = "hall"
+            room_text = "A guard leans on his spear."
+
+            [[npc.idle]]
+            conditions = ["alarm"]
+            text = "The guard paces nervously."
+            "#,
+        );
+
+        let said = run(&mut game, "look");
+        assert!(said.contains("A guard leans on his spear."));
+        assert!(!said.contains("paces"));
+
+        run(&mut game, "pull alarm");
+        let said = run(&mut game, "look");
+        assert!(said.contains("A guard leans on his spear."));
+        assert!(said.contains("The guard paces nervously."));
+    }
+
+    #[test]
+    fn npc_visibility_follows_its_conditions() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "summon"
+            verbs = ["ring"]
+            nouns = ["bell"]
+            response = "You ring the bell."
+            effects = ["summoned"]
+
+            [[npc]]
+            id = "butler"
+            name = "butler"
+            start_room = "hall"
+            conditions = ["summoned"]
+
+            [[npc]]
+            id = "cat"
+            name = "cat"
+            start_room = "hall"
+            "#,
+        );
+
+        let said = run(&mut game, "look");
+        assert!(said.contains("A cat is here."));
+        assert!(!said.contains("butler"));
+
+        run(&mut game, "ring bell");
+        assert!(run(&mut game, "look").contains("A butler is here."));
+    }
+
+    #[test]
+    fn calling_brings_an_npc_from_the_next_room() {
+        let mut game = hall_and_yard(
+            r#"
+            [[npc]]
+            id = "guard"
+            name = "guard"
+            start_room = "yard"
+            responds_to_call = true
+            comes_when_called = true
+
+            [[npc]]
+            id = "hermit"
+            name = "hermit"
+            start_room = "nowhere_near"
+            responds_to_call = true
+
+            [[room]]
+            id = "nowhere_near"
+            name = "Cave"
+            desc = "A cave."
+            "#,
+        );
+
+        assert!(run(&mut game, "call guard").contains("The guard comes at your call."));
+        assert_eq!(game.npc_locations["guard"], "hall");
+        assert_eq!(run(&mut game, "call hermit"), "No one answers.");
     }
-    h
 }
@@ -6,11 +6,29 @@ pub enum OutputBlock {
     Text(String),
     Event(String),
     Exits(String),
+    /// The same exits as the `Exits` line, one direction each, for front ends that
+    /// draw them as buttons. Always directly before `Exits`; terminals skip it.
+    ExitsList(Vec<String>),
 }
 
+/// Items a command could not tell apart; the next command may pick one.
+#[derive(Debug, Clone)]
+pub struct Choice {
+    /// The words that matched them all, as the command used them.
+    pub query: String,
+    /// What to use instead of `query` to get each item ("brass key", "first torch").
+    pub options: Vec<String>,
+}
+
+/// Everything one command produced. Blocks always come in three runs, whatever
+/// order they were added in: titles and text first, then events, then the exits.
+/// So a room described after a global condition fired still comes before the
+/// event, and front ends can rely on events never being followed by plain text.
 #[derive(Default, Debug, Serialize)]
 pub struct Output {
     pub blocks: Vec<OutputBlock>,
+    #[serde(skip)]
+    pub choice: Option<Choice>,
 }
 
 impl Output {
@@ -24,15 +42,7 @@ impl Output {
             return;
         }
 
-        if let Some(pos) = self
-            .blocks
-            .iter()
-            .position(|b| matches!(b, OutputBlock::Exits(_)))
-        {
-            self.blocks.insert(pos, OutputBlock::Title(s));
-        } else {
-            self.blocks.push(OutputBlock::Title(s));
-        }
+        self.insert_text_block(OutputBlock::Title(s));
     }
 
     pub fn say(&mut self, s: impl Into<String>) {
@@ -41,15 +51,7 @@ impl Output {
             return;
         }
 
-        if let Some(pos) = self
-            .blocks
-            .iter()
-            .position(|b| matches!(b, OutputBlock::Exits(_)))
-        {
-            self.blocks.insert(pos, OutputBlock::Text(s));
-        } else {
-            self.blocks.push(OutputBlock::Text(s));
-        }
+        self.insert_text_block(OutputBlock::Text(s));
     }
 
     pub fn event(&mut self, s: impl Into<String>) {
@@ -59,25 +61,84 @@ impl Output {
         }
 
         // If Exits is already present, keep it last by inserting before it.
-        if let Some(pos) = self
-            .blocks
-            .iter()
-            .position(|b| matches!(b, OutputBlock::Exits(_)))
-        {
+        if let Some(pos) = self.exits_position() {
             self.blocks.insert(pos, OutputBlock::Event(s));
         } else {
             self.blocks.push(OutputBlock::Event(s));
         }
     }
 
-    pub fn set_exits(&mut self, s: impl Into<String>) {
-        let s = s.into();
-        if s.trim().is_empty() {
-            return;
+    /// Set the room's exits: "Exits: north, south" (or "Exits: (none)") plus the
+    /// structured list.
+    pub fn set_exits(&mut self, directions: Vec<String>) {
+        let line = if directions.is_empty() {
+            "Exits: (none)".to_string()
+        } else {
+            format!("Exits: {}", directions.join(", "))
+        };
+
+        // ensure only one pair of exit blocks exists, always last
+        self.blocks
+            .retain(|b| !matches!(b, OutputBlock::Exits(_) | OutputBlock::ExitsList(_)));
+        self.blocks.push(OutputBlock::ExitsList(directions));
+        self.blocks.push(OutputBlock::Exits(line));
+    }
+
+    /// Add a title or text block at the end of the text run, before any events.
+    fn insert_text_block(&mut self, block: OutputBlock) {
+        let events_or_exits = self.blocks.iter().position(|b| {
+            matches!(
+                b,
+                OutputBlock::Event(_) | OutputBlock::Exits(_) | OutputBlock::ExitsList(_)
+            )
+        });
+        match events_or_exits {
+            Some(pos) => self.blocks.insert(pos, block),
+            None => self.blocks.push(block),
+        }
+    }
+
+    /// Where the exit blocks start, if they are present.
+    fn exits_position(&self) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|b| matches!(b, OutputBlock::Exits(_) | OutputBlock::ExitsList(_)))
+    }
+
+    /// Flatten into the lines a terminal front end prints, blank separators included.
+    /// Titles and exits are preceded by a blank line; the first event is separated
+    /// from any earlier output by one.
+    pub fn to_lines(&self) -> Vec<String> {
+        self.to_styled_lines(|_, line| line.to_string())
+    }
+
+    /// Like `to_lines`, but each non-blank line goes through `style` along with the
+    /// block it came from (e.g. to bold titles).
+    pub fn to_styled_lines(&self, style: impl Fn(&OutputBlock, &str) -> String) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        let mut started_events = false;
+
+        for block in &self.blocks {
+            let text = match block {
+                OutputBlock::Title(t) | OutputBlock::Exits(t) => {
+                    lines.push(String::new());
+                    t
+                }
+                OutputBlock::Text(t) => t,
+                OutputBlock::ExitsList(_) => continue,
+                OutputBlock::Event(ev) => {
+                    if !started_events {
+                        if !lines.is_empty() {
+                            lines.push(String::new()); // visual separation before first event
+                        }
+                        started_events = true;
+                    }
+                    ev
+                }
+            };
+            lines.extend(text.lines().map(|line| style(block, line)));
         }
 
-        // ensure only one Exits block exists, always last
-        self.blocks.retain(|b| !matches!(b, OutputBlock::Exits(_)));
-        self.blocks.push(OutputBlock::Exits(s));
+        lines
     }
 }
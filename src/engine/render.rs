@@ -1,53 +1,76 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
-use crate::engine::conditions::conditions_met;
+use crate::engine::conditions::{EvalContext, conditions_met};
+use crate::engine::helpers::exit_known_flag;
+use crate::engine::locations::ItemLocations;
 use crate::engine::output::Output;
 use crate::world;
 
+/// How much of a room to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomDetail {
+    /// Title, description, items, NPCs and exits.
+    Full,
+    /// Everything except the room's own description (revisits in brief mode).
+    Brief,
+    /// Title and exits only.
+    NameOnly,
+}
+
 pub fn render_room(
     out: &mut Output,
     room: &world::Room,
-    flags: &HashSet<String>,
+    ctx: &EvalContext,
+    world: &world::World,
+    npc_locations: &HashMap<String, String>,
+) {
+    render_room_detail(out, room, ctx, world, npc_locations, RoomDetail::Full);
+}
+
+pub fn render_room_detail(
+    out: &mut Output,
+    room: &world::Room,
+    ctx: &EvalContext,
     world: &world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
     npc_locations: &HashMap<String, String>,
+    detail: RoomDetail,
 ) {
     use world::ItemLocation;
 
-    let mut room_desc = String::new();
+    // Each source (base desc, state descs, item and NPC mentions) is one part
+    let mut parts: Vec<Cow<'_, str>> = Vec::new();
 
-    out.title(room.name.clone());
+    out.title(room_display_name(room, ctx));
 
-    room_desc.push_str(room.desc.trim());
+    if detail == RoomDetail::NameOnly {
+        render_exits(out, room, ctx);
+        return;
+    }
+
+    if detail == RoomDetail::Full {
+        parts.push(room.desc.trim().into());
 
-    for state_desc in &room.state_descs {
-        if conditions_met(&state_desc.conditions, flags) {
-            let txt = state_desc.text.trim();
-            if !txt.is_empty() {
-                if !room_desc.is_empty() {
-                    room_desc.push(' ');
-                }
-                room_desc.push_str(txt);
+        for state_desc in &room.state_descs {
+            if conditions_met(&state_desc.conditions, ctx) {
+                parts.push(state_desc.text.trim().into());
             }
         }
     }
 
-    for item in world.items.values() {
-        let loc = match item_locations.get(&item.id) {
-            Some(l) => l,
-            None => continue,
-        };
-
-        if let ItemLocation::Room(room_id) = loc {
-            if room_id == &room.id && conditions_met(&item.conditions, flags) {
-                let txt = item.room_text.trim();
-                if !txt.is_empty() {
-                    if !room_desc.is_empty() {
-                        room_desc.push(' ');
-                    }
-                    room_desc.push_str(txt);
-                }
-            }
+    // Items and NPCs are mentioned in the order the world file lists them
+    for item in ctx
+        .item_locations
+        .items_at(world, &ItemLocation::Room(room.id.clone()))
+    {
+        if conditions_met(&item.conditions, ctx) {
+            let txt = item
+                .room_state_texts
+                .iter()
+                .find(|rs| conditions_met(&rs.conditions, ctx))
+                .map(|rs| rs.text.trim())
+                .unwrap_or_else(|| item.room_text.trim());
+            parts.push(txt.into());
         }
     }
 
@@ -57,60 +80,135 @@ pub fn render_room(
             None => continue,
         };
 
-        if npc_room == &room.id && conditions_met(&npc.conditions, flags) {
-            let txt = npc.room_text.trim();
-            if !txt.is_empty() {
-                if !room_desc.is_empty() {
-                    room_desc.push(' ');
-                }
-                room_desc.push_str(txt);
+        if npc_room == &room.id && conditions_met(&npc.conditions, ctx) {
+            // An NPC without room_text still gets mentioned, or players would never
+            // know it's there.
+            match npc.room_text.trim() {
+                "" => parts.push(format!("A {} is here.", npc.name).into()),
+                txt => parts.push(txt.into()),
+            }
+            if let Some(idle) = npc
+                .idle_texts
+                .iter()
+                .find(|idle| conditions_met(&idle.conditions, ctx))
+            {
+                parts.push(idle.text.trim().into());
             }
         }
     }
 
-    out.say(room_desc);
+    parts.retain(|p| !p.is_empty());
 
-    let visible_exits: Vec<&world::Exit> = room
+    if world.structured_room_text {
+        for part in parts {
+            out.say(part);
+        }
+    } else if detail == RoomDetail::Full || !parts.is_empty() {
+        out.say(parts.join(" "));
+    }
+
+    render_exits(out, room, ctx);
+}
+
+fn render_exits(out: &mut Output, room: &world::Room, ctx: &EvalContext) {
+    let mut dirs: Vec<String> = room
         .exits
         .iter()
-        .filter(|e| conditions_met(&e.conditions, flags))
+        .filter(|e| conditions_met(&e.conditions, ctx))
+        .filter(|e| !e.secret || ctx.flags.contains(&exit_known_flag(&room.id, &e.direction)))
+        .map(|e| e.direction.clone())
         .collect();
+    dirs.sort();
+    dirs.dedup();
+
+    out.set_exits(dirs);
+}
 
-    if visible_exits.is_empty() {
-        out.set_exits("Exits: (none)");
-    } else {
-        let mut dirs: Vec<&String> = visible_exits.iter().map(|e| &e.direction).collect();
-        dirs.sort();
-        dirs.dedup();
-        let list = dirs
-            .into_iter()
-            .map(|d| d.as_str())
-            .collect::<Vec<&str>>()
-            .join(", ");
-        out.set_exits(format!("Exits: {}", list));
+/// "objects": list what the parser recognizes here — visible items, NPCs, and the
+/// nouns of room actions currently available (features such as a rug or a lever).
+pub fn render_affordances(
+    out: &mut Output,
+    room: &world::Room,
+    ctx: &EvalContext,
+    world: &world::World,
+    npc_locations: &HashMap<String, String>,
+) {
+    // Only items that can be taken count as items; fixed ones are features
+    let (mut items, fixed): (Vec<&world::Item>, Vec<&world::Item>) = world
+        .items
+        .values()
+        .filter(|item| {
+            matches!(
+                ctx.item_locations.get(&item.id),
+                Some(world::ItemLocation::Room(room_id)) if room_id == &room.id
+            ) && conditions_met(&item.conditions, ctx)
+        })
+        .partition(|item| item.portable);
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+    let items: Vec<&str> = items.into_iter().map(|item| item.name.as_str()).collect();
+
+    let mut npcs: Vec<&str> = world
+        .npcs
+        .values()
+        .filter(|npc| {
+            npc_locations.get(&npc.id) == Some(&room.id) && conditions_met(&npc.conditions, ctx)
+        })
+        .map(|npc| npc.name.as_str())
+        .collect();
+    npcs.sort();
+
+    let mut features: Vec<&str> = room
+        .actions
+        .iter()
+        .filter(|a| conditions_met(&a.conditions, ctx))
+        .filter_map(|a| a.nouns.first().map(|n| n.trim()))
+        .filter(|n| !n.is_empty() && !items.contains(n) && !npcs.contains(n))
+        .chain(fixed.iter().map(|item| item.name.as_str()))
+        .collect();
+    features.sort();
+    features.dedup();
+
+    if items.is_empty() && npcs.is_empty() && features.is_empty() {
+        out.say("There's nothing of note here.");
+        return;
+    }
+
+    for (label, names) in [("Items", items), ("People", npcs), ("Features", features)] {
+        if !names.is_empty() {
+            out.say(format!("{}: {}.", label, names.join(", ")));
+        }
     }
 }
 
+/// Room name as currently shown: the last matching `name_state`, else `room.name`.
+fn room_display_name(room: &world::Room, ctx: &EvalContext) -> String {
+    room.name_states
+        .iter()
+        .rev()
+        .find(|ns| conditions_met(&ns.conditions, ctx))
+        .map(|ns| ns.text.clone())
+        .unwrap_or_else(|| room.name.clone())
+}
+
 pub fn room_depends_on_any_flag(
     room: &world::Room,
     world: &world::World,
-    item_locations: &HashMap<String, world::ItemLocation>,
+    item_locations: &ItemLocations,
     npc_locations: &HashMap<String, String>,
     flags_changed: &HashSet<String>,
+    items_moved: &HashSet<String>,
 ) -> bool {
     use world::{ItemKind, ItemLocation};
 
-    // Helper: does any condition string mention a changed flag (with or without '!')?
-    fn conds_touch_changed(conds: &[String], changed: &HashSet<String>) -> bool {
+    // Helper: does any condition test a changed flag (present or absent), or
+    // where a moved item is or what it holds?
+    let conds_touch_changed = |conds: &[world::Condition], changed: &HashSet<String>| {
         conds.iter().any(|c| {
-            let t = c.trim();
-            if t.is_empty() {
-                return false;
-            }
-            let name = t.trim_start_matches('!').trim();
-            !name.is_empty() && changed.contains(name)
+            c.flag().is_some_and(|name| changed.contains(name))
+                || c.tested_item()
+                    .is_some_and(|item| items_moved.contains(item))
         })
-    }
+    };
 
     // room.state_desc conditions
     for sd in &room.state_descs {
@@ -119,9 +217,18 @@ pub fn room_depends_on_any_flag(
         }
     }
 
-    // exit conditions
+    // room.name_state conditions
+    for ns in &room.name_states {
+        if conds_touch_changed(&ns.conditions, flags_changed) {
+            return true;
+        }
+    }
+
+    // exit conditions, and secret exits becoming known
     for ex in &room.exits {
-        if conds_touch_changed(&ex.conditions, flags_changed) {
+        if conds_touch_changed(&ex.conditions, flags_changed)
+            || (ex.secret && flags_changed.contains(&exit_known_flag(&room.id, &ex.direction)))
+        {
             return true;
         }
     }
@@ -130,48 +237,213 @@ pub fn room_depends_on_any_flag(
     // - direct room items whose own conditions touch changed flags
     // - containers in the room whose container-conditions touch changed flags
     // - items inside those containers whose own conditions touch changed flags
-    for item in world.items.values() {
-        match item_locations.get(&item.id) {
-            Some(ItemLocation::Room(room_id)) if room_id == &room.id => {
-                // direct item visibility
-                if conds_touch_changed(&item.conditions, flags_changed) {
-                    return true;
-                }
-
-                // if it's a container in this room, its "open/closed" gating may depend on flags
-                if let ItemKind::Container(props) = &item.kind {
-                    if conds_touch_changed(&props.conditions, flags_changed) {
-                        return true;
-                    }
-
-                    // contents whose visibility conditions depend on changed flags
-                    for inner in world.items.values() {
-                        match item_locations.get(&inner.id) {
-                            Some(ItemLocation::Item(parent_id)) if parent_id == &item.id => {
-                                if conds_touch_changed(&inner.conditions, flags_changed) {
-                                    return true;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                }
+    for item in item_locations.items_at(world, &ItemLocation::Room(room.id.clone())) {
+        // direct item visibility
+        if conds_touch_changed(&item.conditions, flags_changed) {
+            return true;
+        }
+
+        // state-dependent room text
+        if item
+            .room_state_texts
+            .iter()
+            .any(|rs| conds_touch_changed(&rs.conditions, flags_changed))
+        {
+            return true;
+        }
+
+        // if it's a container in this room, its "open/closed" gating may depend on flags
+        if let ItemKind::Container(props) = &item.kind {
+            if conds_touch_changed(&props.conditions, flags_changed) {
+                return true;
+            }
+
+            // contents whose visibility conditions depend on changed flags
+            if item_locations
+                .items_at(world, &ItemLocation::Item(item.id.clone()))
+                .iter()
+                .any(|inner| conds_touch_changed(&inner.conditions, flags_changed))
+            {
+                return true;
             }
-            _ => {}
         }
     }
 
-    // NPC visibility in this room depends on flags?
+    // NPC visibility (or its idle line) in this room depends on flags?
     for npc in world.npcs.values() {
-        match npc_locations.get(&npc.id) {
-            Some(room_id) if room_id == &room.id => {
-                if conds_touch_changed(&npc.conditions, flags_changed) {
-                    return true;
-                }
-            }
-            _ => {}
+        if npc_locations.get(&npc.id) != Some(&room.id) {
+            continue;
+        }
+        if conds_touch_changed(&npc.conditions, flags_changed)
+            || npc
+                .idle_texts
+                .iter()
+                .any(|idle| conds_touch_changed(&idle.conditions, flags_changed))
+        {
+            return true;
         }
     }
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::OutputBlock;
+    use crate::testing::{hall, hall_with, run};
+
+    #[test]
+    fn room_text_follows_a_toggled_flag() {
+        let mut game = hall(
+            r#"
+            [[room.state_desc]]
+            conditions = ["lamp_on"]
+            text = "Lamplight fills the room."
+
+            [[room.action]]
+            id = "switch_lamp"
+            verbs = ["switch"]
+            nouns = ["lamp"]
+            toggle_flag = "lamp_on"
+            response = "Click."
+            "#,
+        );
+
+        assert!(!run(&mut game, "look").contains("Lamplight"));
+        run(&mut game, "switch lamp");
+        assert!(run(&mut game, "look").contains("Lamplight fills the room."));
+        run(&mut game, "switch lamp");
+        assert!(!run(&mut game, "look").contains("Lamplight"));
+    }
+
+    #[test]
+    fn start_flags_apply_from_the_first_description() {
+        let mut game = hall_with(
+            r#"start_flags = ["storm"]"#,
+            r#"
+            [[room.state_desc]]
+            conditions = ["storm"]
+            text = "Rain lashes the windows."
+            "#,
+        );
+
+        let first = game.initialize().expect("start room");
+        assert!(
+            first.blocks.iter().any(
+                |b| matches!(b, OutputBlock::Text(t) if t.contains("Rain lashes the windows."))
+            )
+        );
+    }
+
+    const FURNISHED: &str = r#"
+        [[room.state_desc]]
+        conditions = ["storm"]
+        text = "Rain lashes the windows."
+
+        [[item]]
+        id = "lamp"
+        name = "lamp"
+        start_location = "room:hall"
+        room_text = "A lamp stands in the corner."
+
+        [[item]]
+        id = "chair"
+        name = "chair"
+        start_location = "room:hall"
+        room_text = "A chair faces the fire."
+    "#;
+
+    fn texts(blocks: &[OutputBlock]) -> Vec<&str> {
+        blocks
+            .iter()
+            .filter_map(|b| match b {
+                OutputBlock::Text(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn structured_room_text_gives_a_block_per_source() {
+        let mut game = hall_with(
+            "structured_room_text = true\nstart_flags = [\"storm\"]",
+            FURNISHED,
+        );
+        let (out, _) = game.step("look");
+        assert_eq!(
+            texts(&out.blocks),
+            vec![
+                "A hall.",
+                "Rain lashes the windows.",
+                "A lamp stands in the corner.",
+                "A chair faces the fire.",
+            ]
+        );
+
+        let mut game = hall_with(r#"start_flags = ["storm"]"#, FURNISHED);
+        let (out, _) = game.step("look");
+        assert_eq!(texts(&out.blocks).len(), 1);
+    }
+
+    #[test]
+    fn items_are_described_in_file_order() {
+        let mut game = hall(FURNISHED);
+        for _ in 0..5 {
+            let said = run(&mut game, "look");
+            let lamp = said.find("lamp").expect("lamp described");
+            let chair = said.find("chair").expect("chair described");
+            assert!(lamp < chair);
+
+            let said = run(&mut game, "take all");
+            assert!(said.find("lamp") < said.find("chair"));
+            run(&mut game, "drop all");
+        }
+    }
+
+    #[test]
+    fn the_exits_list_names_each_direction() {
+        let mut game = crate::testing::game(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = "A hall."
+
+            [[room.exit]]
+            direction = "north"
+            target = "yard"
+
+            [[room.exit]]
+            direction = "down"
+            target = "cellar"
+
+            [[room.exit]]
+            direction = "east"
+            target = "yard"
+            conditions = ["door_open"]
+
+            [[room]]
+            id = "yard"
+            name = "Yard"
+            desc = "A yard."
+
+            [[room]]
+            id = "cellar"
+            name = "Cellar"
+            desc = "A cellar."
+            "#,
+        );
+
+        let (out, _) = game.step("look");
+        let exits = out.blocks.iter().find_map(|b| match b {
+            OutputBlock::ExitsList(dirs) => Some(dirs.clone()),
+            _ => None,
+        });
+        assert_eq!(exits, Some(vec!["down".to_string(), "north".to_string()]));
+    }
+}
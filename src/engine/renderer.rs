@@ -0,0 +1,43 @@
+use crate::engine::output::{Output, OutputBlock};
+
+/// Presents a turn's `Output` to the player. Front ends (terminal, TUI, HTML, ...)
+/// implement this over the shared `OutputBlock` semantics.
+pub trait OutputRenderer {
+    fn render(&mut self, out: &Output);
+}
+
+/// Plain terminal output, one line per `Output::to_lines` entry.
+#[derive(Debug, Default)]
+pub struct TerminalRenderer;
+
+impl OutputRenderer for TerminalRenderer {
+    fn render(&mut self, out: &Output) {
+        for line in out.to_lines() {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Terminal output with ANSI styling: bold titles, cyan events, dim exits.
+#[derive(Debug, Default)]
+pub struct AnsiRenderer;
+
+impl AnsiRenderer {
+    /// Style one line of a block; usable with `Output::to_styled_lines`.
+    pub fn style_line(block: &OutputBlock, line: &str) -> String {
+        match block {
+            OutputBlock::Title(_) => format!("\x1b[1m{}\x1b[0m", line),
+            OutputBlock::Event(_) => format!("\x1b[36m{}\x1b[0m", line),
+            OutputBlock::Exits(_) => format!("\x1b[2m{}\x1b[0m", line),
+            _ => line.to_string(),
+        }
+    }
+}
+
+impl OutputRenderer for AnsiRenderer {
+    fn render(&mut self, out: &Output) {
+        for line in out.to_styled_lines(Self::style_line) {
+            println!("{}", line);
+        }
+    }
+}
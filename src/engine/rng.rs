@@ -0,0 +1,97 @@
+/// Source of chance for roaming, attacks and anything else left to luck.
+///
+/// Each roll is for `key` (an NPC id) at `seed` (the turn it happens on).
+/// Deterministic implementations can hash them; truly random ones may ignore them.
+pub trait Rng {
+    /// A roll in 0..=99.
+    fn roll_percent(&mut self, seed: u64, key: &str) -> u64;
+
+    /// An index in 0..len (0 when len is 0).
+    fn pick_index(&mut self, seed: u64, key: &str, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        (self.roll_percent(seed, key) as usize * len / 100).min(len - 1)
+    }
+
+    /// Internal state, kept with checkpoints so a revert replays the same luck.
+    /// Stateless generators can leave the default.
+    fn state(&self) -> u64 {
+        0
+    }
+
+    /// Put back a value returned by `state`.
+    fn set_state(&mut self, _state: u64) {}
+}
+
+/// Default: a stable FNV-1a hash of the turn and key. The same turn and key always
+/// give the same roll, so a world plays out identically for the same commands.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FnvRng;
+
+impl FnvRng {
+    fn stable_hash_u64(turn_index: u64, s: &str) -> u64 {
+        // Simple stable hash: not cryptographic, just deterministic.
+        let mut h = 1469598103934665603u64 ^ turn_index;
+        for b in s.as_bytes() {
+            h ^= *b as u64;
+            h = h.wrapping_mul(1099511628211u64);
+        }
+        h
+    }
+}
+
+impl Rng for FnvRng {
+    fn roll_percent(&mut self, seed: u64, key: &str) -> u64 {
+        Self::stable_hash_u64(seed, key) % 100
+    }
+
+    fn pick_index(&mut self, seed: u64, key: &str, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        (Self::stable_hash_u64(seed.wrapping_add(999), key) % len as u64) as usize
+    }
+}
+
+/// Seedable pseudo-random generator (SplitMix64). Ignores the turn and key; seed it
+/// from the clock for genuinely varied play or from a constant for reproducible runs.
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        SeededRng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl Rng for SeededRng {
+    fn roll_percent(&mut self, _seed: u64, _key: &str) -> u64 {
+        self.next_u64() % 100
+    }
+
+    fn pick_index(&mut self, _seed: u64, _key: &str, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        (self.next_u64() % len as u64) as usize
+    }
+
+    fn state(&self) -> u64 {
+        self.state
+    }
+
+    fn set_state(&mut self, state: u64) {
+        self.state = state;
+    }
+}
@@ -4,15 +4,137 @@ pub mod world;
 use std::collections::{HashMap, HashSet};
 
 use engine::{
-    Output, handle_drop, handle_drop_all, handle_examine, handle_give_to_npc, handle_inventory,
+    ItemLocations, Output, RoomDetail, fire_drop_triggers, fire_take_triggers, handle_drop,
+    handle_drop_all, handle_examine, handle_examine_all, handle_give_to_npc, handle_inventory,
     handle_take, handle_take_all_from_container, handle_take_all_room, handle_take_from_container,
-    handle_take_from_npc, handle_talk_to_npc, render_room, roam_npcs_after_player_move,
-    room_depends_on_any_flag, try_handle_action, try_handle_container_store,
-    try_handle_global_action, try_handle_movement, try_handle_npc_action,
+    handle_take_from_npc, handle_talk_to_npc, parse_movement_sequence, render_affordances,
+    render_room, render_room_detail, roam_npcs_for_turn, room_depends_on_any_flag,
+    try_handle_action, try_handle_container_store, try_handle_easter_verb,
+    try_handle_global_action, try_handle_liquid, try_handle_look_in, try_handle_movement,
+    try_handle_npc_action, try_handle_read, try_handle_wear,
 };
 use world::{ItemLocation, World};
 
-pub use world::{load_world_from_file, load_world_from_str};
+pub use world::{
+    WorldLoadError, load_world_from_file, load_world_from_json_file, load_world_from_json_str,
+    load_world_from_str,
+};
+
+/// Called with a flag's name and whether it is now set; see `GameState::on_flag_change`.
+pub type FlagListener = Box<dyn FnMut(&str, bool)>;
+
+/// How much of a room is shown when the player walks into it ("look" is always full).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DescriptionMode {
+    /// Full description on every visit.
+    Verbose,
+    /// Full description on the first visit, then only what's in the room.
+    #[default]
+    Brief,
+    /// Only the room name and exits.
+    Superbrief,
+}
+
+/// Where an item stands relative to the player right now; see
+/// `GameState::item_accessibility`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accessibility {
+    /// Carried by the player.
+    InInventory,
+    /// Lying visible in the current room.
+    InRoom,
+    /// Inside an open container in the room or carried (nested ones too, if every
+    /// container around it is open).
+    InOpenContainerHere,
+    /// Inside a container in the room or carried, with a closed one around it.
+    InClosedContainerHere,
+    /// Held by a visible NPC in the current room.
+    HeldByNpcHere,
+    /// Somewhere else, or out of play.
+    Elsewhere,
+    /// Here, but its conditions (or those of something around it) keep it unseen.
+    Hidden,
+}
+
+/// How a finished game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOutcome {
+    Victory,
+    Defeat,
+}
+
+/// Everything a "revert" puts back: the state of play when the player last walked
+/// into one of the world's autosave_rooms.
+struct Checkpoint {
+    current_room_id: String,
+    flags: HashSet<String>,
+    fired_global_conditions: HashSet<String>,
+    fired_dialogues: HashSet<String>,
+    fired_drop_triggers: HashSet<String>,
+    fired_take_triggers: HashSet<String>,
+    fired_enter_triggers: HashSet<String>,
+    scored_events: HashSet<String>,
+    worn_items: HashSet<String>,
+    player_hp: Option<u64>,
+    action_cooldowns: engine::ActionCooldowns,
+    item_locations: ItemLocations,
+    item_liquids: engine::ItemLiquids,
+    item_spawn_turns: HashMap<String, u64>,
+    npc_locations: HashMap<String, String>,
+    turn_index: u64,
+    action_index: u64,
+    visited_rooms: HashSet<String>,
+    rng_state: u64,
+}
+
+/// A "Which do you mean?" waiting for the player's answer.
+pub struct PendingChoice {
+    /// The ambiguous command, lowercased, words separated by single spaces.
+    pub command: String,
+    pub choice: engine::Choice,
+}
+
+impl PendingChoice {
+    /// The command to run if `answer` picks one option: its number in the list
+    /// ("2") or words that fit only that option ("iron", "the second torch").
+    fn resolve(&self, answer: &str) -> Option<String> {
+        let answer = answer.trim().to_lowercase();
+        let options = &self.choice.options;
+
+        let picked = if let Ok(n) = answer.parse::<usize>() {
+            options.get(n.checked_sub(1)?)?
+        } else {
+            let words: Vec<&str> = answer.split_whitespace().filter(|w| *w != "the").collect();
+            if words.is_empty() {
+                return None;
+            }
+            let exact = words.join(" ");
+            let fits: Vec<&String> = options
+                .iter()
+                .filter(|o| {
+                    let option_words: Vec<&str> = o.split_whitespace().collect();
+                    words.iter().all(|w| option_words.contains(w))
+                })
+                .collect();
+            match fits.as_slice() {
+                [one] => *one,
+                _ => fits.into_iter().find(|o| **o == exact)?,
+            }
+        };
+
+        // Swap the ambiguous words for the chosen option
+        let padded = format!(" {} ", self.command);
+        let query = format!(" {} ", self.choice.query);
+        let at = padded.find(&query)?;
+        let rebuilt = format!(
+            "{} {} {}",
+            &padded[..at],
+            picked,
+            &padded[at + query.len()..]
+        );
+        Some(rebuilt.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+}
 
 pub struct GameState {
     pub world: World,
@@ -20,10 +142,36 @@ pub struct GameState {
     pub flags: HashSet<String>,
     pub fired_global_conditions: HashSet<String>,
     pub fired_dialogues: HashSet<String>,
-    pub item_locations: HashMap<String, ItemLocation>,
+    pub fired_drop_triggers: HashSet<String>,
+    pub fired_take_triggers: HashSet<String>,
+    pub fired_enter_triggers: HashSet<String>,
+    /// Ids of the carried items the player has put on.
+    pub worn_items: HashSet<String>,
+    /// Current health, when the world sets player_max_hp.
+    pub player_hp: Option<u64>,
+    /// Ids of the `[[score]]` entries already awarded; the score is their points.
+    pub scored_events: HashSet<String>,
+    /// Turn each action with a cooldown last fired.
+    pub action_cooldowns: engine::ActionCooldowns,
+    pub item_locations: ItemLocations,
+    /// Liquid each filled item holds ("bottle" -> "water"); empty items are absent.
+    pub item_liquids: engine::ItemLiquids,
+    pub item_spawn_turns: HashMap<String, u64>,
     pub npc_locations: HashMap<String, String>,
     pub turn_index: u64,
     pub action_index: u64,
+    pub outcome: Option<GameOutcome>,
+    pub description_mode: DescriptionMode,
+    pub visited_rooms: HashSet<String>,
+    pub rng: Box<dyn engine::Rng>,
+    /// NPC last talked to, given something or examined; "him"/"her"/"them" refer to it.
+    pub last_referenced_npc: Option<String>,
+    /// Set when the last command was ambiguous; the next one may answer it.
+    pub pending_choice: Option<PendingChoice>,
+    /// Registered with `on_flag_change`.
+    flag_listener: Option<FlagListener>,
+    /// Latest autosave, taken on entering one of the world's autosave_rooms.
+    checkpoint: Option<Checkpoint>,
 }
 
 #[cfg(feature = "wasm")]
@@ -39,6 +187,14 @@ mod wasm_bindings {
         quit: bool,
     }
 
+    #[derive(Serialize)]
+    struct WasmAbout {
+        name: String,
+        author: Option<String>,
+        version: Option<String>,
+        credits: Option<String>,
+    }
+
     #[wasm_bindgen]
     pub struct WasmGame {
         state: GameState,
@@ -74,6 +230,19 @@ mod wasm_bindings {
             }
         }
 
+        /// The world's name, author, version and credits (missing ones are null).
+        #[wasm_bindgen]
+        pub fn about(&self) -> JsValue {
+            let meta = self.state.world.metadata();
+            to_value(&WasmAbout {
+                name: meta.name.to_string(),
+                author: meta.author.map(str::to_string),
+                version: meta.version.map(str::to_string),
+                credits: meta.credits.map(str::to_string),
+            })
+            .unwrap_or(JsValue::NULL)
+        }
+
         /// Process a player command and return the resulting output blocks and quit flag.
         #[wasm_bindgen]
         pub fn step(&mut self, input: &str) -> JsValue {
@@ -92,39 +261,214 @@ mod wasm_bindings {
 
 impl GameState {
     pub fn new(world: World) -> Self {
-        let mut item_locations: HashMap<String, ItemLocation> = HashMap::new();
+        let mut item_locations: ItemLocations = ItemLocations::new();
+        let mut item_spawn_turns: HashMap<String, u64> = HashMap::new();
         for (id, item) in &world.items {
-            item_locations.insert(id.clone(), item.start_location.clone());
+            if let Some(loc) = &item.start_location {
+                item_locations.insert(id.clone(), loc.clone());
+                item_spawn_turns.insert(id.clone(), 0);
+            }
+        }
+
+        // The starting kit overrides each listed item's own start_location
+        for id in &world.start_inventory {
+            if world.items.contains_key(id) {
+                item_locations.insert(id.clone(), ItemLocation::Inventory);
+                item_spawn_turns.insert(id.clone(), 0);
+            }
         }
 
         let mut npc_locations: HashMap<String, String> = HashMap::new();
+        let mut flags: HashSet<String> = HashSet::new();
         for (id, npc) in &world.npcs {
             npc_locations.insert(id.clone(), npc.start_room.clone());
+            if let Some(disp) = &npc.disposition {
+                engine::set_npc_disposition(&mut flags, id, disp);
+            }
         }
 
+        let item_liquids: engine::ItemLiquids = world
+            .items
+            .iter()
+            .filter_map(|(id, item)| Some((id.clone(), item.start_liquid.clone()?)))
+            .collect();
+
+        engine::apply_effects(&mut flags, &world.start_flags);
+
         GameState {
+            player_hp: world.player_max_hp,
             world,
             current_room_id: String::new(),
-            flags: HashSet::new(),
+            flags,
             fired_global_conditions: HashSet::new(),
             fired_dialogues: HashSet::new(),
+            fired_drop_triggers: HashSet::new(),
+            fired_take_triggers: HashSet::new(),
+            fired_enter_triggers: HashSet::new(),
+            scored_events: HashSet::new(),
+            worn_items: HashSet::new(),
+            action_cooldowns: engine::ActionCooldowns::new(),
             item_locations,
+            item_liquids,
+            item_spawn_turns,
             npc_locations,
             turn_index: 0,
             action_index: 0,
+            outcome: None,
+            description_mode: DescriptionMode::default(),
+            visited_rooms: HashSet::new(),
+            rng: Box::new(engine::FnvRng),
+            last_referenced_npc: None,
+            pending_choice: None,
+            flag_listener: None,
+            checkpoint: None,
+        }
+    }
+
+    /// Call `listener(flag, now_set)` for every flag a command adds or removes,
+    /// whatever set it (effects, global conditions, dispositions).
+    /// Changes are reported once per command, after it has run, in flag-name
+    /// order; a flag set to what it already was is not reported. Replaces any
+    /// previous listener.
+    pub fn on_flag_change(&mut self, listener: FlagListener) {
+        self.flag_listener = Some(listener);
+    }
+
+    /// The game was lost but there is a checkpoint to go back to.
+    fn can_revert(&self) -> bool {
+        self.outcome == Some(GameOutcome::Defeat) && self.checkpoint.is_some()
+    }
+
+    /// The flags, turn, item locations and liquids that conditions are checked against.
+    fn eval_context(&self) -> engine::EvalContext<'_> {
+        engine::EvalContext::new(
+            &self.flags,
+            self.turn_index,
+            &self.item_locations,
+            &self.item_liquids,
+        )
+    }
+
+    fn notify_flag_changes(&mut self, before: &HashSet<String>) {
+        let Some(listener) = self.flag_listener.as_mut() else {
+            return;
+        };
+
+        let mut changes: Vec<(&str, bool)> = self
+            .flags
+            .difference(before)
+            .map(|f| (f.as_str(), true))
+            .chain(before.difference(&self.flags).map(|f| (f.as_str(), false)))
+            .collect();
+        changes.sort();
+
+        for (flag, set) in changes {
+            listener(flag, set);
+        }
+    }
+
+    /// Current disposition of an NPC ("friendly", "hostile", ...), if any.
+    pub fn npc_disposition(&self, npc_id: &str) -> Option<&str> {
+        engine::npc_disposition(&self.flags, npc_id)
+    }
+
+    /// Liquid currently held by an item ("water", ...), if any.
+    pub fn item_liquid(&self, item_id: &str) -> Option<&str> {
+        self.item_liquids.get(item_id).map(String::as_str)
+    }
+
+    /// Whether the player could get at an item right now, for hint systems, bots and
+    /// walkthrough checks. Does not change anything.
+    pub fn item_accessibility(&self, item_id: &str) -> Accessibility {
+        let Some(item) = self.world.items.get(item_id) else {
+            return Accessibility::Elsewhere;
+        };
+
+        match self.item_locations.get(item_id) {
+            Some(ItemLocation::Inventory) => Accessibility::InInventory,
+            Some(ItemLocation::Room(room_id)) if *room_id == self.current_room_id => {
+                if engine::item_visible(item, &self.eval_context()) {
+                    Accessibility::InRoom
+                } else {
+                    Accessibility::Hidden
+                }
+            }
+            Some(ItemLocation::Npc(npc_id)) => {
+                let here = self.npc_locations.get(npc_id) == Some(&self.current_room_id);
+                if !here {
+                    Accessibility::Elsewhere
+                } else if engine::npc_present(
+                    &self.world,
+                    &self.npc_locations,
+                    &self.eval_context(),
+                    &self.current_room_id,
+                    npc_id,
+                ) && engine::item_visible(item, &self.eval_context())
+                {
+                    Accessibility::HeldByNpcHere
+                } else {
+                    Accessibility::Hidden
+                }
+            }
+            Some(ItemLocation::Item(container_id)) => {
+                self.container_accessibility(item, container_id)
+            }
+            _ => Accessibility::Elsewhere,
+        }
+    }
+
+    /// `item_accessibility` for an item inside `container_id`: walk out through the
+    /// containers around it to where the outermost one is.
+    fn container_accessibility(&self, item: &world::Item, container_id: &str) -> Accessibility {
+        let mut hidden = !engine::item_visible(item, &self.eval_context());
+        let mut closed = false;
+        let mut current = container_id;
+
+        // Bounded walk up the container chain (guards against accidental cycles)
+        for _ in 0..=self.item_locations.len() {
+            let Some(container) = self.world.items.get(current) else {
+                return Accessibility::Elsewhere;
+            };
+            closed |= !engine::container_open(container, &self.eval_context());
+
+            match self.item_locations.get(current) {
+                Some(ItemLocation::Item(parent_id)) => {
+                    hidden |= !engine::item_visible(container, &self.eval_context());
+                    current = parent_id;
+                    continue;
+                }
+                Some(ItemLocation::Inventory) => {}
+                Some(ItemLocation::Room(room_id)) if *room_id == self.current_room_id => {
+                    hidden |= !engine::item_visible(container, &self.eval_context());
+                }
+                _ => return Accessibility::Elsewhere,
+            }
+
+            return if hidden {
+                Accessibility::Hidden
+            } else if closed {
+                Accessibility::InClosedContainerHere
+            } else {
+                Accessibility::InOpenContainerHere
+            };
         }
+
+        Accessibility::Elsewhere
     }
 
     pub fn initialize(&mut self) -> Option<Output> {
         self.current_room_id = self.world.start_room.clone();
+        self.visited_rooms.insert(self.current_room_id.clone());
+        if self.world.autosave_rooms.contains(&self.current_room_id) {
+            self.checkpoint = Some(self.snapshot());
+        }
         if let Some(room) = self.world.rooms.get(&self.current_room_id) {
             let mut out = Output::new();
             render_room(
                 &mut out,
                 room,
-                &self.flags,
+                &self.eval_context(),
                 &self.world,
-                &self.item_locations,
                 &self.npc_locations,
             );
             Some(out)
@@ -133,90 +477,614 @@ impl GameState {
         }
     }
 
+    fn snapshot(&self) -> Checkpoint {
+        Checkpoint {
+            current_room_id: self.current_room_id.clone(),
+            flags: self.flags.clone(),
+            fired_global_conditions: self.fired_global_conditions.clone(),
+            fired_dialogues: self.fired_dialogues.clone(),
+            fired_drop_triggers: self.fired_drop_triggers.clone(),
+            fired_take_triggers: self.fired_take_triggers.clone(),
+            fired_enter_triggers: self.fired_enter_triggers.clone(),
+            scored_events: self.scored_events.clone(),
+            worn_items: self.worn_items.clone(),
+            player_hp: self.player_hp,
+            action_cooldowns: self.action_cooldowns.clone(),
+            item_locations: self.item_locations.clone(),
+            item_liquids: self.item_liquids.clone(),
+            item_spawn_turns: self.item_spawn_turns.clone(),
+            npc_locations: self.npc_locations.clone(),
+            turn_index: self.turn_index,
+            action_index: self.action_index,
+            visited_rooms: self.visited_rooms.clone(),
+            rng_state: self.rng.state(),
+        }
+    }
+
+    /// "revert": go back to the latest checkpoint, even after the game was lost.
+    /// The checkpoint is kept, so the player can revert again.
+    fn revert_to_checkpoint(&mut self, out: &mut Output) {
+        let Some(cp) = &self.checkpoint else {
+            out.say(&self.world.messages.no_checkpoint);
+            return;
+        };
+
+        let flags_before = self.flag_listener.is_some().then(|| self.flags.clone());
+
+        self.current_room_id = cp.current_room_id.clone();
+        self.flags = cp.flags.clone();
+        self.fired_global_conditions = cp.fired_global_conditions.clone();
+        self.fired_dialogues = cp.fired_dialogues.clone();
+        self.fired_drop_triggers = cp.fired_drop_triggers.clone();
+        self.fired_take_triggers = cp.fired_take_triggers.clone();
+        self.fired_enter_triggers = cp.fired_enter_triggers.clone();
+        self.scored_events = cp.scored_events.clone();
+        self.worn_items = cp.worn_items.clone();
+        self.player_hp = cp.player_hp;
+        self.action_cooldowns = cp.action_cooldowns.clone();
+        self.item_locations = cp.item_locations.clone();
+        self.item_liquids = cp.item_liquids.clone();
+        self.item_spawn_turns = cp.item_spawn_turns.clone();
+        self.npc_locations = cp.npc_locations.clone();
+        self.turn_index = cp.turn_index;
+        self.action_index = cp.action_index;
+        self.visited_rooms = cp.visited_rooms.clone();
+        self.rng.set_state(cp.rng_state);
+        self.outcome = None;
+        self.last_referenced_npc = None;
+        self.pending_choice = None;
+
+        out.say(&self.world.messages.checkpoint_restored);
+        if let Some(room) = self.world.rooms.get(&self.current_room_id) {
+            render_room(
+                out,
+                room,
+                &self.eval_context(),
+                &self.world,
+                &self.npc_locations,
+            );
+        }
+
+        if let Some(before) = flags_before {
+            self.notify_flag_changes(&before);
+        }
+    }
+
+    /// Bookkeeping for a turn that passes: a successful move or a "wait".
+    fn advance_turn(&mut self) {
+        self.turn_index += 1;
+        roam_npcs_for_turn(
+            &self.world,
+            &mut self.npc_locations,
+            &mut self.item_locations,
+            &self.flags,
+            self.turn_index,
+            self.rng.as_mut(),
+        );
+    }
+
+    /// A move through a slow exit takes several turns; NPCs roam after each one.
+    fn advance_turns(&mut self, turns: u64) {
+        for _ in 0..turns {
+            self.advance_turn();
+        }
+    }
+
+    /// Render the room the player just arrived in, honoring the description mode,
+    /// then let the NPCs there react to the arrival.
+    fn render_current_room(&mut self, out: &mut Output) {
+        let first_visit = self.visited_rooms.insert(self.current_room_id.clone());
+
+        let detail = match self.description_mode {
+            DescriptionMode::Verbose => RoomDetail::Full,
+            DescriptionMode::Brief if first_visit => RoomDetail::Full,
+            DescriptionMode::Brief => RoomDetail::Brief,
+            DescriptionMode::Superbrief => RoomDetail::NameOnly,
+        };
+
+        if let Some(room) = self.world.rooms.get(&self.current_room_id) {
+            render_room_detail(
+                out,
+                room,
+                &self.eval_context(),
+                &self.world,
+                &self.npc_locations,
+                detail,
+            );
+        }
+
+        engine::fire_npc_enter_triggers(
+            out,
+            &self.world,
+            &self.npc_locations,
+            &self.current_room_id,
+            &mut self.flags,
+            self.turn_index,
+            &self.item_locations,
+            &self.item_liquids,
+            &mut self.fired_enter_triggers,
+        );
+    }
+
+    /// "about": the world's name and whichever of version, author and credits it sets.
+    fn render_about(&self, out: &mut Output) {
+        let world = &self.world;
+
+        match world.version.as_deref().map(str::trim) {
+            Some(v) if !v.is_empty() => out.say(format!("{} (version {})", world.name, v)),
+            _ => out.say(world.name.clone()),
+        }
+        if let Some(author) = &world.author {
+            out.say(format!("By {}", author.trim()));
+        }
+        if let Some(credits) = &world.credits {
+            out.say(credits.trim());
+        }
+    }
+
+    /// Read-only readout for "status" / "health": the turn count, out of the
+    /// world's turn_limit when it has one, then health when the world tracks it.
+    fn render_status(&self, out: &mut Output) {
+        match self.world.turn_limit {
+            Some(limit) => out.say(engine::fill_message(
+                &self.world.messages.turns_of_limit,
+                &[
+                    ("turns", &self.turn_index.to_string()),
+                    ("limit", &limit.to_string()),
+                ],
+            )),
+            None => out.say(engine::fill_message(
+                &self.world.messages.turns,
+                &[("turns", &self.turn_index.to_string())],
+            )),
+        }
+        if let (Some(hp), Some(max)) = (self.player_hp, self.world.player_max_hp) {
+            out.say(engine::fill_message(
+                &self.world.messages.health,
+                &[("hp", &hp.to_string()), ("max", &max.to_string())],
+            ));
+        }
+    }
+
+    /// Points earned so far from the world's `[[score]]` entries.
+    pub fn score(&self) -> u64 {
+        self.world
+            .score_events
+            .iter()
+            .filter(|sc| self.scored_events.contains(&sc.id))
+            .map(|sc| sc.points)
+            .sum()
+    }
+
+    /// Read-only readout for "score": the points earned out of the most there are,
+    /// with the title of the highest rank they reach when the world has ranks.
+    fn render_score(&self, out: &mut Output) {
+        let points = self.score();
+        let score = points.to_string();
+        let max = self
+            .world
+            .score_events
+            .iter()
+            .map(|sc| sc.points)
+            .sum::<u64>()
+            .to_string();
+        // Ranks are sorted by min_score at load, so the last one reached is the highest
+        let rank = self
+            .world
+            .ranks
+            .iter()
+            .rev()
+            .find(|r| r.min_score <= points);
+        match rank {
+            Some(rank) => out.say(engine::fill_message(
+                &self.world.messages.score_rank,
+                &[("score", &score), ("max", &max), ("rank", &rank.title)],
+            )),
+            None => out.say(engine::fill_message(
+                &self.world.messages.score,
+                &[("score", &score), ("max", &max)],
+            )),
+        }
+    }
+
+    /// Ids of the items in the current room and in the containers there, sorted, so
+    /// two calls can be compared to see whether the room's contents changed.
+    fn room_item_ids(&self) -> Vec<String> {
+        let room = ItemLocation::Room(self.current_room_id.clone());
+        let mut ids: Vec<String> = Vec::new();
+        for id in self.item_locations.ids_at(&room) {
+            ids.push(id.to_string());
+            ids.extend(
+                self.item_locations
+                    .ids_at(&ItemLocation::Item(id.to_string()))
+                    .map(str::to_string),
+            );
+        }
+        ids.sort();
+        ids
+    }
+
+    /// Execute a compound movement ("n n e", "go north twice") one move at a time.
+    /// Stops at the first move that is blocked or unavailable and reports progress.
+    /// Only the room reached is described, unless `show_each_room` asks for every one.
+    fn walk_movement_sequence(&mut self, out: &mut Output, moves: &[String], show_each_room: bool) {
+        let mut taken = 0usize;
+
+        for dir in moves {
+            let prev_room_id = self.current_room_id.clone();
+            let room = match self.world.rooms.get(&self.current_room_id) {
+                Some(r) => r,
+                None => break,
+            };
+
+            let mut turn_cost = 1;
+            let handled = try_handle_movement(
+                out,
+                &mut self.current_room_id,
+                &self.world,
+                room,
+                dir,
+                &self.npc_locations,
+                &mut self.flags,
+                self.turn_index,
+                &self.item_locations,
+                &self.item_liquids,
+                self.action_index,
+                self.rng.as_mut(),
+                &mut turn_cost,
+            );
+
+            if !handled {
+                out.say(engine::fill_message(
+                    &self.world.messages.cant_go,
+                    &[("direction", dir)],
+                ));
+                break;
+            }
+
+            if self.current_room_id == prev_room_id {
+                // blocked or ambiguous; the movement handler already explained why
+                break;
+            }
+
+            taken += 1;
+            self.advance_turns(turn_cost);
+            if show_each_room {
+                self.render_current_room(out);
+            }
+        }
+
+        if taken < moves.len() {
+            out.say(engine::fill_message(
+                &self.world.messages.moves_stopped,
+                &[
+                    ("taken", &taken.to_string()),
+                    ("total", &moves.len().to_string()),
+                ],
+            ));
+        }
+
+        if taken > 0 && !show_each_room {
+            self.render_current_room(out);
+        }
+    }
+
+    fn carried_item_ids(&self) -> HashSet<String> {
+        self.item_locations
+            .iter()
+            .filter(|(_, loc)| matches!(loc, ItemLocation::Inventory))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Swap an NPC pronoun in `query` for the name of the NPC last referred to, and
+    /// remember whichever NPC the query names. Returns None (after saying so) when
+    /// the pronoun's NPC is no longer here.
+    fn resolve_npc_query(&mut self, query: &str, out: &mut Output) -> Option<String> {
+        let mut query = query.to_string();
+
+        if engine::is_npc_pronoun(&query)
+            && let Some(npc_id) = &self.last_referenced_npc
+        {
+            let present = engine::npc_present(
+                &self.world,
+                &self.npc_locations,
+                &self.eval_context(),
+                &self.current_room_id,
+                npc_id,
+            );
+            if !present {
+                out.say(&self.world.messages.npc_gone);
+                return None;
+            }
+            query = self.world.npcs[npc_id].name.to_lowercase();
+        }
+
+        if let Some(npc_id) = engine::npc_id_for_query(
+            &self.world,
+            &self.npc_locations,
+            &self.eval_context(),
+            &self.current_room_id,
+            &query,
+        ) {
+            self.last_referenced_npc = Some(npc_id);
+        }
+
+        Some(query)
+    }
+
+    /// "goto <room>": walk the shortest known route to a room already visited.
+    fn goto_room(&mut self, out: &mut Output, target: &str) {
+        let target = target.trim();
+        let target = target.strip_prefix("the ").unwrap_or(target).trim();
+
+        // An exact id or name, else the one room whose name has all the words ("shed")
+        let known: Vec<&world::Room> = self
+            .world
+            .rooms
+            .values()
+            .filter(|room| self.visited_rooms.contains(&room.id))
+            .collect();
+        let exact = known.iter().find(|room| {
+            engine::eq_ignore_case(&room.id, target) || engine::eq_ignore_case(&room.name, target)
+        });
+        let by_words = || {
+            let words: Vec<String> = target.split_whitespace().map(str::to_lowercase).collect();
+            let mut fits = known.iter().filter(|room| {
+                let name = room.name.to_lowercase();
+                let name_words: Vec<&str> = name.split_whitespace().collect();
+                words.iter().all(|w| name_words.contains(&w.as_str()))
+            });
+            match (fits.next(), fits.next()) {
+                (Some(room), None) => Some(room),
+                _ => None,
+            }
+        };
+        let room_id = exact.or_else(by_words).map(|room| room.id.clone());
+
+        let room_id = match room_id {
+            Some(id) => id,
+            None => {
+                out.say(&self.world.messages.goto_unknown);
+                return;
+            }
+        };
+
+        if room_id == self.current_room_id {
+            out.say(&self.world.messages.goto_here);
+            return;
+        }
+
+        match engine::find_route(
+            &self.world,
+            &self.eval_context(),
+            &self.current_room_id,
+            &room_id,
+            &self.visited_rooms,
+        ) {
+            Some(route) => {
+                self.walk_movement_sequence(out, &route, self.world.goto_shows_each_room)
+            }
+            None => out.say(&self.world.messages.goto_no_route),
+        }
+    }
+
     /// Process a single player input; returns (output, quit?)
     pub fn step(&mut self, input: &str) -> (Output, bool) {
         let mut out = Output::new();
+
+        let command = input.trim().to_lowercase();
+        if !self.world.autosave_rooms.is_empty()
+            && matches!(command.as_str(), "revert" | "checkpoint")
+        {
+            self.revert_to_checkpoint(&mut out);
+            return (out, false);
+        }
+
+        if self.outcome.is_some() {
+            // A lost game can still be reverted, so keep the player around
+            if self.can_revert() && command != "quit" && command != "exit" {
+                out.say(&self.world.messages.game_over_revert);
+                return (out, false);
+            }
+            out.say(&self.world.messages.game_over);
+            return (out, true);
+        }
+
+        let room_at_start = self.current_room_id.clone();
+
+        // An answer to "Which do you mean?" reruns the ambiguous command with it;
+        // anything else drops the question
+        let answered = self
+            .pending_choice
+            .take()
+            .and_then(|pending| pending.resolve(input));
+        let input = answered.as_deref().unwrap_or(input);
+
+        let expanded = engine::expand_command_aliases(&self.world, input);
+        let input = expanded.as_deref().unwrap_or(input);
+
+        let compound = engine::expand_compound_verbs(&self.world, input);
+        let input = compound.as_deref().unwrap_or(input);
+
+        let flags_at_start = self.flag_listener.is_some().then(|| self.flags.clone());
+        let room_items_at_start = self
+            .world
+            .render_after_item_change
+            .then(|| self.room_item_ids());
+        self.item_locations.take_moved();
+        let mut changed_liquids: HashSet<String> = HashSet::new();
+
         let lower = input.to_lowercase();
         let mut quit = false;
         let mut rendered_room_this_turn = false;
         self.action_index = self.action_index.wrapping_add(1);
 
         if lower == "quit" || lower == "exit" {
-            out.say("Goodbye.");
+            out.say(&self.world.messages.goodbye);
             quit = true;
         } else if lower == "inventory" || lower == "i" {
-            handle_inventory(&mut out, &self.world, &self.item_locations);
+            handle_inventory(&mut out, &self.world, &self.eval_context());
+        } else if lower == "status" || lower == "health" {
+            self.render_status(&mut out);
+        } else if lower == "score"
+            && !(self.world.score_events.is_empty() && self.world.ranks.is_empty())
+        {
+            self.render_score(&mut out);
+        } else if lower == "about" || lower == "credits" || lower == "version" {
+            self.render_about(&mut out);
+        } else if self.world.show_affordances
+            && matches!(
+                lower.trim_end_matches('?'),
+                "objects" | "what can i do" | "what can i do here"
+            )
+        {
+            if let Some(room) = self.world.rooms.get(&self.current_room_id) {
+                render_affordances(
+                    &mut out,
+                    room,
+                    &self.eval_context(),
+                    &self.world,
+                    &self.npc_locations,
+                );
+            }
+        } else if lower == "wait" || lower == "z" {
+            out.say(&self.world.messages.time_passes);
+            self.advance_turn();
+        } else if lower == "verbose" {
+            self.description_mode = DescriptionMode::Verbose;
+            out.say(&self.world.messages.verbose_mode);
+        } else if lower == "brief" {
+            self.description_mode = DescriptionMode::Brief;
+            out.say(&self.world.messages.brief_mode);
+        } else if lower == "superbrief" {
+            self.description_mode = DescriptionMode::Superbrief;
+            out.say(&self.world.messages.superbrief_mode);
         } else {
             let mut parts = input.split_whitespace();
             let verb = parts.next().unwrap_or("");
             let rest = parts.collect::<Vec<&str>>().join(" ");
             let rest_lower = rest.to_lowercase();
 
-            if verb.eq_ignore_ascii_case("talk") || verb.eq_ignore_ascii_case("speak") {
-                if rest_lower.is_empty() {
-                    out.say("Talk to whom?");
+            // "put coin on the floor" is just "drop coin"
+            let floor_item = engine::floor_drop_item(
+                verb,
+                &rest_lower,
+                &self.world,
+                &self.current_room_id,
+                &self.eval_context(),
+            )
+            .map(str::to_string);
+            let (verb, rest, rest_lower) = match floor_item {
+                Some(item) => ("drop", item.clone(), item),
+                None => (verb, rest, rest_lower),
+            };
+
+            if try_handle_wear(
+                &mut out,
+                verb,
+                &rest_lower,
+                &self.world,
+                &engine::EvalContext::new(
+                    &self.flags,
+                    self.turn_index,
+                    &self.item_locations,
+                    &self.item_liquids,
+                ),
+                &mut self.worn_items,
+            ) {
+                // handled
+            } else if verb.eq_ignore_ascii_case("goto")
+                || (verb.eq_ignore_ascii_case("go") && rest_lower.starts_with("to "))
+            {
+                let target = if verb.eq_ignore_ascii_case("go") {
+                    &rest_lower["to ".len()..]
+                } else {
+                    rest_lower.as_str()
+                };
+                if target.trim().is_empty() {
+                    out.say(&self.world.messages.go_where);
                 } else {
+                    self.goto_room(&mut out, target);
+                }
+                rendered_room_this_turn = true;
+            } else if verb.eq_ignore_ascii_case("talk") || verb.eq_ignore_ascii_case("speak") {
+                if rest_lower.is_empty() {
+                    out.say(&self.world.messages.talk_to_whom);
+                } else if let Some(target) = self.resolve_npc_query(&rest_lower, &mut out) {
                     handle_talk_to_npc(
                         &mut out,
                         &self.world,
                         &self.npc_locations,
                         &self.current_room_id,
-                        &rest_lower,
+                        &target,
                         &mut self.flags,
+                        self.turn_index,
+                        &self.item_locations,
+                        &self.item_liquids,
                         &mut self.fired_dialogues,
                     );
                 }
             } else if verb.eq_ignore_ascii_case("give") {
                 if rest_lower.is_empty() {
-                    out.say("Give what to whom?");
+                    out.say(&self.world.messages.give_what_to_whom);
                 } else if let Some(idx) = rest_lower.rfind(" to ") {
                     let item_part = rest_lower[..idx].trim();
                     let npc_part = rest_lower[idx + " to ".len()..].trim();
 
                     if item_part.is_empty() || npc_part.is_empty() {
-                        out.say("I don't understand who you want to give that to.");
-                    } else {
+                        out.say(&self.world.messages.give_unclear);
+                    } else if let Some(npc_part) = self.resolve_npc_query(npc_part, &mut out) {
                         handle_give_to_npc(
                             &mut out,
                             &mut self.item_locations,
+                            &self.item_liquids,
                             &self.world,
                             &self.npc_locations,
                             &self.current_room_id,
                             item_part,
-                            npc_part,
+                            &npc_part,
                             &mut self.flags,
+                            self.turn_index,
+                            &mut self.action_cooldowns,
                         );
                     }
                 } else {
-                    out.say("Give it to whom?");
+                    out.say(&self.world.messages.give_to_whom);
                 }
             } else if verb.eq_ignore_ascii_case("take") || verb.eq_ignore_ascii_case("get") {
+                let carried_before = self.carried_item_ids();
+
                 if rest.is_empty() {
-                    out.say("Take what?");
+                    out.say(&self.world.messages.take_what);
                 } else if rest_lower == "all" {
                     handle_take_all_room(
                         &mut out,
                         &mut self.item_locations,
+                        &self.item_liquids,
                         &self.world,
                         &self.current_room_id,
                         &self.flags,
+                        self.turn_index,
                     );
                 } else if let Some(idx) = rest_lower.find(" from ") {
                     let item_part = rest_lower[..idx].trim();
                     let container_part = rest_lower[idx + " from ".len()..].trim();
 
                     if item_part.is_empty() || container_part.is_empty() {
-                        out.say("I don't understand what you want to take from where.");
+                        out.say(&self.world.messages.take_from_unclear);
                     } else {
                         let handled_npc = handle_take_from_npc(
                             &mut out,
                             &mut self.item_locations,
+                            &self.item_liquids,
                             &self.world,
                             &self.npc_locations,
                             &self.current_room_id,
                             item_part,
                             container_part,
                             &self.flags,
+                            self.turn_index,
                         );
 
                         if !handled_npc {
@@ -224,20 +1092,26 @@ impl GameState {
                                 handle_take_all_from_container(
                                     &mut out,
                                     &mut self.item_locations,
+                                    &self.item_liquids,
                                     &self.world,
                                     &self.current_room_id,
                                     container_part,
-                                    &self.flags,
+                                    &mut self.flags,
+                                    self.turn_index,
+                                    &mut self.action_cooldowns,
                                 );
                             } else {
                                 handle_take_from_container(
                                     &mut out,
                                     &mut self.item_locations,
+                                    &self.item_liquids,
                                     &self.world,
                                     &self.current_room_id,
                                     item_part,
                                     container_part,
-                                    &self.flags,
+                                    &mut self.flags,
+                                    self.turn_index,
+                                    &mut self.action_cooldowns,
                                 );
                             }
                         }
@@ -246,30 +1120,68 @@ impl GameState {
                     handle_take(
                         &mut out,
                         &mut self.item_locations,
+                        &self.item_liquids,
                         &self.world,
                         &self.current_room_id,
                         &rest_lower,
                         &self.flags,
+                        self.turn_index,
                     );
                 }
+
+                let taken: Vec<String> = self
+                    .world
+                    .items
+                    .keys()
+                    .filter(|id| {
+                        !carried_before.contains(*id)
+                            && matches!(self.item_locations.get(id), Some(ItemLocation::Inventory))
+                    })
+                    .cloned()
+                    .collect();
+                fire_take_triggers(
+                    &mut out,
+                    &self.world,
+                    &taken,
+                    &mut self.flags,
+                    self.turn_index,
+                    &self.item_locations,
+                    &self.item_liquids,
+                    &mut self.fired_take_triggers,
+                );
             } else if verb.eq_ignore_ascii_case("drop") {
                 if rest.is_empty() {
-                    out.say("Drop what?");
-                } else if rest_lower == "all" {
-                    handle_drop_all(
-                        &mut out,
-                        &mut self.item_locations,
-                        &self.world,
-                        &self.current_room_id,
-                    );
+                    out.say(&self.world.messages.drop_what);
                 } else {
-                    handle_drop(
-                        &mut out,
-                        &mut self.item_locations,
-                        &self.world,
-                        &self.current_room_id,
-                        &rest_lower,
-                    );
+                    let dropped = if rest_lower == "all" {
+                        handle_drop_all(
+                            &mut out,
+                            &mut self.item_locations,
+                            &self.world,
+                            &self.current_room_id,
+                        )
+                    } else {
+                        handle_drop(
+                            &mut out,
+                            &mut self.item_locations,
+                            &self.world,
+                            &self.current_room_id,
+                            &rest_lower,
+                        )
+                        .into_iter()
+                        .collect()
+                    };
+
+                    if let Some(room) = self.world.rooms.get(&self.current_room_id) {
+                        fire_drop_triggers(
+                            &mut out,
+                            &self.world,
+                            room,
+                            &dropped,
+                            &mut self.flags,
+                            &mut self.fired_drop_triggers,
+                        );
+                    }
                 }
             } else if verb.eq_ignore_ascii_case("examine")
                 || verb.eq_ignore_ascii_case("x")
@@ -282,26 +1194,61 @@ impl GameState {
                 };
 
                 if target.is_empty() {
-                    out.say("Examine what?");
-                } else {
+                    out.say(&self.world.messages.examine_what);
+                } else if target == "all" || target == "everything" {
+                    handle_examine_all(
+                        &mut out,
+                        &self.world,
+                        &self.current_room_id,
+                        &self.eval_context(),
+                    );
+                } else if let Some(target) = self.resolve_npc_query(target, &mut out) {
                     handle_examine(
                         &mut out,
                         &self.world,
-                        &self.item_locations,
                         &self.npc_locations,
                         &self.current_room_id,
-                        target,
-                        &self.flags,
+                        &target,
+                        &self.eval_context(),
+                        &self.worn_items,
+                        self.player_hp,
                     );
                 }
-            } else if try_handle_container_store(
+            } else if try_handle_look_in(
+                &mut out,
+                verb,
+                &rest_lower,
+                &self.world,
+                &self.current_room_id,
+                &self.eval_context(),
+            ) || try_handle_read(
+                &mut out,
+                verb,
+                &rest_lower,
+                &self.world,
+                &self.current_room_id,
+                &self.eval_context(),
+            ) || try_handle_liquid(
+                &mut out,
+                verb,
+                &rest_lower,
+                &self.world,
+                &self.item_locations,
+                &mut self.item_liquids,
+                &mut changed_liquids,
+                &self.current_room_id,
+                &self.flags,
+                self.turn_index,
+            ) || try_handle_container_store(
                 &mut out,
                 verb,
                 &rest_lower,
                 &mut self.item_locations,
+                &self.item_liquids,
                 &self.world,
                 &self.current_room_id,
                 &mut self.flags,
+                self.turn_index,
             ) {
                 // handled
             } else if let Some(current_room) = self.world.rooms.get(&self.current_room_id) {
@@ -309,16 +1256,24 @@ impl GameState {
                     render_room(
                         &mut out,
                         current_room,
-                        &self.flags,
+                        &self.eval_context(),
                         &self.world,
-                        &self.item_locations,
                         &self.npc_locations,
                     );
                     rendered_room_this_turn = true;
                 } else {
                     let prev_room_id = self.current_room_id.clone();
+                    let mut turn_cost = 1;
 
-                    if try_handle_movement(
+                    if let Some(moves) = parse_movement_sequence(
+                        &self.world,
+                        current_room,
+                        &self.npc_locations,
+                        &lower,
+                    ) {
+                        self.walk_movement_sequence(&mut out, &moves, false);
+                        rendered_room_this_turn = true;
+                    } else if try_handle_movement(
                         &mut out,
                         &mut self.current_room_id,
                         &self.world,
@@ -326,64 +1281,87 @@ impl GameState {
                         &lower,
                         &self.npc_locations,
                         &mut self.flags,
+                        self.turn_index,
+                        &self.item_locations,
+                        &self.item_liquids,
                         self.action_index,
+                        self.rng.as_mut(),
+                        &mut turn_cost,
                     ) {
                         let moved = self.current_room_id != prev_room_id;
 
                         if moved {
-                            self.turn_index += 1;
-                            roam_npcs_after_player_move(
-                                &self.world,
-                                &mut self.npc_locations,
-                                &self.flags,
-                                self.turn_index,
-                            );
-
-                            if let Some(room) = self.world.rooms.get(&self.current_room_id) {
-                                render_room(
-                                    &mut out,
-                                    room,
-                                    &self.flags,
-                                    &self.world,
-                                    &self.item_locations,
-                                    &self.npc_locations,
-                                );
-                                rendered_room_this_turn = true;
-                            }
-                        } else {
-                            rendered_room_this_turn = true;
+                            self.advance_turns(turn_cost);
+                            self.render_current_room(&mut out);
                         }
-                    } else if try_handle_npc_action(
-                        &mut out,
-                        input,
-                        &self.world,
-                        &mut self.item_locations,
-                        &self.npc_locations,
-                        &self.current_room_id,
-                        &mut self.flags,
-                    ) {
-                        // handled
-                    } else if try_handle_action(
-                        &mut out,
-                        current_room,
-                        input,
-                        &self.world,
-                        &self.item_locations,
-                        &self.current_room_id,
-                        &mut self.flags,
-                    ) {
-                        // handled
-                    } else if try_handle_global_action(
-                        &mut out,
-                        input,
-                        &self.world,
-                        &self.item_locations,
-                        &self.current_room_id,
-                        &mut self.flags,
-                    ) {
-                        // handled
+                        rendered_room_this_turn = true;
                     } else {
-                        out.say("I don't understand that command.");
+                        // "<verb> <target> with <instrument>" names the item being used
+                        let instrument = lower
+                            .find(" with ")
+                            .map(|idx| lower[idx + " with ".len()..].trim())
+                            .filter(|phrase| !phrase.is_empty());
+
+                        let handled = try_handle_npc_action(
+                            &mut out,
+                            input,
+                            instrument,
+                            &self.world,
+                            &mut self.item_locations,
+                            &self.item_liquids,
+                            &self.npc_locations,
+                            &self.current_room_id,
+                            &mut self.flags,
+                            self.turn_index,
+                            &mut self.action_cooldowns,
+                        ) || try_handle_action(
+                            &mut out,
+                            current_room,
+                            input,
+                            instrument,
+                            &self.world,
+                            &mut self.item_locations,
+                            &self.item_liquids,
+                            &self.current_room_id,
+                            &mut self.flags,
+                            self.turn_index,
+                            &mut self.action_cooldowns,
+                        ) || try_handle_global_action(
+                            &mut out,
+                            input,
+                            instrument,
+                            &self.world,
+                            &mut self.item_locations,
+                            &self.item_liquids,
+                            &self.current_room_id,
+                            &mut self.flags,
+                            self.turn_index,
+                            &mut self.action_cooldowns,
+                        ) || engine::try_handle_call(
+                            &mut out,
+                            input,
+                            &self.world,
+                            &mut self.npc_locations,
+                            &mut self.item_locations,
+                            &self.item_liquids,
+                            &self.current_room_id,
+                            &self.flags,
+                            self.turn_index,
+                        ) || try_handle_easter_verb(
+                            &mut out,
+                            input,
+                            &self.world,
+                            &self.eval_context(),
+                        );
+
+                        if !handled {
+                            let text = current_room
+                                .unknown_command_text
+                                .as_deref()
+                                .or(self.world.unknown_command_text.as_deref())
+                                .unwrap_or(&self.world.messages.unknown_command);
+                            out.say(text.trim());
+                        }
                     }
                 }
             } else {
@@ -397,14 +1375,60 @@ impl GameState {
 
         let flags_before = self.flags.clone();
 
+        engine::evaluate_item_decay(
+            &mut out,
+            &self.world,
+            &mut self.item_locations,
+            &mut self.item_spawn_turns,
+            &self.current_room_id,
+            self.turn_index,
+        );
+
+        // Every item move of the turn is done, whatever made it
+        engine::evaluate_container_completion(
+            &mut out,
+            &self.world,
+            &self.item_locations,
+            &mut self.flags,
+        );
+
+        // Whatever left the inventory is no longer worn
+        let item_locations = &self.item_locations;
+        self.worn_items
+            .retain(|id| matches!(item_locations.get(id), Some(ItemLocation::Inventory)));
+
         engine::evaluate_global_conditions(
             &mut out,
             &self.world,
             &mut self.flags,
+            self.turn_index,
+            &self.item_locations,
+            &self.item_liquids,
             &self.current_room_id,
             &mut self.fired_global_conditions,
         );
 
+        engine::evaluate_npc_deaths(
+            &mut out,
+            &self.world,
+            &self.flags,
+            &mut self.item_locations,
+            &mut self.npc_locations,
+            &self.current_room_id,
+        );
+
+        engine::evaluate_score_events(
+            &mut out,
+            &self.world,
+            &engine::EvalContext::new(
+                &self.flags,
+                self.turn_index,
+                &self.item_locations,
+                &self.item_liquids,
+            ),
+            &mut self.scored_events,
+        );
+
         let mut changed_flags: HashSet<String> = HashSet::new();
         for f in self.flags.difference(&flags_before) {
             changed_flags.insert(f.clone());
@@ -413,27 +1437,643 @@ impl GameState {
             changed_flags.insert(f.clone());
         }
 
-        if !changed_flags.is_empty() && !rendered_room_this_turn {
-            if let Some(room) = self.world.rooms.get(&self.current_room_id) {
-                if room_depends_on_any_flag(
-                    room,
-                    &self.world,
-                    &self.item_locations,
-                    &self.npc_locations,
-                    &changed_flags,
-                ) {
-                    render_room(
-                        &mut out,
+        let mut changed_items = self.item_locations.take_moved();
+        changed_items.extend(changed_liquids);
+
+        let room_items_changed = room_items_at_start.is_some_and(|before| {
+            self.current_room_id == room_at_start && before != self.room_item_ids()
+        });
+
+        if !rendered_room_this_turn
+            && let Some(room) = self.world.rooms.get(&self.current_room_id)
+            && (room_items_changed
+                || ((!changed_flags.is_empty() || !changed_items.is_empty())
+                    && room_depends_on_any_flag(
                         room,
-                        &self.flags,
                         &self.world,
                         &self.item_locations,
                         &self.npc_locations,
-                    );
-                }
-            }
+                        &changed_flags,
+                        &changed_items,
+                    )))
+        {
+            render_room(
+                &mut out,
+                room,
+                &self.eval_context(),
+                &self.world,
+                &self.npc_locations,
+            );
+        }
+
+        if let Some(ending) = engine::evaluate_endings(&self.world, &self.eval_context()) {
+            out.event(ending.text.trim());
+            self.outcome = Some(if ending.is_victory {
+                GameOutcome::Victory
+            } else {
+                GameOutcome::Defeat
+            });
+            quit = true;
+        }
+
+        if self.outcome.is_none()
+            && let Some(limit) = self.world.turn_limit
+            && self.turn_index > limit
+        {
+            let text = self
+                .world
+                .turn_limit_text
+                .as_deref()
+                .unwrap_or(&self.world.messages.out_of_time);
+            out.event(text.trim());
+            self.outcome = Some(GameOutcome::Defeat);
+            quit = true;
+        }
+
+        if self.outcome.is_none()
+            && self.current_room_id != room_at_start
+            && self.world.autosave_rooms.contains(&self.current_room_id)
+        {
+            self.checkpoint = Some(self.snapshot());
+            out.say(&self.world.messages.checkpoint_saved);
+        }
+
+        if self.can_revert() {
+            out.event(&self.world.messages.revert_hint);
+            quit = false;
+        }
+
+        if let Some(choice) = out.choice.take() {
+            self.pending_choice = Some(PendingChoice {
+                command: lower.split_whitespace().collect::<Vec<_>>().join(" "),
+                choice,
+            });
+        }
+
+        if let Some(before) = flags_at_start {
+            self.notify_flag_changes(&before);
         }
 
         (out, quit)
     }
 }
+
+/// Shared helpers for the unit tests: a game on an inline world, and the text a
+/// command printed.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+    use engine::OutputBlock;
+
+    /// A game on `world_toml`, already in its start room.
+    pub fn game(world_toml: &str) -> GameState {
+        let mut game =
+            GameState::new(load_world_from_str(world_toml).expect("test world should load"));
+        game.initialize()
+            .expect("test world should have its start room");
+        game
+    }
+
+    /// A game in a single room, "hall", with `world_fields` added to [world] and
+    /// `rest` (the hall's actions, then items, NPCs, ...) after the room.
+    pub fn hall_with(world_fields: &str, rest: &str) -> GameState {
+        game(&format!(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+            {}
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = "A hall."
+
+            {}
+            "#,
+            world_fields, rest
+        ))
+    }
+
+    pub fn hall(rest: &str) -> GameState {
+        hall_with("", rest)
+    }
+
+    /// Every block the command printed except the exit buttons, one per line.
+    pub fn run(game: &mut GameState, input: &str) -> String {
+        let (out, _) = game.step(input);
+        out.blocks
+            .iter()
+            .filter_map(|block| match block {
+                OutputBlock::Title(s)
+                | OutputBlock::Text(s)
+                | OutputBlock::Event(s)
+                | OutputBlock::Exits(s) => Some(s.as_str()),
+                OutputBlock::ExitsList(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::OutputBlock;
+    use crate::testing::{game, hall, hall_with, run};
+    use crate::{Accessibility, GameOutcome};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn revert_restores_the_rng() {
+        let mut game = game(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "gate"
+            autosave_rooms = ["yard"]
+
+            [[room]]
+            id = "gate"
+            name = "Gate"
+            desc = "A gate."
+
+            [[room.exit]]
+            direction = "north"
+            target = "yard"
+
+            [[room]]
+            id = "yard"
+            name = "Yard"
+            desc = "A yard."
+
+            [[npc]]
+            id = "cat"
+            name = "cat"
+            start_room = "gate"
+            roam_enabled = true
+            roam_rooms = ["gate", "yard"]
+            roam_chance_percent = 50
+            "#,
+        );
+        game.rng = Box::new(crate::engine::SeededRng::new(7));
+
+        run(&mut game, "north");
+        let saved = game.rng.state();
+        run(&mut game, "wait");
+        run(&mut game, "wait");
+        assert_ne!(game.rng.state(), saved);
+
+        run(&mut game, "revert");
+        assert_eq!(game.rng.state(), saved);
+    }
+
+    #[test]
+    fn turn_conditions_read_the_turn_count() {
+        let mut game = game(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = "A hall."
+
+            [[global_condition]]
+            id = "bell"
+            conditions = ["turn>=2"]
+            one_shot = true
+            response = "A bell rings."
+            "#,
+        );
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let seen = Rc::clone(&changes);
+        game.on_flag_change(Box::new(move |flag, set| {
+            seen.borrow_mut().push((flag.to_string(), set))
+        }));
+
+        assert!(!run(&mut game, "wait").contains("A bell rings."));
+        assert!(run(&mut game, "wait").contains("A bell rings."));
+        assert!(game.flags.iter().all(|f| !f.starts_with("turn")));
+        assert!(changes.borrow().is_empty());
+    }
+
+    #[test]
+    fn location_conditions_read_where_items_are() {
+        let mut game = game(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = "A hall."
+
+            [[item]]
+            id = "key"
+            name = "key"
+            desc = "A key."
+            start_location = "room:hall"
+
+            [[global_condition]]
+            id = "found"
+            conditions = ["at:key:inventory", "!at:key:room:hall"]
+            one_shot = true
+            response = "The key is warm."
+            effects = ["key_found"]
+            "#,
+        );
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let seen = Rc::clone(&changes);
+        game.on_flag_change(Box::new(move |flag, set| {
+            seen.borrow_mut().push((flag.to_string(), set))
+        }));
+
+        assert!(run(&mut game, "take key").contains("The key is warm."));
+        assert!(
+            game.flags
+                .iter()
+                .all(|f| !f.starts_with("at:") && !f.starts_with("in:"))
+        );
+        assert_eq!(*changes.borrow(), vec![("key_found".to_string(), true)]);
+    }
+
+    #[test]
+    fn parser_replies_come_from_messages() {
+        let mut game = game(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+
+            [messages]
+            talk_to_whom = "Parler à qui ?"
+            npc_nothing_to_say = "{npc} n'a rien à dire."
+            unknown_command = "Je ne comprends pas."
+            brief_mode = "Mode bref."
+            goto_unknown = "Connais pas."
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = "A hall."
+
+            [[npc]]
+            id = "guard"
+            name = "Guard"
+            desc = "A guard."
+            start_room = "hall"
+            "#,
+        );
+
+        assert_eq!(run(&mut game, "talk"), "Parler à qui ?");
+        assert_eq!(run(&mut game, "talk to guard"), "Guard n'a rien à dire.");
+        assert_eq!(run(&mut game, "give"), "Give what to whom?");
+        assert_eq!(run(&mut game, "dance wildly"), "Je ne comprends pas.");
+        assert_eq!(run(&mut game, "brief"), "Mode bref.");
+        assert_eq!(run(&mut game, "goto atlantis"), "Connais pas.");
+    }
+
+    #[test]
+    fn setting_the_won_flag_wins() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "win"
+            verbs = ["press"]
+            nouns = ["button"]
+            response = "Confetti falls."
+            effects = ["won"]
+
+            [[ending]]
+            id = "victory"
+            conditions = ["won"]
+            victory = true
+            text = "You have won."
+            "#,
+        );
+
+        let (out, quit) = game.step("press button");
+        assert!(quit);
+        assert!(
+            out.blocks
+                .iter()
+                .any(|b| matches!(b, OutputBlock::Event(t) if t == "You have won."))
+        );
+        assert_eq!(game.outcome, Some(GameOutcome::Victory));
+        assert_eq!(run(&mut game, "look"), "The game is over.");
+    }
+
+    #[test]
+    fn status_shows_the_turns_taken() {
+        let mut game = hall_with("turn_limit = 10", "");
+        run(&mut game, "wait");
+        run(&mut game, "wait");
+        assert_eq!(run(&mut game, "status"), "Turns: 2 of 10.");
+
+        let mut game = hall("");
+        run(&mut game, "wait");
+        assert_eq!(run(&mut game, "status"), "Turns: 1.");
+    }
+
+    #[test]
+    fn score_shows_the_points_and_the_rank_they_earn() {
+        let mut game = hall(
+            r#"
+            [[room.action]]
+            id = "find_idol"
+            verbs = ["search"]
+            nouns = ["floor"]
+            response = "You find an idol."
+            effects = ["idol_found"]
+
+            [[score]]
+            id = "idol"
+            conditions = ["idol_found"]
+            points = 10
+
+            [[score]]
+            id = "escape"
+            conditions = ["escaped"]
+            points = 15
+
+            [[rank]]
+            min_score = 10
+            title = "Treasure Hunter"
+
+            [[rank]]
+            min_score = 0
+            title = "Novice Adventurer"
+            "#,
+        );
+
+        assert_eq!(
+            run(&mut game, "score"),
+            "Your score is 0 of a possible 25, earning you the rank of Novice Adventurer."
+        );
+        assert!(run(&mut game, "search floor").contains("[Your score has gone up by 10.]"));
+        assert!(!run(&mut game, "search floor").contains("score"));
+        assert_eq!(game.score(), 10);
+        assert_eq!(
+            run(&mut game, "score"),
+            "Your score is 10 of a possible 25, earning you the rank of Treasure Hunter."
+        );
+    }
+
+    #[test]
+    fn running_out_of_turns_ends_the_game() {
+        let mut game = hall_with(
+            "turn_limit = 2\nturn_limit_text = \"The tide comes in.\"",
+            "",
+        );
+
+        assert!(!game.step("wait").1);
+        assert!(!game.step("wait").1);
+        assert!(run(&mut game, "wait").contains("The tide comes in."));
+        assert_eq!(game.outcome, Some(GameOutcome::Defeat));
+        assert!(game.step("wait").1);
+    }
+
+    #[test]
+    fn about_prints_the_world_credits() {
+        let mut game = hall_with(
+            "author = \"Jane Doe\"\nversion = \"1.2\"\ncredits = \"Thanks to the testers.\"",
+            "",
+        );
+
+        assert_eq!(
+            run(&mut game, "about"),
+            "T (version 1.2)\nBy Jane Doe\nThanks to the testers."
+        );
+        assert_eq!(game.turn_index, 0);
+    }
+
+    const TWO_ROOMS: &str = r#"
+        [world]
+        id = "t"
+        name = "T"
+        start_room = "hall"
+        {world}
+
+        [[room]]
+        id = "hall"
+        name = "Hall"
+        desc = "A hall."
+
+        [[room.exit]]
+        direction = "north"
+        target = "yard"
+
+        [[room]]
+        id = "yard"
+        name = "Yard"
+        desc = "A yard with a well."
+
+        [[room.exit]]
+        direction = "south"
+        target = "hall"
+
+        [[item]]
+        id = "bucket"
+        name = "bucket"
+        start_location = "room:yard"
+        room_text = "A bucket sits by the well."
+
+        {rest}
+    "#;
+
+    /// A hall with a yard to the north; `world_fields` go into [world] and `rest`
+    /// after the rooms.
+    fn two_rooms(world_fields: &str, rest: &str) -> crate::GameState {
+        game(
+            &TWO_ROOMS
+                .replace("{world}", world_fields)
+                .replace("{rest}", rest),
+        )
+    }
+
+    #[test]
+    fn description_modes_decide_what_a_revisit_shows() {
+        let mut game = two_rooms("", "");
+        assert!(run(&mut game, "north").contains("A yard with a well."));
+        run(&mut game, "south");
+        let said = run(&mut game, "north");
+        assert!(!said.contains("A yard with a well."));
+        assert!(said.contains("A bucket sits by the well."));
+
+        let mut game = two_rooms("", "");
+        run(&mut game, "verbose");
+        run(&mut game, "north");
+        run(&mut game, "south");
+        assert!(run(&mut game, "north").contains("A yard with a well."));
+
+        let mut game = two_rooms("", "");
+        run(&mut game, "superbrief");
+        let said = run(&mut game, "north");
+        assert!(said.contains("Yard"));
+        assert!(!said.contains("A yard with a well."));
+        assert!(!said.contains("bucket"));
+        assert!(run(&mut game, "look").contains("A yard with a well."));
+    }
+
+    #[test]
+    fn reverting_restores_the_checkpoint() {
+        let mut game = two_rooms(
+            r#"autosave_rooms = ["yard"]"#,
+            r#"
+            [[global_action]]
+            id = "jump"
+            verbs = ["jump"]
+            nouns = ["well"]
+            response = "You jump into the well."
+            effects = ["drowned"]
+
+            [[ending]]
+            id = "drowned"
+            conditions = ["drowned"]
+            text = "You drown."
+
+            [[item]]
+            id = "flask"
+            name = "flask"
+            start_location = "inventory"
+            holds_liquid = true
+            start_liquid = "wine"
+            "#,
+        );
+
+        assert!(run(&mut game, "north").contains("Checkpoint saved."));
+        run(&mut game, "take bucket");
+        run(&mut game, "drink flask");
+        assert_eq!(game.item_liquid("flask"), None);
+        run(&mut game, "jump in well");
+        assert_eq!(game.outcome, Some(GameOutcome::Defeat));
+
+        run(&mut game, "revert");
+        assert_eq!(game.outcome, None);
+        assert_eq!(game.current_room_id, "yard");
+        assert!(!game.flags.contains("drowned"));
+        assert_eq!(game.item_accessibility("bucket"), Accessibility::InRoom);
+        assert_eq!(game.item_liquid("flask"), Some("wine"));
+    }
+
+    #[test]
+    fn accessibility_covers_every_case() {
+        let game = hall(
+            r#"
+            [[npc]]
+            id = "merchant"
+            name = "merchant"
+            start_room = "hall"
+
+            [[item]]
+            id = "coin"
+            name = "coin"
+            start_location = "inventory"
+
+            [[item]]
+            id = "lamp"
+            name = "lamp"
+            start_location = "room:hall"
+
+            [[item]]
+            id = "box"
+            name = "box"
+            start_location = "room:hall"
+            kind = "container"
+
+            [[item]]
+            id = "ring"
+            name = "ring"
+            start_location = "item:box"
+
+            [[item]]
+            id = "safe"
+            name = "safe"
+            start_location = "room:hall"
+            kind = "container"
+            container_conditions = ["safe_open"]
+
+            [[item]]
+            id = "gold"
+            name = "gold"
+            start_location = "item:safe"
+
+            [[item]]
+            id = "sword"
+            name = "sword"
+            start_location = "npc:merchant"
+
+            [[item]]
+            id = "ghost"
+            name = "ghost"
+            start_location = "room:hall"
+            conditions = ["seen"]
+
+            [[item]]
+            id = "moon"
+            name = "moon"
+            start_location = "nowhere"
+            "#,
+        );
+
+        assert_eq!(game.item_accessibility("coin"), Accessibility::InInventory);
+        assert_eq!(game.item_accessibility("lamp"), Accessibility::InRoom);
+        assert_eq!(
+            game.item_accessibility("ring"),
+            Accessibility::InOpenContainerHere
+        );
+        assert_eq!(
+            game.item_accessibility("gold"),
+            Accessibility::InClosedContainerHere
+        );
+        assert_eq!(
+            game.item_accessibility("sword"),
+            Accessibility::HeldByNpcHere
+        );
+        assert_eq!(game.item_accessibility("ghost"), Accessibility::Hidden);
+        assert_eq!(game.item_accessibility("moon"), Accessibility::Elsewhere);
+        assert_eq!(game.item_accessibility("nothing"), Accessibility::Elsewhere);
+    }
+
+    #[test]
+    fn a_turn_prints_replies_then_events_then_exits() {
+        let mut game = two_rooms(
+            "",
+            r#"
+            [[global_condition]]
+            id = "crow"
+            conditions = []
+            allowed_rooms = ["yard"]
+            one_shot = true
+            response = "A crow caws."
+            "#,
+        );
+
+        let (out, _) = game.step("north");
+        let kinds: Vec<&str> = out
+            .blocks
+            .iter()
+            .map(|b| match b {
+                OutputBlock::Title(_) => "title",
+                OutputBlock::Text(_) => "text",
+                OutputBlock::Event(_) => "event",
+                OutputBlock::ExitsList(_) => "exits_list",
+                OutputBlock::Exits(_) => "exits",
+            })
+            .collect();
+        let event = kinds.iter().position(|k| *k == "event").expect("crow caws");
+        assert!(kinds[..event].contains(&"title"));
+        assert!(kinds[..event].iter().all(|k| *k == "title" || *k == "text"));
+        assert_eq!(kinds[event + 1..], ["exits_list", "exits"]);
+    }
+}
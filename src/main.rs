@@ -1,50 +1,201 @@
 use std::env;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
 
-use int_fic::{GameState, engine, load_world_from_file};
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
 
-fn flush_output(out: engine::Output) {
-    use engine::OutputBlock;
+use int_fic::engine::{AnsiRenderer, OutputRenderer, TerminalRenderer};
+use int_fic::{GameState, engine, load_world_from_file, load_world_from_json_file};
+
+/// Pauses with "--more--" every `page` lines.
+struct Pager {
+    page: usize,
+    ansi: bool,
+}
 
-    let mut printed_anything = false;
-    let mut started_events = false;
+impl OutputRenderer for Pager {
+    fn render(&mut self, out: &engine::Output) {
+        use engine::OutputBlock;
 
-    for block in out.blocks {
-        match block {
-            OutputBlock::Title(t) => {
-                println!("\n{}", t);
-                printed_anything = true;
-            }
-            OutputBlock::Text(line) => {
-                println!("{}", line);
-                printed_anything = true;
+        let lines = if self.ansi {
+            out.to_styled_lines(AnsiRenderer::style_line)
+        } else {
+            out.to_lines()
+        };
+
+        // The blank line and "Exits: ..." stay together on one page, however many
+        // lines wrapping made of the exits.
+        let exits_start = match out.blocks.last() {
+            Some(OutputBlock::Exits(exits)) => {
+                lines.len().saturating_sub(1 + exits.lines().count())
             }
-            OutputBlock::Event(ev) => {
-                if !started_events {
-                    if printed_anything {
-                        println!(); // visual separation before first event
-                    }
-                    started_events = true;
-                }
-                println!("{}", ev);
-                printed_anything = true;
+            _ => lines.len(),
+        };
+
+        let mut shown = 0usize;
+        for (i, line) in lines.iter().enumerate() {
+            let needed = if i == exits_start { lines.len() - i } else { 1 };
+            if shown > 0 && shown + needed > self.page {
+                wait_for_more();
+                shown = 0;
             }
-            OutputBlock::Exits(exits) => {
-                println!("\n{}", exits);
-                printed_anything = true;
+            println!("{}", line);
+            shown += 1;
+        }
+    }
+}
+
+fn wait_for_more() {
+    print!("--more--");
+    let _ = io::stdout().flush();
+    let mut discard = String::new();
+    let _ = io::stdin().read_line(&mut discard);
+}
+
+/// Command history is kept in ~/.rustyfic_history between sessions.
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".rustyfic_history"))
+}
+
+/// Command line: `int_fic [world_file] [--page <lines>] [--width <columns>] [--color | --no-color]`.
+struct CliArgs {
+    world_path: PathBuf,
+    page: Option<usize>,
+    /// Without either color flag, color is used only when stdout is a terminal and
+    /// NO_COLOR is unset.
+    color: bool,
+    /// Wrap width; defaults to $COLUMNS, else 80.
+    width: usize,
+}
+
+fn parse_args() -> CliArgs {
+    let mut world_path: Option<PathBuf> = None;
+    let mut page: Option<usize> = None;
+    let mut color: Option<bool> = None;
+    let mut width: Option<usize> = None;
+
+    fn positive(flag: &str, value: Option<String>) -> usize {
+        match value.and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) if n > 0 => n,
+            _ => {
+                eprintln!("{} expects a positive number", flag);
+                std::process::exit(1);
             }
         }
     }
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--color" {
+            color = Some(true);
+        } else if arg == "--no-color" {
+            color = Some(false);
+        } else if arg == "--page" {
+            page = Some(positive("--page", args.next()));
+        } else if arg == "--width" {
+            width = Some(positive("--width", args.next()));
+        } else if world_path.is_none() {
+            world_path = Some(PathBuf::from(arg));
+        }
+    }
+
+    CliArgs {
+        world_path: world_path.unwrap_or_else(|| PathBuf::from("public/default.toml")),
+        page,
+        color: color.unwrap_or_else(|| {
+            io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        }),
+        width: width.unwrap_or_else(|| {
+            env::var("COLUMNS")
+                .ok()
+                .and_then(|c| c.trim().parse::<usize>().ok())
+                .filter(|c| *c > 0)
+                .unwrap_or(80)
+        }),
+    }
+}
+
+/// Word-wrap every block to `width` columns. Existing line breaks (and the blank
+/// lines between paragraphs) are kept; wrapped lines keep the original indent.
+fn wrap_output(out: &mut engine::Output, width: usize) {
+    use engine::OutputBlock;
+
+    for block in &mut out.blocks {
+        let text = match block {
+            OutputBlock::Title(t)
+            | OutputBlock::Text(t)
+            | OutputBlock::Event(t)
+            | OutputBlock::Exits(t) => t,
+            OutputBlock::ExitsList(_) => continue,
+        };
+        *text = wrap_text(text, width);
+    }
+}
+
+fn wrap_text(text: &str, width: usize) -> String {
+    text.split('\n')
+        .map(|line| wrap_line(line, width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn wrap_line(line: &str, width: usize) -> String {
+    if line.chars().count() <= width {
+        return line.to_string();
+    }
+
+    let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+    let indent_len = indent.chars().count();
+
+    let mut wrapped: Vec<String> = Vec::new();
+    let mut current = indent.clone();
+    let mut current_len = indent_len;
+
+    for word in line.split_whitespace() {
+        let word_len = word.chars().count();
+        if current_len > indent_len && current_len + 1 + word_len > width {
+            wrapped.push(std::mem::replace(&mut current, indent.clone()));
+            current_len = indent_len;
+        }
+        if current_len > indent_len {
+            current.push(' ');
+            current_len += 1;
+        }
+        current.push_str(word);
+        current_len += word_len;
+    }
+    wrapped.push(current);
+
+    wrapped.join("\n")
 }
 
 fn main() -> io::Result<()> {
-    let world_path: PathBuf = env::args()
-        .nth(1)
-        .map(PathBuf::from)
-        .unwrap_or_else(|| PathBuf::from("public/default.toml"));
+    let args = parse_args();
+    let world_path = args.world_path;
 
-    let world = match load_world_from_file(&world_path) {
+    let mut renderer: Box<dyn OutputRenderer> = match args.page {
+        Some(page) => Box::new(Pager {
+            page,
+            ansi: args.color,
+        }),
+        None if args.color => Box::new(AnsiRenderer),
+        None => Box::new(TerminalRenderer),
+    };
+
+    // Pick the format from the extension; anything but .json is read as TOML
+    let is_json = world_path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
+    let loaded = if is_json {
+        load_world_from_json_file(&world_path)
+    } else {
+        load_world_from_file(&world_path)
+    };
+
+    let world = match loaded {
         Ok(w) => {
             println!("Using world file: {}", world_path.display());
             w
@@ -57,44 +208,55 @@ fn main() -> io::Result<()> {
 
     println!("Welcome to {}!", world.name);
     if !world.desc.trim().is_empty() {
-        println!("{}", world.desc.trim());
+        println!("{}", wrap_text(world.desc.trim(), args.width));
     }
     println!();
     println!("Type 'look' to look around, 'quit' to exit.\n");
 
     let mut game = GameState::new(world);
 
-    if let Some(out) = game.initialize() {
-        flush_output(out);
+    if let Some(mut out) = game.initialize() {
+        wrap_output(&mut out, args.width);
+        renderer.render(&out);
     } else {
         eprintln!("Error: start_room '{}' not found.", game.world.start_room);
         return Ok(());
     }
 
-    let stdin = io::stdin();
+    let mut editor = DefaultEditor::new().map_err(io::Error::other)?;
+    let history = history_path();
+    if let Some(path) = &history {
+        // Missing on first run; nothing to recall yet
+        let _ = editor.load_history(path);
+    }
 
     loop {
-        print!("> ");
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        let bytes_read = stdin.read_line(&mut input)?;
-        if bytes_read == 0 {
-            println!("\nGoodbye.");
-            break;
-        }
+        let input = match editor.readline("> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Eof | ReadlineError::Interrupted) => {
+                println!("\nGoodbye.");
+                break;
+            }
+            Err(e) => return Err(io::Error::other(e)),
+        };
 
         let input = input.trim();
         if input.is_empty() {
             continue;
         }
+        let _ = editor.add_history_entry(input);
 
-        let (out, quit) = game.step(input);
-        flush_output(out);
+        let (mut out, quit) = game.step(input);
+        wrap_output(&mut out, args.width);
+        renderer.render(&out);
         if quit {
             break;
         }
     }
 
+    if let Some(path) = &history {
+        let _ = editor.save_history(path);
+    }
+
     Ok(())
 }
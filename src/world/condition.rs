@@ -0,0 +1,324 @@
+use std::fmt;
+
+use super::ItemLocation;
+
+/// A single entry of a `conditions` list, parsed once when the world loads.
+///
+/// Written forms:
+/// - "flag" / "!flag": the flag must be present / absent
+/// - "turn>=10" (also <, <=, >, =, !=): compares the turn count. A leading "!"
+///   flips the comparison, so "!turn>=10" is stored as "turn<10".
+/// - "at:<item>:inventory", "at:<item>:room:<room>", "at:<item>:npc:<npc>" and
+///   "in:<item>:<container>" (or "!" before any of them): the item must be / must
+///   not be in that place.
+/// - "liquid:<item>:<liquid>" / "!liquid:<item>:<liquid>": the item must / must not
+///   hold that liquid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Flag(String),
+    NotFlag(String),
+    Turn(TurnOp, u64),
+    At(String, ItemLocation),
+    NotAt(String, ItemLocation),
+    Liquid(String, String),
+    NotLiquid(String, String),
+}
+
+impl Condition {
+    /// Parse one condition string. Surrounding whitespace is ignored, around the
+    /// "!" as well. A malformed turn comparison ("turn>=soon"), location
+    /// ("at:key:pocket") or liquid ("liquid:bottle") is kept as a plain flag so the
+    /// validator can report it.
+    pub fn parse(cond: &str) -> Condition {
+        let cond = cond.trim();
+        let (negated, name) = match cond.strip_prefix('!') {
+            Some(name) => (true, name.trim()),
+            None => (false, cond),
+        };
+
+        if let Some(Ok((op, n))) = parse_turn_condition(name) {
+            return Condition::Turn(if negated { op.negated() } else { op }, n);
+        }
+
+        if let Some(Ok((item, liquid))) = parse_liquid_condition(name) {
+            return if negated {
+                Condition::NotLiquid(item, liquid)
+            } else {
+                Condition::Liquid(item, liquid)
+            };
+        }
+
+        match parse_location_condition(name) {
+            Some(Ok((item, loc))) if negated => Condition::NotAt(item, loc),
+            Some(Ok((item, loc))) => Condition::At(item, loc),
+            _ if negated => Condition::NotFlag(name.to_string()),
+            _ => Condition::Flag(name.to_string()),
+        }
+    }
+
+    /// The flag tested, if this is a flag condition.
+    pub fn flag(&self) -> Option<&str> {
+        match self {
+            Condition::Flag(name) | Condition::NotFlag(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The item whose location or liquid is tested, if this is an item condition.
+    pub fn tested_item(&self) -> Option<&str> {
+        match self {
+            Condition::At(item, _)
+            | Condition::NotAt(item, _)
+            | Condition::Liquid(item, _)
+            | Condition::NotLiquid(item, _) => Some(item),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Flag(name) => write!(f, "{}", name),
+            Condition::NotFlag(name) => write!(f, "!{}", name),
+            Condition::Turn(op, n) => write!(f, "turn{}{}", op, n),
+            Condition::At(item, loc) => write_location(f, item, loc),
+            Condition::NotAt(item, loc) => {
+                f.write_str("!")?;
+                write_location(f, item, loc)
+            }
+            Condition::Liquid(item, liquid) => write!(f, "liquid:{}:{}", item, liquid),
+            Condition::NotLiquid(item, liquid) => write!(f, "!liquid:{}:{}", item, liquid),
+        }
+    }
+}
+
+fn write_location(f: &mut fmt::Formatter<'_>, item: &str, loc: &ItemLocation) -> fmt::Result {
+    match loc {
+        ItemLocation::Inventory => write!(f, "at:{}:inventory", item),
+        ItemLocation::Room(room) => write!(f, "at:{}:room:{}", item, room),
+        ItemLocation::Npc(npc) => write!(f, "at:{}:npc:{}", item, npc),
+        ItemLocation::Item(container) => write!(f, "in:{}:{}", item, container),
+    }
+}
+
+/// Comparison operators allowed in turn conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl TurnOp {
+    /// The comparison that holds exactly when this one doesn't.
+    fn negated(self) -> TurnOp {
+        match self {
+            TurnOp::Lt => TurnOp::Ge,
+            TurnOp::Le => TurnOp::Gt,
+            TurnOp::Gt => TurnOp::Le,
+            TurnOp::Ge => TurnOp::Lt,
+            TurnOp::Eq => TurnOp::Ne,
+            TurnOp::Ne => TurnOp::Eq,
+        }
+    }
+}
+
+impl fmt::Display for TurnOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            TurnOp::Lt => "<",
+            TurnOp::Le => "<=",
+            TurnOp::Gt => ">",
+            TurnOp::Ge => ">=",
+            TurnOp::Eq => "=",
+            TurnOp::Ne => "!=",
+        };
+        f.write_str(symbol)
+    }
+}
+
+/// Parse a turn condition such as "turn>=10" or "turn < 5".
+/// Returns None if `cond` isn't a turn comparison at all (plain flags like "turnstile"),
+/// and Some(Err(..)) if it looks like one but is malformed.
+pub(crate) fn parse_turn_condition(cond: &str) -> Option<Result<(TurnOp, u64), String>> {
+    let rest = cond.trim().strip_prefix("turn")?.trim_start();
+    if !rest.starts_with(['<', '>', '=', '!']) {
+        return None;
+    }
+
+    let (op, num) = if let Some(n) = rest.strip_prefix(">=") {
+        (TurnOp::Ge, n)
+    } else if let Some(n) = rest.strip_prefix("<=") {
+        (TurnOp::Le, n)
+    } else if let Some(n) = rest.strip_prefix("==") {
+        (TurnOp::Eq, n)
+    } else if let Some(n) = rest.strip_prefix("!=") {
+        (TurnOp::Ne, n)
+    } else if let Some(n) = rest.strip_prefix('>') {
+        (TurnOp::Gt, n)
+    } else if let Some(n) = rest.strip_prefix('<') {
+        (TurnOp::Lt, n)
+    } else if let Some(n) = rest.strip_prefix('=') {
+        (TurnOp::Eq, n)
+    } else {
+        return Some(Err(
+            "has an unknown comparison (use <, <=, >, >=, = or !=)".to_string()
+        ));
+    };
+
+    Some(num.trim().parse::<u64>().map(|n| (op, n)).map_err(|_| {
+        format!(
+            "compares the turn to '{}', which is not a number",
+            num.trim()
+        )
+    }))
+}
+
+/// Parse a location condition such as "at:key:inventory" or "in:coin:purse".
+/// Returns None if `cond` isn't a location condition at all, and Some(Err(..)) if
+/// it starts like one but is malformed.
+pub(crate) fn parse_location_condition(
+    cond: &str,
+) -> Option<Result<(String, ItemLocation), String>> {
+    if let Some(rest) = cond.strip_prefix("at:") {
+        let parsed = match rest.split(':').collect::<Vec<_>>().as_slice() {
+            [item, "inventory"] if !item.is_empty() => {
+                Some((item.to_string(), ItemLocation::Inventory))
+            }
+            [item, "room", room] if !item.is_empty() && !room.is_empty() => {
+                Some((item.to_string(), ItemLocation::Room(room.to_string())))
+            }
+            [item, "npc", npc] if !item.is_empty() && !npc.is_empty() => {
+                Some((item.to_string(), ItemLocation::Npc(npc.to_string())))
+            }
+            _ => None,
+        };
+        return Some(parsed.ok_or_else(|| {
+            "should look like 'at:<item>:inventory', 'at:<item>:room:<room>' or 'at:<item>:npc:<npc>'"
+                .to_string()
+        }));
+    }
+
+    let rest = cond.strip_prefix("in:")?;
+    Some(match rest.split(':').collect::<Vec<_>>().as_slice() {
+        [item, container] if !item.is_empty() && !container.is_empty() => {
+            Ok((item.to_string(), ItemLocation::Item(container.to_string())))
+        }
+        _ => Err("should look like 'in:<item>:<container>'".to_string()),
+    })
+}
+
+/// Parse a liquid condition such as "liquid:bottle:water".
+/// Returns None if `cond` isn't a liquid condition at all, and Some(Err(..)) if it
+/// starts like one but is malformed.
+pub(crate) fn parse_liquid_condition(cond: &str) -> Option<Result<(String, String), String>> {
+    let rest = cond.strip_prefix("liquid:")?;
+    Some(match rest.split(':').collect::<Vec<_>>().as_slice() {
+        [item, liquid] if !item.is_empty() && !liquid.is_empty() => {
+            Ok((item.to_string(), liquid.to_string()))
+        }
+        _ => Err("should look like 'liquid:<item>:<liquid>'".to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Condition, TurnOp};
+    use crate::world::ItemLocation;
+
+    fn round_trip(cond: &str) -> String {
+        Condition::parse(cond).to_string()
+    }
+
+    #[test]
+    fn flags_parse_with_or_without_negation() {
+        assert_eq!(Condition::parse("lit"), Condition::Flag("lit".into()));
+        assert_eq!(
+            Condition::parse(" ! lit "),
+            Condition::NotFlag("lit".into())
+        );
+        assert_eq!(
+            Condition::parse("turnstile"),
+            Condition::Flag("turnstile".into())
+        );
+        assert_eq!(round_trip("!lit"), "!lit");
+    }
+
+    #[test]
+    fn turn_conditions_parse_every_operator() {
+        assert_eq!(
+            Condition::parse("turn >= 10"),
+            Condition::Turn(TurnOp::Ge, 10)
+        );
+        assert_eq!(Condition::parse("turn==3"), Condition::Turn(TurnOp::Eq, 3));
+        for cond in [
+            "turn<1", "turn<=2", "turn>3", "turn>=4", "turn=5", "turn!=6",
+        ] {
+            assert_eq!(round_trip(cond), cond);
+        }
+        assert_eq!(round_trip("!turn>=10"), "turn<10");
+        assert_eq!(round_trip("!turn=2"), "turn!=2");
+    }
+
+    #[test]
+    fn location_conditions_parse_every_place() {
+        assert_eq!(
+            Condition::parse("at:key:room:cellar"),
+            Condition::At("key".into(), ItemLocation::Room("cellar".into()))
+        );
+        assert_eq!(
+            Condition::parse("!in:coin:purse"),
+            Condition::NotAt("coin".into(), ItemLocation::Item("purse".into()))
+        );
+        for cond in [
+            "at:key:inventory",
+            "at:key:room:cellar",
+            "at:key:npc:guard",
+            "in:coin:purse",
+            "!at:key:inventory",
+        ] {
+            assert_eq!(round_trip(cond), cond);
+        }
+        assert_eq!(
+            Condition::parse("at:key:room:cellar").tested_item(),
+            Some("key")
+        );
+    }
+
+    #[test]
+    fn liquid_conditions_name_an_item_and_a_liquid() {
+        assert_eq!(
+            Condition::parse("!liquid:bottle:water"),
+            Condition::NotLiquid("bottle".into(), "water".into())
+        );
+        assert_eq!(round_trip("liquid:bottle:water"), "liquid:bottle:water");
+        assert_eq!(
+            Condition::parse("liquid:bottle:water").tested_item(),
+            Some("bottle")
+        );
+    }
+
+    #[test]
+    fn malformed_conditions_are_kept_as_flags() {
+        assert_eq!(
+            Condition::parse("turn>=soon"),
+            Condition::Flag("turn>=soon".into())
+        );
+        assert_eq!(
+            Condition::parse("at:key:pocket"),
+            Condition::Flag("at:key:pocket".into())
+        );
+        assert_eq!(
+            Condition::parse("!in:coin"),
+            Condition::NotFlag("in:coin".into())
+        );
+        assert_eq!(
+            Condition::parse("liquid:bottle"),
+            Condition::Flag("liquid:bottle".into())
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+use std::io;
+
+use super::validator::ValidationError;
+
+/// Everything that can go wrong while loading a world file.
+#[derive(Debug)]
+pub enum WorldLoadError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file is not valid TOML/JSON, or doesn't match the expected shape.
+    Parse(String),
+    /// Two rooms/items/NPCs share an id.
+    DuplicateId { kind: &'static str, id: String },
+    /// A room reference that must resolve at load time doesn't ("start_room", "NPC 'x' start_room").
+    MissingRoom { referrer: String, room_id: String },
+    /// The world's engine_min_version is newer than this engine.
+    EngineTooOld { required: String },
+    /// Any other malformed entry (empty ids or names, bad start_location, ...).
+    Invalid(String),
+    /// The world was built but failed validation.
+    Validation(Vec<ValidationError>),
+}
+
+impl fmt::Display for WorldLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldLoadError::Io(e) => write!(f, "{}", e),
+            WorldLoadError::Parse(msg) => write!(f, "{}", msg),
+            WorldLoadError::DuplicateId { kind, id } => write!(f, "Duplicate {} id: {}", kind, id),
+            WorldLoadError::MissingRoom { referrer, room_id } => {
+                write!(f, "{} '{}' not found among rooms", referrer, room_id)
+            }
+            WorldLoadError::EngineTooOld { required } => write!(
+                f,
+                "This world requires a newer rustyfic (version {} or later; this is {}).",
+                required,
+                env!("CARGO_PKG_VERSION")
+            ),
+            WorldLoadError::Invalid(msg) => write!(f, "{}", msg),
+            WorldLoadError::Validation(errors) => {
+                let msgs = errors
+                    .iter()
+                    .map(|e| e.message.as_str())
+                    .collect::<Vec<&str>>()
+                    .join("\n");
+                write!(f, "{}", msgs)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorldLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WorldLoadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WorldLoadError {
+    fn from(e: io::Error) -> Self {
+        WorldLoadError::Io(e)
+    }
+}
+
+/// For callers (like the CLI) that only deal in io::Error.
+impl From<WorldLoadError> for io::Error {
+    fn from(e: WorldLoadError) -> Self {
+        match e {
+            WorldLoadError::Io(inner) => inner,
+            other => io::Error::new(io::ErrorKind::InvalidData, other.to_string()),
+        }
+    }
+}
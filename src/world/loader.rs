@@ -1,20 +1,22 @@
-use serde::Deserialize;
-use std::collections::HashMap;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::io;
 use std::path::Path;
 
+use super::condition::Condition;
+use super::error::WorldLoadError;
 use super::model::{
-    Action, ContainerProps, Exit, GlobalCondition, Item, ItemKind, ItemLocation, Room, StateDesc,
-    World,
+    Action, ConditionalEffect, ContainerProps, DropTrigger, EasterVerb, Ending, Exit,
+    GlobalCondition, Item, ItemKind, ItemLocation, ItemSpawn, ItemTransform, Messages, Npc, Rank,
+    Room, ScoreEvent, StateDesc, TakeTrigger, VerbCondition, World,
 };
 use super::validate_world;
 
-////////////////////
-/// TOML STRUCTS ///
-////////////////////
+//////////////////
+// TOML STRUCTS //
+//////////////////
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct WorldFile {
     world: WorldHeader,
     #[serde(default)]
@@ -27,18 +29,78 @@ struct WorldFile {
     global_condition: Vec<GlobalConditionConfig>, // [[global_condition]]
     #[serde(default)]
     global_action: Vec<ActionConfig>, // [[global_action]]
+    #[serde(default)]
+    ending: Vec<EndingConfig>, // [[ending]]
+    #[serde(default)]
+    score: Vec<ScoreConfig>, // [[score]]
+    #[serde(default)]
+    rank: Vec<RankConfig>, // [[rank]]
+    #[serde(default)]
+    easter_verb: Vec<EasterVerbConfig>, // [[easter_verb]]
+    #[serde(default)]
+    on_take: Vec<TakeTriggerConfig>, // [[on_take]]
+    #[serde(default)]
+    messages: MessagesConfig, // [messages]
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct WorldHeader {
     id: String,
     name: String,
     start_room: String,
     #[serde(default)]
     desc: String,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    credits: Option<String>,
+    #[serde(default)]
+    engine_min_version: Option<String>,
+    #[serde(default)]
+    take_all_includes_containers: bool,
+    #[serde(default)]
+    auto_open: bool,
+    #[serde(default)]
+    effects_clear_first: bool,
+    #[serde(default)]
+    start_inventory: Vec<String>,
+    #[serde(default)]
+    start_flags: Vec<String>,
+    #[serde(default)]
+    autosave_rooms: Vec<String>,
+    #[serde(default)]
+    deep_examine: bool,
+    #[serde(default)]
+    show_affordances: bool,
+    #[serde(default)]
+    goto_shows_each_room: bool,
+    #[serde(default)]
+    render_after_item_change: bool,
+    #[serde(default)]
+    structured_room_text: bool,
+    #[serde(default)]
+    ambiguity_text: Option<String>,
+    #[serde(default)]
+    unknown_command_text: Option<String>,
+    #[serde(default, alias = "player_description")]
+    player_desc: Option<String>,
+    #[serde(default)]
+    player_desc_state: Vec<StateDescConfig>, // [[world.player_desc_state]]
+    #[serde(default)]
+    player_max_hp: Option<u64>,
+    #[serde(default)]
+    aliases: IndexMap<String, String>, // [world.aliases]
+    #[serde(default)]
+    aliases_override_builtins: bool,
+    #[serde(default)]
+    turn_limit: Option<u64>,
+    #[serde(default)]
+    turn_limit_text: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct RoomConfig {
     id: String,
     name: String,
@@ -51,16 +113,35 @@ struct RoomConfig {
     action: Vec<ActionConfig>, // [[room.action]]
     #[serde(default)]
     state_desc: Vec<StateDescConfig>, // [[room.state_desc]]
+    #[serde(default)]
+    name_state: Vec<StateDescConfig>, // [[room.name_state]]
+    #[serde(default)]
+    liquid_source: Option<String>,
+    #[serde(default)]
+    on_drop: Vec<DropTriggerConfig>, // [[room.on_drop]]
+    #[serde(default)]
+    unknown_command_text: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct DropTriggerConfig {
+    item: String,
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    effects: Vec<String>,
+    #[serde(default = "default_true")]
+    one_shot: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct StateDescConfig {
     #[serde(default)]
     conditions: Vec<String>,
     text: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct ExitConfig {
     direction: String,
     target: String,
@@ -70,9 +151,33 @@ struct ExitConfig {
 
     #[serde(default)]
     conditions: Vec<String>,
+
+    #[serde(default)]
+    travel_text: Option<String>,
+
+    #[serde(default)]
+    arrival_effects: Vec<String>,
+
+    #[serde(default)]
+    description: Option<String>,
+
+    #[serde(default)]
+    secret: bool,
+
+    #[serde(default)]
+    verb_condition: Vec<VerbConditionConfig>, // [[room.exit.verb_condition]]
+
+    #[serde(default)]
+    turn_cost: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct VerbConditionConfig {
+    verb: String,
+    conditions: Vec<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct ActionConfig {
     id: String,
     verbs: Vec<String>,
@@ -94,21 +199,75 @@ struct ActionConfig {
     #[serde(default)]
     requires_inventory: Vec<String>,
 
+    #[serde(default)]
+    requires_reachable: Vec<String>,
+
+    #[serde(default)]
+    instrument: Option<String>,
+
+    #[serde(default)]
+    conditional_effect: Vec<ConditionalEffectConfig>, // [[room.action.conditional_effect]]
+
+    #[serde(default)]
+    transform: Vec<TransformConfig>, // [[room.action.transform]]
+
+    #[serde(default)]
+    spawn: Vec<SpawnConfig>, // [[room.action.spawn]]
+
+    #[serde(default)]
+    remove_items: Vec<String>,
+
+    #[serde(default)]
+    toggle_flag: Option<String>,
+
+    #[serde(default)]
+    response_off: Option<String>,
+
     #[serde(default)]
     missing_inventory_text: Option<String>,
 
     #[serde(default)]
     missing_scope_text: Option<String>,
+
+    #[serde(default)]
+    blocked_text: Option<String>,
+
+    #[serde(default)]
+    keep_items: bool,
+
+    #[serde(default)]
+    cooldown_turns: Option<u64>,
+
+    #[serde(default)]
+    cooldown_text: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ConditionalEffectConfig {
+    conditions: Vec<String>,
+    effects: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct TransformConfig {
+    from: String,
+    into: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
+struct SpawnConfig {
+    item: String,
+    location: String, // same forms as an item's start_location, except "nowhere"
+}
+
+#[derive(Deserialize, Serialize)]
 struct ItemConfig {
     id: String,
     name: String,
     #[serde(default)]
     aliases: Vec<String>,
 
-    /// Where the item starts: "room:house", "inventory", "item:trophy_case", etc.
+    /// Where the item starts: "room:house", "inventory", "item:trophy_case", "nowhere", etc.
     start_location: String,
 
     #[serde(default)]
@@ -126,6 +285,15 @@ struct ItemConfig {
     #[serde(default)]
     portable: Option<bool>,
 
+    #[serde(default = "default_true")]
+    droppable: bool,
+
+    #[serde(default)]
+    examine_requires_inventory: bool,
+
+    #[serde(default)]
+    inscription: Option<String>,
+
     #[serde(default)]
     kind: Option<String>, // e.g. "simple", "container", "weapon"
 
@@ -144,6 +312,15 @@ struct ItemConfig {
     #[serde(default)]
     container_closed_text: Option<String>,
 
+    #[serde(default)]
+    container_open_text: Option<String>,
+
+    #[serde(default)]
+    container_use_conditions: Vec<String>,
+
+    #[serde(default)]
+    container_use_blocked_text: Option<String>,
+
     #[serde(default)]
     complete_text: Option<String>,
 
@@ -152,9 +329,33 @@ struct ItemConfig {
 
     #[serde(default)]
     container_prep: Option<String>,
+
+    #[serde(default)]
+    decays_after: Option<u64>,
+
+    #[serde(default)]
+    decays_into: Option<String>,
+
+    #[serde(default)]
+    decay_text: Option<String>,
+
+    #[serde(default)]
+    room_state: Vec<StateDescConfig>, // [[item.room_state]]
+
+    #[serde(default)]
+    wearable: bool,
+
+    #[serde(default)]
+    holds_liquid: bool,
+
+    #[serde(default)]
+    start_liquid: Option<String>,
+
+    #[serde(default)]
+    liquid_source: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct GlobalConditionConfig {
     id: String,
 
@@ -178,7 +379,154 @@ struct GlobalConditionConfig {
     one_shot: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
+struct EndingConfig {
+    id: String,
+
+    #[serde(default)]
+    conditions: Vec<String>,
+
+    text: String,
+
+    #[serde(default)]
+    victory: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ScoreConfig {
+    id: String,
+
+    #[serde(default)]
+    conditions: Vec<String>,
+
+    points: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+struct RankConfig {
+    min_score: i64,
+    title: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct EasterVerbConfig {
+    verbs: Vec<String>,
+
+    #[serde(default)]
+    conditions: Vec<String>,
+
+    response: String,
+}
+
+/// `[messages]`: any built-in reply left out keeps its English default.
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+struct MessagesConfig {
+    take: Option<String>,
+    take_from: Option<String>,
+    take_from_npc: Option<String>,
+    already_have: Option<String>,
+    cant_take: Option<String>,
+    drop: Option<String>,
+    not_here: Option<String>,
+    inventory_empty: Option<String>,
+    inventory_header: Option<String>,
+    go: Option<String>,
+    cant_go: Option<String>,
+    time_passes: Option<String>,
+    take_what: Option<String>,
+    drop_what: Option<String>,
+    examine_what: Option<String>,
+    give: Option<String>,
+    cant_give: Option<String>,
+    npc_refuses: Option<String>,
+    npc_lacks: Option<String>,
+    cant_drop: Option<String>,
+    not_carrying: Option<String>,
+    no_container: Option<String>,
+    not_container: Option<String>,
+    no_npc: Option<String>,
+    npc_gone: Option<String>,
+    nothing_to_take: Option<String>,
+    nothing_to_drop: Option<String>,
+    nothing_to_give: Option<String>,
+    nothing_to_examine: Option<String>,
+    nothing_special: Option<String>,
+    npc_nothing_special: Option<String>,
+    npc_nothing_to_say: Option<String>,
+    npc_nothing_new: Option<String>,
+    no_answer: Option<String>,
+    cant_do_that: Option<String>,
+    be_more_specific: Option<String>,
+    go_where: Option<String>,
+    talk_to_whom: Option<String>,
+    give_what: Option<String>,
+    give_to_whom: Option<String>,
+    give_what_to_whom: Option<String>,
+    give_unclear: Option<String>,
+    take_from_where: Option<String>,
+    take_from_whom: Option<String>,
+    take_what_from_whom: Option<String>,
+    take_all_from_where: Option<String>,
+    take_from_unclear: Option<String>,
+    not_in_container: Option<String>,
+    nothing_inside: Option<String>,
+    store_what: Option<String>,
+    not_carrying_any: Option<String>,
+    cant_store: Option<String>,
+    store_where: Option<String>,
+    store_where_which: Option<String>,
+    store_in_itself: Option<String>,
+    container_full: Option<String>,
+    store: Option<String>,
+    store_all_where: Option<String>,
+    store_all_which: Option<String>,
+    nothing_to_store: Option<String>,
+    container_unusable: Option<String>,
+    cooldown: Option<String>,
+    unknown_command: Option<String>,
+    goto_unknown: Option<String>,
+    goto_here: Option<String>,
+    goto_no_route: Option<String>,
+    moves_stopped: Option<String>,
+    verbose_mode: Option<String>,
+    brief_mode: Option<String>,
+    superbrief_mode: Option<String>,
+    turns: Option<String>,
+    turns_of_limit: Option<String>,
+    score: Option<String>,
+    score_rank: Option<String>,
+    score_up: Option<String>,
+    health: Option<String>,
+    wear: Option<String>,
+    take_off: Option<String>,
+    already_wearing: Option<String>,
+    wearing: Option<String>,
+    out_of_time: Option<String>,
+    checkpoint_saved: Option<String>,
+    no_checkpoint: Option<String>,
+    checkpoint_restored: Option<String>,
+    revert_hint: Option<String>,
+    game_over: Option<String>,
+    game_over_revert: Option<String>,
+    goodbye: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct TakeTriggerConfig {
+    item: String,
+
+    #[serde(default)]
+    conditions: Vec<String>,
+
+    #[serde(default)]
+    response: String,
+
+    #[serde(default)]
+    effects: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
 struct NpcConfig {
     id: String,
     name: String,
@@ -207,6 +555,9 @@ struct NpcConfig {
     #[serde(default)]
     roam_chance_percent: Option<u8>,
 
+    #[serde(default)]
+    carries_on_roam: Vec<String>,
+
     // Movement blocking controls
     #[serde(default)]
     block_movement: Option<bool>,
@@ -220,6 +571,9 @@ struct NpcConfig {
     #[serde(default)]
     block_exits: Vec<String>,
 
+    #[serde(default)]
+    block_priority: i32,
+
     // Foe/attack controls
     #[serde(default)]
     foe: Option<bool>,
@@ -235,9 +589,54 @@ struct NpcConfig {
 
     #[serde(default)]
     dialogue: Vec<NpcDialogueConfig>,
+
+    #[serde(default)]
+    disposition: Option<String>,
+
+    #[serde(default)]
+    want: Vec<NpcWantConfig>, // [[npc.want]]
+
+    #[serde(default)]
+    idle: Vec<StateDescConfig>, // [[npc.idle]]
+
+    #[serde(default)]
+    on_player_enter: Vec<NpcTriggerConfig>, // [[npc.on_player_enter]]
+
+    #[serde(default)]
+    responds_to_call: bool,
+
+    #[serde(default)]
+    call_text: Option<String>,
+
+    #[serde(default)]
+    comes_when_called: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+struct NpcTriggerConfig {
+    id: String,
+    #[serde(default)]
+    conditions: Vec<String>,
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    effects: Vec<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
+struct NpcWantConfig {
+    item: String,
+    #[serde(default)]
+    conditions: Vec<String>,
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    effects: Vec<String>,
+    #[serde(default)]
+    consumed: bool,
+}
+
+#[derive(Deserialize, Serialize)]
 struct NpcDialogueConfig {
     id: String,
     #[serde(default)]
@@ -247,6 +646,8 @@ struct NpcDialogueConfig {
     effects: Vec<String>,
     #[serde(default = "default_true")]
     one_shot: bool,
+    #[serde(default)]
+    sets_disposition: Option<String>,
 }
 
 // Helper for serde default
@@ -254,30 +655,54 @@ fn default_true() -> bool {
     true
 }
 
-/////////////////////////////
-/// TOML PARSER FUNCTIONS ///
-/////////////////////////////
+///////////////////////////
+// TOML PARSER FUNCTIONS //
+///////////////////////////
 
 /// Public API: load a world from a .toml file on disk.
-pub fn load_world_from_file(path: &Path) -> io::Result<World> {
+pub fn load_world_from_file(path: &Path) -> Result<World, WorldLoadError> {
     let contents = fs::read_to_string(path)?;
     load_world_from_str(&contents)
 }
 
 /// Public API: load a world from a TOML string.
-pub fn load_world_from_str(contents: &str) -> io::Result<World> {
-    let world_file: WorldFile = toml::from_str(&contents)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+pub fn load_world_from_str(contents: &str) -> Result<World, WorldLoadError> {
+    let world_file: WorldFile =
+        toml::from_str(contents).map_err(|e| WorldLoadError::Parse(e.to_string()))?;
+
+    build_world(world_file)
+}
+
+/// Public API: load a world from a .json file on disk.
+pub fn load_world_from_json_file(path: &Path) -> Result<World, WorldLoadError> {
+    let contents = fs::read_to_string(path)?;
+    load_world_from_json_str(&contents)
+}
+
+/// Public API: load a world from a JSON string.
+/// Same shape as the TOML format: `{"world": {...}, "room": [...], "item": [...]}`.
+pub fn load_world_from_json_str(contents: &str) -> Result<World, WorldLoadError> {
+    let world_file: WorldFile =
+        serde_json::from_str(contents).map_err(|e| WorldLoadError::Parse(e.to_string()))?;
+
+    build_world(world_file)
+}
+
+/// Shared post-parse construction and validation for every input format.
+fn build_world(world_file: WorldFile) -> Result<World, WorldLoadError> {
+    if let Some(required) = &world_file.world.engine_min_version {
+        check_engine_version(required)?;
+    }
 
     // Build rooms map
-    let mut rooms_map: HashMap<String, Room> = HashMap::new();
+    let mut rooms_map: IndexMap<String, Room> = IndexMap::new();
 
     for room_cfg in world_file.room {
         if rooms_map.contains_key(&room_cfg.id) {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Duplicate room id: {}", room_cfg.id),
-            ));
+            return Err(WorldLoadError::DuplicateId {
+                kind: "room",
+                id: room_cfg.id,
+            });
         }
 
         let exits = room_cfg
@@ -287,38 +712,58 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
                 direction: e.direction,
                 target: e.target,
                 verbs: e.verbs,
-                conditions: e.conditions,
+                conditions: parse_conditions(&e.conditions),
+                travel_text: e.travel_text.map(|s| normalize_multiline_desc(&s)),
+                description: e.description.map(|s| normalize_multiline_desc(&s)),
+                secret: e.secret,
+                arrival_effects: e.arrival_effects,
+                verb_conditions: e
+                    .verb_condition
+                    .into_iter()
+                    .map(|vc| VerbCondition {
+                        verb: vc.verb,
+                        conditions: parse_conditions(&vc.conditions),
+                    })
+                    .collect(),
+                turn_cost: e.turn_cost,
             })
             .collect();
 
         let actions = room_cfg
             .action
             .into_iter()
-            .map(|a| Action {
-                id: a.id,
-                verbs: a.verbs,
-                nouns: a.nouns,
-                response: normalize_multiline_desc(&a.response),
-                effects: a.effects,
-                conditions: a.conditions,
-                scope_requirements: a.scope_requirements,
-                requires_inventory: a.requires_inventory,
-                missing_inventory_text: a
-                    .missing_inventory_text
-                    .map(|s| normalize_multiline_desc(&s)),
-                missing_scope_text: a.missing_scope_text.map(|s| normalize_multiline_desc(&s)),
-            })
-            .collect();
+            .map(build_action)
+            .collect::<Result<_, _>>()?;
 
         let state_descs = room_cfg
             .state_desc
             .into_iter()
             .map(|sd| StateDesc {
-                conditions: sd.conditions,
+                conditions: parse_conditions(&sd.conditions),
                 text: normalize_multiline_desc(&sd.text),
             })
             .collect();
 
+        let name_states = room_cfg
+            .name_state
+            .into_iter()
+            .map(|ns| StateDesc {
+                conditions: parse_conditions(&ns.conditions),
+                text: ns.text.trim().to_string(),
+            })
+            .collect();
+
+        let on_drop = room_cfg
+            .on_drop
+            .into_iter()
+            .map(|d| DropTrigger {
+                item: d.item,
+                response: normalize_multiline_desc(&d.response),
+                effects: d.effects,
+                one_shot: d.one_shot,
+            })
+            .collect();
+
         rooms_map.insert(
             room_cfg.id.clone(),
             Room {
@@ -328,34 +773,43 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
                 exits,
                 actions,
                 state_descs,
+                name_states,
+                liquid_source: room_cfg.liquid_source,
+                on_drop,
+                unknown_command_text: room_cfg
+                    .unknown_command_text
+                    .map(|s| normalize_multiline_desc(&s)),
             },
         );
     }
 
     // Ensure start_room exists
     if !rooms_map.contains_key(&world_file.world.start_room) {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!(
-                "start_room '{}' not found among rooms",
-                world_file.world.start_room
-            ),
-        ));
+        return Err(WorldLoadError::MissingRoom {
+            referrer: "start_room".to_string(),
+            room_id: world_file.world.start_room,
+        });
     }
 
     // Build items map
-    let mut items_map: HashMap<String, Item> = HashMap::new();
+    let mut items_map: IndexMap<String, Item> = IndexMap::new();
 
     for ic in world_file.item {
         if items_map.contains_key(&ic.id) {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Duplicate item id: {}", ic.id),
-            ));
+            return Err(WorldLoadError::DuplicateId {
+                kind: "item",
+                id: ic.id,
+            });
         }
 
-        let start_location = parse_item_location(&ic.start_location)
-            .map_err(|msg| io::Error::new(io::ErrorKind::InvalidData, msg))?;
+        let start_location = if ic.start_location.trim().eq_ignore_ascii_case("nowhere") {
+            None
+        } else {
+            Some(
+                parse_item_location(&ic.start_location, "start_location")
+                    .map_err(WorldLoadError::Invalid)?,
+            )
+        };
 
         let (primary_name, mut aliases) = parse_name_and_aliases(&ic.name);
         for extra in &ic.aliases {
@@ -365,16 +819,25 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
             }
         }
         if primary_name.trim().is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Item '{}' has an empty name", ic.id),
-            ));
+            return Err(WorldLoadError::Invalid(format!(
+                "Item '{}' has an empty name",
+                ic.id
+            )));
         }
 
         let kind = parse_item_kind(&ic);
 
         let room_text = normalize_multiline_desc(&ic.room_text);
 
+        let room_state_texts = ic
+            .room_state
+            .iter()
+            .map(|rs| StateDesc {
+                conditions: parse_conditions(&rs.conditions),
+                text: normalize_multiline_desc(&rs.text),
+            })
+            .collect();
+
         let inventory_text = if ic.inventory_text.trim().is_empty() {
             // fall back to PRIMARY name if no custom inventory text
             primary_name.clone()
@@ -393,42 +856,50 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
                 name: primary_name,
                 aliases,
                 room_text,
+                room_state_texts,
                 inventory_text,
                 examine_text,
-                conditions: ic.conditions,
+                conditions: parse_conditions(&ic.conditions),
                 portable,
+                droppable: ic.droppable,
+                examine_requires_inventory: ic.examine_requires_inventory,
+                inscription: ic.inscription.map(|s| normalize_multiline_desc(&s)),
                 kind,
                 start_location,
+                decays_after: ic.decays_after,
+                decays_into: ic.decays_into,
+                decay_text: ic.decay_text.map(|s| normalize_multiline_desc(&s)),
+                wearable: ic.wearable,
+                holds_liquid: ic.holds_liquid,
+                start_liquid: ic.start_liquid,
+                liquid_source: ic.liquid_source,
             },
         );
     }
 
     // Build NPCs map
-    let mut npcs_map: HashMap<String, super::model::Npc> = HashMap::new();
+    let mut npcs_map: IndexMap<String, super::model::Npc> = IndexMap::new();
 
     for nc in world_file.npc {
         if npcs_map.contains_key(&nc.id) {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Duplicate npc id: {}", nc.id),
-            ));
+            return Err(WorldLoadError::DuplicateId {
+                kind: "npc",
+                id: nc.id,
+            });
         }
 
         if nc.start_room.trim().is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("NPC '{}' has an empty start_room", nc.id),
-            ));
+            return Err(WorldLoadError::Invalid(format!(
+                "NPC '{}' has an empty start_room",
+                nc.id
+            )));
         }
 
         if !rooms_map.contains_key(&nc.start_room) {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!(
-                    "NPC '{}' start_room '{}' not found among rooms",
-                    nc.id, nc.start_room
-                ),
-            ));
+            return Err(WorldLoadError::MissingRoom {
+                referrer: format!("NPC '{}' start_room", nc.id),
+                room_id: nc.start_room,
+            });
         }
 
         let (primary_name, mut aliases) = parse_name_and_aliases(&nc.name);
@@ -439,34 +910,21 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
             }
         }
         if primary_name.trim().is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("NPC '{}' has an empty name", nc.id),
-            ));
+            return Err(WorldLoadError::Invalid(format!(
+                "NPC '{}' has an empty name",
+                nc.id
+            )));
         }
 
         let actions = nc
             .action
             .into_iter()
-            .map(|a| Action {
-                id: a.id,
-                verbs: a.verbs,
-                nouns: a.nouns,
-                response: normalize_multiline_desc(&a.response),
-                effects: a.effects,
-                conditions: a.conditions,
-                scope_requirements: a.scope_requirements,
-                requires_inventory: a.requires_inventory,
-                missing_inventory_text: a
-                    .missing_inventory_text
-                    .map(|s| normalize_multiline_desc(&s)),
-                missing_scope_text: a.missing_scope_text.map(|s| normalize_multiline_desc(&s)),
-            })
-            .collect();
+            .map(build_action)
+            .collect::<Result<_, _>>()?;
 
         let roam = {
             let enabled = nc.roam_enabled.unwrap_or(false);
-            let chance = nc.roam_chance_percent.unwrap_or(0).min(100) as u8;
+            let chance = nc.roam_chance_percent.unwrap_or(0).min(100);
             let rooms = nc.roam_rooms;
 
             if enabled && !rooms.is_empty() && chance > 0 {
@@ -485,10 +943,43 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
             .into_iter()
             .map(|d| super::model::NpcDialogue {
                 id: d.id,
-                conditions: d.conditions,
+                conditions: parse_conditions(&d.conditions),
                 response: normalize_multiline_desc(&d.response),
                 effects: d.effects,
                 one_shot: d.one_shot,
+                sets_disposition: d.sets_disposition,
+            })
+            .collect();
+
+        let idle_texts = nc
+            .idle
+            .iter()
+            .map(|sd| StateDesc {
+                conditions: parse_conditions(&sd.conditions),
+                text: normalize_multiline_desc(&sd.text),
+            })
+            .collect();
+
+        let wants = nc
+            .want
+            .into_iter()
+            .map(|w| super::model::NpcWant {
+                item: w.item,
+                conditions: parse_conditions(&w.conditions),
+                response: normalize_multiline_desc(&w.response),
+                effects: w.effects,
+                consumed: w.consumed,
+            })
+            .collect();
+
+        let on_player_enter = nc
+            .on_player_enter
+            .into_iter()
+            .map(|t| super::model::NpcTrigger {
+                id: t.id,
+                conditions: parse_conditions(&t.conditions),
+                response: normalize_multiline_desc(&t.response),
+                effects: t.effects,
             })
             .collect();
 
@@ -500,19 +991,28 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
                 aliases,
                 start_room: nc.start_room,
                 room_text: normalize_multiline_desc(&nc.room_text),
+                idle_texts,
                 examine_text: normalize_multiline_desc(&nc.examine_text),
-                conditions: nc.conditions,
+                conditions: parse_conditions(&nc.conditions),
                 actions,
                 roam,
+                carries_on_roam: nc.carries_on_roam,
                 block_movement: nc.block_movement.unwrap_or(false),
-                block_conditions: nc.block_conditions,
+                block_conditions: parse_conditions(&nc.block_conditions),
                 block_text: nc.block_text,
                 block_exits: nc.block_exits,
+                block_priority: nc.block_priority,
                 foe: nc.foe.unwrap_or(false),
-                attack_chance_percent: nc.attack_chance_percent.unwrap_or(0).min(100) as u8,
+                attack_chance_percent: nc.attack_chance_percent.unwrap_or(0).min(100),
                 attack_text: nc.attack_text.map(|s| normalize_multiline_desc(&s)),
                 attack_effects: nc.attack_effects,
                 dialogue,
+                disposition: nc.disposition,
+                wants,
+                on_player_enter,
+                responds_to_call: nc.responds_to_call,
+                call_text: nc.call_text.map(|s| normalize_multiline_desc(&s)),
+                comes_when_called: nc.comes_when_called,
             },
         );
     }
@@ -522,15 +1022,14 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
 
     for gc in world_file.global_condition {
         if gc.id.trim().is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "global_condition.id may not be empty",
+            return Err(WorldLoadError::Invalid(
+                "global_condition.id may not be empty".to_string(),
             ));
         }
 
         global_conditions.push(GlobalCondition {
             id: gc.id,
-            conditions: gc.conditions,
+            conditions: parse_conditions(&gc.conditions),
             allowed_rooms: gc.allowed_rooms,
             disallowed_rooms: gc.disallowed_rooms,
             response: normalize_multiline_desc(&gc.response),
@@ -543,19 +1042,70 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
     let global_actions: Vec<Action> = world_file
         .global_action
         .into_iter()
-        .map(|a| Action {
-            id: a.id,
-            verbs: a.verbs,
-            nouns: a.nouns,
-            response: normalize_multiline_desc(&a.response),
-            effects: a.effects,
-            conditions: a.conditions,
-            scope_requirements: a.scope_requirements,
-            requires_inventory: a.requires_inventory,
-            missing_inventory_text: a
-                .missing_inventory_text
-                .map(|s| normalize_multiline_desc(&s)),
-            missing_scope_text: a.missing_scope_text.map(|s| normalize_multiline_desc(&s)),
+        .map(build_action)
+        .collect::<Result<_, _>>()?;
+
+    // Build endings
+    let mut endings: Vec<Ending> = Vec::new();
+
+    for ec in world_file.ending {
+        if ec.id.trim().is_empty() {
+            return Err(WorldLoadError::Invalid(
+                "ending.id may not be empty".to_string(),
+            ));
+        }
+
+        endings.push(Ending {
+            id: ec.id,
+            conditions: parse_conditions(&ec.conditions),
+            text: normalize_multiline_desc(&ec.text),
+            is_victory: ec.victory,
+        });
+    }
+
+    // Build score events and ranks; points and thresholds can't be negative
+    let mut score_events: Vec<ScoreEvent> = Vec::new();
+    for sc in world_file.score {
+        let points = u64::try_from(sc.points).map_err(|_| {
+            WorldLoadError::Invalid(format!("score '{}' has negative points", sc.id))
+        })?;
+        score_events.push(ScoreEvent {
+            id: sc.id,
+            conditions: parse_conditions(&sc.conditions),
+            points,
+        });
+    }
+
+    let mut ranks: Vec<Rank> = Vec::new();
+    for rc in world_file.rank {
+        let min_score = u64::try_from(rc.min_score).map_err(|_| {
+            WorldLoadError::Invalid(format!("rank '{}' has a negative min_score", rc.title))
+        })?;
+        ranks.push(Rank {
+            min_score,
+            title: rc.title,
+        });
+    }
+    ranks.sort_by_key(|r| r.min_score);
+
+    let easter_verbs: Vec<EasterVerb> = world_file
+        .easter_verb
+        .into_iter()
+        .map(|ev| EasterVerb {
+            verbs: ev.verbs,
+            conditions: parse_conditions(&ev.conditions),
+            response: normalize_multiline_desc(&ev.response),
+        })
+        .collect();
+
+    let on_take: Vec<TakeTrigger> = world_file
+        .on_take
+        .into_iter()
+        .map(|t| TakeTrigger {
+            item: t.item,
+            conditions: parse_conditions(&t.conditions),
+            response: normalize_multiline_desc(&t.response),
+            effects: t.effects,
         })
         .collect();
 
@@ -563,27 +1113,695 @@ pub fn load_world_from_str(contents: &str) -> io::Result<World> {
         id: world_file.world.id,
         name: world_file.world.name,
         desc: normalize_multiline_desc(&world_file.world.desc),
+        author: world_file.world.author,
+        version: world_file.world.version,
+        credits: world_file
+            .world
+            .credits
+            .map(|s| normalize_multiline_desc(&s)),
+        engine_min_version: world_file.world.engine_min_version,
         start_room: world_file.world.start_room,
         rooms: rooms_map,
         items: items_map,
         npcs: npcs_map,
         global_conditions,
         global_actions,
+        endings,
+        score_events,
+        ranks,
+        easter_verbs,
+        on_take,
+        take_all_includes_containers: world_file.world.take_all_includes_containers,
+        auto_open: world_file.world.auto_open,
+        effects_clear_first: world_file.world.effects_clear_first,
+        start_inventory: world_file.world.start_inventory,
+        start_flags: world_file.world.start_flags,
+        autosave_rooms: world_file.world.autosave_rooms,
+        deep_examine: world_file.world.deep_examine,
+        show_affordances: world_file.world.show_affordances,
+        goto_shows_each_room: world_file.world.goto_shows_each_room,
+        render_after_item_change: world_file.world.render_after_item_change,
+        structured_room_text: world_file.world.structured_room_text,
+        command_aliases: world_file
+            .world
+            .aliases
+            .into_iter()
+            .map(|(alias, command)| {
+                let alias = alias.split_whitespace().collect::<Vec<_>>().join(" ");
+                (alias.to_lowercase(), command.trim().to_string())
+            })
+            .collect(),
+        aliases_override_builtins: world_file.world.aliases_override_builtins,
+        turn_limit: world_file.world.turn_limit,
+        turn_limit_text: world_file
+            .world
+            .turn_limit_text
+            .map(|s| normalize_multiline_desc(&s)),
+        ambiguity_text: world_file.world.ambiguity_text,
+        unknown_command_text: world_file
+            .world
+            .unknown_command_text
+            .map(|s| normalize_multiline_desc(&s)),
+        player_desc: world_file
+            .world
+            .player_desc
+            .map(|s| normalize_multiline_desc(&s)),
+        player_desc_states: world_file
+            .world
+            .player_desc_state
+            .into_iter()
+            .map(|sd| StateDesc {
+                conditions: parse_conditions(&sd.conditions),
+                text: normalize_multiline_desc(&sd.text),
+            })
+            .collect(),
+        player_max_hp: world_file.world.player_max_hp,
+        messages: build_messages(world_file.messages),
     };
 
     let validation_errors = validate_world(&world);
     if !validation_errors.is_empty() {
-        let msgs = validation_errors
-            .into_iter()
-            .map(|e| e.message)
-            .collect::<Vec<String>>()
-            .join("\n");
-        return Err(io::Error::new(io::ErrorKind::InvalidData, msgs));
+        return Err(WorldLoadError::Validation(validation_errors));
     }
 
     Ok(world)
 }
 
+///////////////////////////
+// TOML EXPORT FUNCTIONS //
+///////////////////////////
+
+impl World {
+    /// Serialize the world back into the TOML format it was loaded from.
+    /// Rooms, items and NPCs are written in the order they were authored;
+    /// loading the result yields an equivalent world.
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(&world_to_file(self))
+    }
+}
+
+fn world_to_file(world: &World) -> WorldFile {
+    let rooms: Vec<&Room> = world.rooms.values().collect();
+    let items: Vec<&Item> = world.items.values().collect();
+    let npcs: Vec<&Npc> = world.npcs.values().collect();
+
+    WorldFile {
+        world: WorldHeader {
+            id: world.id.clone(),
+            name: world.name.clone(),
+            start_room: world.start_room.clone(),
+            desc: multiline_desc_to_toml(&world.desc),
+            author: world.author.clone(),
+            version: world.version.clone(),
+            credits: world.credits.as_deref().map(multiline_desc_to_toml),
+            engine_min_version: world.engine_min_version.clone(),
+            take_all_includes_containers: world.take_all_includes_containers,
+            auto_open: world.auto_open,
+            effects_clear_first: world.effects_clear_first,
+            start_inventory: world.start_inventory.clone(),
+            start_flags: world.start_flags.clone(),
+            autosave_rooms: world.autosave_rooms.clone(),
+            deep_examine: world.deep_examine,
+            show_affordances: world.show_affordances,
+            goto_shows_each_room: world.goto_shows_each_room,
+            render_after_item_change: world.render_after_item_change,
+            structured_room_text: world.structured_room_text,
+            aliases: world.command_aliases.clone(),
+            aliases_override_builtins: world.aliases_override_builtins,
+            turn_limit: world.turn_limit,
+            turn_limit_text: world.turn_limit_text.as_deref().map(multiline_desc_to_toml),
+            ambiguity_text: world.ambiguity_text.clone(),
+            unknown_command_text: world
+                .unknown_command_text
+                .as_deref()
+                .map(multiline_desc_to_toml),
+            player_desc: world.player_desc.as_deref().map(multiline_desc_to_toml),
+            player_desc_state: world
+                .player_desc_states
+                .iter()
+                .map(state_desc_to_config)
+                .collect(),
+            player_max_hp: world.player_max_hp,
+        },
+        room: rooms.into_iter().map(room_to_config).collect(),
+        item: items.into_iter().map(item_to_config).collect(),
+        npc: npcs.into_iter().map(npc_to_config).collect(),
+        global_condition: world
+            .global_conditions
+            .iter()
+            .map(|gc| GlobalConditionConfig {
+                id: gc.id.clone(),
+                conditions: conditions_to_strings(&gc.conditions),
+                allowed_rooms: gc.allowed_rooms.clone(),
+                disallowed_rooms: gc.disallowed_rooms.clone(),
+                response: multiline_desc_to_toml(&gc.response),
+                effects: gc.effects.clone(),
+                one_shot: gc.one_shot,
+            })
+            .collect(),
+        global_action: world.global_actions.iter().map(action_to_config).collect(),
+        ending: world
+            .endings
+            .iter()
+            .map(|e| EndingConfig {
+                id: e.id.clone(),
+                conditions: conditions_to_strings(&e.conditions),
+                text: multiline_desc_to_toml(&e.text),
+                victory: e.is_victory,
+            })
+            .collect(),
+        score: world
+            .score_events
+            .iter()
+            .map(|s| ScoreConfig {
+                id: s.id.clone(),
+                conditions: conditions_to_strings(&s.conditions),
+                points: s.points as i64,
+            })
+            .collect(),
+        rank: world
+            .ranks
+            .iter()
+            .map(|r| RankConfig {
+                min_score: r.min_score as i64,
+                title: r.title.clone(),
+            })
+            .collect(),
+        easter_verb: world
+            .easter_verbs
+            .iter()
+            .map(|ev| EasterVerbConfig {
+                verbs: ev.verbs.clone(),
+                conditions: conditions_to_strings(&ev.conditions),
+                response: multiline_desc_to_toml(&ev.response),
+            })
+            .collect(),
+        on_take: world
+            .on_take
+            .iter()
+            .map(|t| TakeTriggerConfig {
+                item: t.item.clone(),
+                conditions: conditions_to_strings(&t.conditions),
+                response: multiline_desc_to_toml(&t.response),
+                effects: t.effects.clone(),
+            })
+            .collect(),
+        messages: messages_to_config(&world.messages),
+    }
+}
+
+fn build_messages(cfg: MessagesConfig) -> Messages {
+    let defaults = Messages::default();
+    Messages {
+        take: cfg.take.unwrap_or(defaults.take),
+        take_from: cfg.take_from.unwrap_or(defaults.take_from),
+        take_from_npc: cfg.take_from_npc.unwrap_or(defaults.take_from_npc),
+        already_have: cfg.already_have.unwrap_or(defaults.already_have),
+        cant_take: cfg.cant_take.unwrap_or(defaults.cant_take),
+        drop: cfg.drop.unwrap_or(defaults.drop),
+        not_here: cfg.not_here.unwrap_or(defaults.not_here),
+        inventory_empty: cfg.inventory_empty.unwrap_or(defaults.inventory_empty),
+        inventory_header: cfg.inventory_header.unwrap_or(defaults.inventory_header),
+        go: cfg.go.unwrap_or(defaults.go),
+        cant_go: cfg.cant_go.unwrap_or(defaults.cant_go),
+        time_passes: cfg.time_passes.unwrap_or(defaults.time_passes),
+        take_what: cfg.take_what.unwrap_or(defaults.take_what),
+        drop_what: cfg.drop_what.unwrap_or(defaults.drop_what),
+        examine_what: cfg.examine_what.unwrap_or(defaults.examine_what),
+        give: cfg.give.unwrap_or(defaults.give),
+        cant_give: cfg.cant_give.unwrap_or(defaults.cant_give),
+        npc_refuses: cfg.npc_refuses.unwrap_or(defaults.npc_refuses),
+        npc_lacks: cfg.npc_lacks.unwrap_or(defaults.npc_lacks),
+        cant_drop: cfg.cant_drop.unwrap_or(defaults.cant_drop),
+        not_carrying: cfg.not_carrying.unwrap_or(defaults.not_carrying),
+        no_container: cfg.no_container.unwrap_or(defaults.no_container),
+        not_container: cfg.not_container.unwrap_or(defaults.not_container),
+        no_npc: cfg.no_npc.unwrap_or(defaults.no_npc),
+        npc_gone: cfg.npc_gone.unwrap_or(defaults.npc_gone),
+        nothing_to_take: cfg.nothing_to_take.unwrap_or(defaults.nothing_to_take),
+        nothing_to_drop: cfg.nothing_to_drop.unwrap_or(defaults.nothing_to_drop),
+        nothing_to_give: cfg.nothing_to_give.unwrap_or(defaults.nothing_to_give),
+        nothing_to_examine: cfg
+            .nothing_to_examine
+            .unwrap_or(defaults.nothing_to_examine),
+        nothing_special: cfg.nothing_special.unwrap_or(defaults.nothing_special),
+        npc_nothing_special: cfg
+            .npc_nothing_special
+            .unwrap_or(defaults.npc_nothing_special),
+        npc_nothing_to_say: cfg
+            .npc_nothing_to_say
+            .unwrap_or(defaults.npc_nothing_to_say),
+        npc_nothing_new: cfg.npc_nothing_new.unwrap_or(defaults.npc_nothing_new),
+        no_answer: cfg.no_answer.unwrap_or(defaults.no_answer),
+        cant_do_that: cfg.cant_do_that.unwrap_or(defaults.cant_do_that),
+        be_more_specific: cfg.be_more_specific.unwrap_or(defaults.be_more_specific),
+        go_where: cfg.go_where.unwrap_or(defaults.go_where),
+        talk_to_whom: cfg.talk_to_whom.unwrap_or(defaults.talk_to_whom),
+        give_what: cfg.give_what.unwrap_or(defaults.give_what),
+        give_to_whom: cfg.give_to_whom.unwrap_or(defaults.give_to_whom),
+        give_what_to_whom: cfg.give_what_to_whom.unwrap_or(defaults.give_what_to_whom),
+        give_unclear: cfg.give_unclear.unwrap_or(defaults.give_unclear),
+        take_from_where: cfg.take_from_where.unwrap_or(defaults.take_from_where),
+        take_from_whom: cfg.take_from_whom.unwrap_or(defaults.take_from_whom),
+        take_what_from_whom: cfg
+            .take_what_from_whom
+            .unwrap_or(defaults.take_what_from_whom),
+        take_all_from_where: cfg
+            .take_all_from_where
+            .unwrap_or(defaults.take_all_from_where),
+        take_from_unclear: cfg.take_from_unclear.unwrap_or(defaults.take_from_unclear),
+        not_in_container: cfg.not_in_container.unwrap_or(defaults.not_in_container),
+        nothing_inside: cfg.nothing_inside.unwrap_or(defaults.nothing_inside),
+        store_what: cfg.store_what.unwrap_or(defaults.store_what),
+        not_carrying_any: cfg.not_carrying_any.unwrap_or(defaults.not_carrying_any),
+        cant_store: cfg.cant_store.unwrap_or(defaults.cant_store),
+        store_where: cfg.store_where.unwrap_or(defaults.store_where),
+        store_where_which: cfg.store_where_which.unwrap_or(defaults.store_where_which),
+        store_in_itself: cfg.store_in_itself.unwrap_or(defaults.store_in_itself),
+        container_full: cfg.container_full.unwrap_or(defaults.container_full),
+        store: cfg.store.unwrap_or(defaults.store),
+        store_all_where: cfg.store_all_where.unwrap_or(defaults.store_all_where),
+        store_all_which: cfg.store_all_which.unwrap_or(defaults.store_all_which),
+        nothing_to_store: cfg.nothing_to_store.unwrap_or(defaults.nothing_to_store),
+        container_unusable: cfg
+            .container_unusable
+            .unwrap_or(defaults.container_unusable),
+        cooldown: cfg.cooldown.unwrap_or(defaults.cooldown),
+        unknown_command: cfg.unknown_command.unwrap_or(defaults.unknown_command),
+        goto_unknown: cfg.goto_unknown.unwrap_or(defaults.goto_unknown),
+        goto_here: cfg.goto_here.unwrap_or(defaults.goto_here),
+        goto_no_route: cfg.goto_no_route.unwrap_or(defaults.goto_no_route),
+        moves_stopped: cfg.moves_stopped.unwrap_or(defaults.moves_stopped),
+        verbose_mode: cfg.verbose_mode.unwrap_or(defaults.verbose_mode),
+        brief_mode: cfg.brief_mode.unwrap_or(defaults.brief_mode),
+        superbrief_mode: cfg.superbrief_mode.unwrap_or(defaults.superbrief_mode),
+        turns: cfg.turns.unwrap_or(defaults.turns),
+        turns_of_limit: cfg.turns_of_limit.unwrap_or(defaults.turns_of_limit),
+        score: cfg.score.unwrap_or(defaults.score),
+        score_rank: cfg.score_rank.unwrap_or(defaults.score_rank),
+        score_up: cfg.score_up.unwrap_or(defaults.score_up),
+        health: cfg.health.unwrap_or(defaults.health),
+        wear: cfg.wear.unwrap_or(defaults.wear),
+        take_off: cfg.take_off.unwrap_or(defaults.take_off),
+        already_wearing: cfg.already_wearing.unwrap_or(defaults.already_wearing),
+        wearing: cfg.wearing.unwrap_or(defaults.wearing),
+        out_of_time: cfg.out_of_time.unwrap_or(defaults.out_of_time),
+        checkpoint_saved: cfg.checkpoint_saved.unwrap_or(defaults.checkpoint_saved),
+        no_checkpoint: cfg.no_checkpoint.unwrap_or(defaults.no_checkpoint),
+        checkpoint_restored: cfg
+            .checkpoint_restored
+            .unwrap_or(defaults.checkpoint_restored),
+        revert_hint: cfg.revert_hint.unwrap_or(defaults.revert_hint),
+        game_over: cfg.game_over.unwrap_or(defaults.game_over),
+        game_over_revert: cfg.game_over_revert.unwrap_or(defaults.game_over_revert),
+        goodbye: cfg.goodbye.unwrap_or(defaults.goodbye),
+    }
+}
+
+/// Only messages that differ from the defaults are written back out.
+fn messages_to_config(messages: &Messages) -> MessagesConfig {
+    let defaults = Messages::default();
+    let changed = |value: &String, default: String| (*value != default).then(|| value.clone());
+    MessagesConfig {
+        take: changed(&messages.take, defaults.take),
+        take_from: changed(&messages.take_from, defaults.take_from),
+        take_from_npc: changed(&messages.take_from_npc, defaults.take_from_npc),
+        already_have: changed(&messages.already_have, defaults.already_have),
+        cant_take: changed(&messages.cant_take, defaults.cant_take),
+        drop: changed(&messages.drop, defaults.drop),
+        not_here: changed(&messages.not_here, defaults.not_here),
+        inventory_empty: changed(&messages.inventory_empty, defaults.inventory_empty),
+        inventory_header: changed(&messages.inventory_header, defaults.inventory_header),
+        go: changed(&messages.go, defaults.go),
+        cant_go: changed(&messages.cant_go, defaults.cant_go),
+        time_passes: changed(&messages.time_passes, defaults.time_passes),
+        take_what: changed(&messages.take_what, defaults.take_what),
+        drop_what: changed(&messages.drop_what, defaults.drop_what),
+        examine_what: changed(&messages.examine_what, defaults.examine_what),
+        give: changed(&messages.give, defaults.give),
+        cant_give: changed(&messages.cant_give, defaults.cant_give),
+        npc_refuses: changed(&messages.npc_refuses, defaults.npc_refuses),
+        npc_lacks: changed(&messages.npc_lacks, defaults.npc_lacks),
+        cant_drop: changed(&messages.cant_drop, defaults.cant_drop),
+        not_carrying: changed(&messages.not_carrying, defaults.not_carrying),
+        no_container: changed(&messages.no_container, defaults.no_container),
+        not_container: changed(&messages.not_container, defaults.not_container),
+        no_npc: changed(&messages.no_npc, defaults.no_npc),
+        npc_gone: changed(&messages.npc_gone, defaults.npc_gone),
+        nothing_to_take: changed(&messages.nothing_to_take, defaults.nothing_to_take),
+        nothing_to_drop: changed(&messages.nothing_to_drop, defaults.nothing_to_drop),
+        nothing_to_give: changed(&messages.nothing_to_give, defaults.nothing_to_give),
+        nothing_to_examine: changed(&messages.nothing_to_examine, defaults.nothing_to_examine),
+        nothing_special: changed(&messages.nothing_special, defaults.nothing_special),
+        npc_nothing_special: changed(&messages.npc_nothing_special, defaults.npc_nothing_special),
+        npc_nothing_to_say: changed(&messages.npc_nothing_to_say, defaults.npc_nothing_to_say),
+        npc_nothing_new: changed(&messages.npc_nothing_new, defaults.npc_nothing_new),
+        no_answer: changed(&messages.no_answer, defaults.no_answer),
+        cant_do_that: changed(&messages.cant_do_that, defaults.cant_do_that),
+        be_more_specific: changed(&messages.be_more_specific, defaults.be_more_specific),
+        go_where: changed(&messages.go_where, defaults.go_where),
+        talk_to_whom: changed(&messages.talk_to_whom, defaults.talk_to_whom),
+        give_what: changed(&messages.give_what, defaults.give_what),
+        give_to_whom: changed(&messages.give_to_whom, defaults.give_to_whom),
+        give_what_to_whom: changed(&messages.give_what_to_whom, defaults.give_what_to_whom),
+        give_unclear: changed(&messages.give_unclear, defaults.give_unclear),
+        take_from_where: changed(&messages.take_from_where, defaults.take_from_where),
+        take_from_whom: changed(&messages.take_from_whom, defaults.take_from_whom),
+        take_what_from_whom: changed(&messages.take_what_from_whom, defaults.take_what_from_whom),
+        take_all_from_where: changed(&messages.take_all_from_where, defaults.take_all_from_where),
+        take_from_unclear: changed(&messages.take_from_unclear, defaults.take_from_unclear),
+        not_in_container: changed(&messages.not_in_container, defaults.not_in_container),
+        nothing_inside: changed(&messages.nothing_inside, defaults.nothing_inside),
+        store_what: changed(&messages.store_what, defaults.store_what),
+        not_carrying_any: changed(&messages.not_carrying_any, defaults.not_carrying_any),
+        cant_store: changed(&messages.cant_store, defaults.cant_store),
+        store_where: changed(&messages.store_where, defaults.store_where),
+        store_where_which: changed(&messages.store_where_which, defaults.store_where_which),
+        store_in_itself: changed(&messages.store_in_itself, defaults.store_in_itself),
+        container_full: changed(&messages.container_full, defaults.container_full),
+        store: changed(&messages.store, defaults.store),
+        store_all_where: changed(&messages.store_all_where, defaults.store_all_where),
+        store_all_which: changed(&messages.store_all_which, defaults.store_all_which),
+        nothing_to_store: changed(&messages.nothing_to_store, defaults.nothing_to_store),
+        container_unusable: changed(&messages.container_unusable, defaults.container_unusable),
+        cooldown: changed(&messages.cooldown, defaults.cooldown),
+        unknown_command: changed(&messages.unknown_command, defaults.unknown_command),
+        goto_unknown: changed(&messages.goto_unknown, defaults.goto_unknown),
+        goto_here: changed(&messages.goto_here, defaults.goto_here),
+        goto_no_route: changed(&messages.goto_no_route, defaults.goto_no_route),
+        moves_stopped: changed(&messages.moves_stopped, defaults.moves_stopped),
+        verbose_mode: changed(&messages.verbose_mode, defaults.verbose_mode),
+        brief_mode: changed(&messages.brief_mode, defaults.brief_mode),
+        superbrief_mode: changed(&messages.superbrief_mode, defaults.superbrief_mode),
+        turns: changed(&messages.turns, defaults.turns),
+        turns_of_limit: changed(&messages.turns_of_limit, defaults.turns_of_limit),
+        score: changed(&messages.score, defaults.score),
+        score_rank: changed(&messages.score_rank, defaults.score_rank),
+        score_up: changed(&messages.score_up, defaults.score_up),
+        health: changed(&messages.health, defaults.health),
+        wear: changed(&messages.wear, defaults.wear),
+        take_off: changed(&messages.take_off, defaults.take_off),
+        already_wearing: changed(&messages.already_wearing, defaults.already_wearing),
+        wearing: changed(&messages.wearing, defaults.wearing),
+        out_of_time: changed(&messages.out_of_time, defaults.out_of_time),
+        checkpoint_saved: changed(&messages.checkpoint_saved, defaults.checkpoint_saved),
+        no_checkpoint: changed(&messages.no_checkpoint, defaults.no_checkpoint),
+        checkpoint_restored: changed(&messages.checkpoint_restored, defaults.checkpoint_restored),
+        revert_hint: changed(&messages.revert_hint, defaults.revert_hint),
+        game_over: changed(&messages.game_over, defaults.game_over),
+        game_over_revert: changed(&messages.game_over_revert, defaults.game_over_revert),
+        goodbye: changed(&messages.goodbye, defaults.goodbye),
+    }
+}
+
+fn state_desc_to_config(sd: &StateDesc) -> StateDescConfig {
+    StateDescConfig {
+        conditions: conditions_to_strings(&sd.conditions),
+        text: multiline_desc_to_toml(&sd.text),
+    }
+}
+
+fn room_to_config(room: &Room) -> RoomConfig {
+    RoomConfig {
+        id: room.id.clone(),
+        name: room.name.clone(),
+        desc: multiline_desc_to_toml(&room.desc),
+        exit: room
+            .exits
+            .iter()
+            .map(|e| ExitConfig {
+                direction: e.direction.clone(),
+                target: e.target.clone(),
+                verbs: e.verbs.clone(),
+                conditions: conditions_to_strings(&e.conditions),
+                travel_text: e.travel_text.as_deref().map(multiline_desc_to_toml),
+                description: e.description.as_deref().map(multiline_desc_to_toml),
+                secret: e.secret,
+                arrival_effects: e.arrival_effects.clone(),
+                verb_condition: e
+                    .verb_conditions
+                    .iter()
+                    .map(|vc| VerbConditionConfig {
+                        verb: vc.verb.clone(),
+                        conditions: conditions_to_strings(&vc.conditions),
+                    })
+                    .collect(),
+                turn_cost: e.turn_cost,
+            })
+            .collect(),
+        action: room.actions.iter().map(action_to_config).collect(),
+        state_desc: room.state_descs.iter().map(state_desc_to_config).collect(),
+        name_state: room
+            .name_states
+            .iter()
+            .map(|ns| StateDescConfig {
+                conditions: conditions_to_strings(&ns.conditions),
+                text: ns.text.clone(),
+            })
+            .collect(),
+        liquid_source: room.liquid_source.clone(),
+        on_drop: room
+            .on_drop
+            .iter()
+            .map(|d| DropTriggerConfig {
+                item: d.item.clone(),
+                response: multiline_desc_to_toml(&d.response),
+                effects: d.effects.clone(),
+                one_shot: d.one_shot,
+            })
+            .collect(),
+        unknown_command_text: room
+            .unknown_command_text
+            .as_deref()
+            .map(multiline_desc_to_toml),
+    }
+}
+
+fn action_to_config(a: &Action) -> ActionConfig {
+    ActionConfig {
+        id: a.id.clone(),
+        verbs: a.verbs.clone(),
+        nouns: a.nouns.clone(),
+        response: multiline_desc_to_toml(&a.response),
+        effects: a.effects.clone(),
+        conditions: conditions_to_strings(&a.conditions),
+        scope_requirements: a.scope_requirements.clone(),
+        requires_inventory: a.requires_inventory.clone(),
+        requires_reachable: a.requires_reachable.clone(),
+        instrument: a.instrument.clone(),
+        conditional_effect: a
+            .conditional_effects
+            .iter()
+            .map(|ce| ConditionalEffectConfig {
+                conditions: conditions_to_strings(&ce.conditions),
+                effects: ce.effects.clone(),
+            })
+            .collect(),
+        transform: a
+            .transforms
+            .iter()
+            .map(|t| TransformConfig {
+                from: t.from.clone(),
+                into: t.into.clone(),
+            })
+            .collect(),
+        spawn: a
+            .spawns
+            .iter()
+            .map(|sp| SpawnConfig {
+                item: sp.item.clone(),
+                location: item_location_to_string(&Some(sp.location.clone())),
+            })
+            .collect(),
+        remove_items: a.remove_items.clone(),
+        toggle_flag: a.toggle_flag.clone(),
+        response_off: a.response_off.as_deref().map(multiline_desc_to_toml),
+        missing_inventory_text: a
+            .missing_inventory_text
+            .as_deref()
+            .map(multiline_desc_to_toml),
+        missing_scope_text: a.missing_scope_text.as_deref().map(multiline_desc_to_toml),
+        blocked_text: a.blocked_text.as_deref().map(multiline_desc_to_toml),
+        keep_items: a.keep_items,
+        cooldown_turns: a.cooldown_turns,
+        cooldown_text: a.cooldown_text.as_deref().map(multiline_desc_to_toml),
+    }
+}
+
+fn item_location_to_string(loc: &Option<ItemLocation>) -> String {
+    match loc {
+        None => "nowhere".to_string(),
+        Some(ItemLocation::Room(id)) => format!("room:{}", id),
+        Some(ItemLocation::Inventory) => "inventory".to_string(),
+        Some(ItemLocation::Item(id)) => format!("item:{}", id),
+        Some(ItemLocation::Npc(id)) => format!("npc:{}", id),
+    }
+}
+
+fn item_to_config(item: &Item) -> ItemConfig {
+    let mut ic = ItemConfig {
+        id: item.id.clone(),
+        name: item.name.clone(),
+        aliases: item.aliases.clone(),
+        start_location: item_location_to_string(&item.start_location),
+        room_text: multiline_desc_to_toml(&item.room_text),
+        inventory_text: multiline_desc_to_toml(&item.inventory_text),
+        examine_text: multiline_desc_to_toml(&item.examine_text),
+        conditions: conditions_to_strings(&item.conditions),
+        portable: Some(item.portable),
+        droppable: item.droppable,
+        examine_requires_inventory: item.examine_requires_inventory,
+        inscription: item.inscription.as_deref().map(multiline_desc_to_toml),
+        kind: None,
+        capacity: None,
+        container_conditions: Vec::new(),
+        complete_when: Vec::new(),
+        complete_flag: None,
+        container_closed_text: None,
+        container_open_text: None,
+        container_use_conditions: Vec::new(),
+        container_use_blocked_text: None,
+        complete_text: None,
+        container_verbs: Vec::new(),
+        container_prep: None,
+        decays_after: item.decays_after,
+        decays_into: item.decays_into.clone(),
+        decay_text: item.decay_text.as_deref().map(multiline_desc_to_toml),
+        room_state: item
+            .room_state_texts
+            .iter()
+            .map(state_desc_to_config)
+            .collect(),
+        wearable: item.wearable,
+        holds_liquid: item.holds_liquid,
+        start_liquid: item.start_liquid.clone(),
+        liquid_source: item.liquid_source.clone(),
+    };
+
+    if let ItemKind::Container(props) = &item.kind {
+        ic.kind = Some("container".to_string());
+        ic.capacity = props.capacity;
+        ic.container_conditions = conditions_to_strings(&props.conditions);
+        ic.complete_when = props.complete_when.clone();
+        ic.complete_flag = props.complete_flag.clone();
+        ic.container_closed_text = Some(props.closed_text.clone());
+        ic.container_open_text = props.open_text.as_deref().map(multiline_desc_to_toml);
+        ic.container_use_conditions = conditions_to_strings(&props.use_conditions);
+        ic.container_use_blocked_text = props.use_blocked_text.clone();
+        ic.complete_text = props.complete_text.clone();
+        ic.container_verbs = props.verbs.clone();
+        ic.container_prep = Some(props.prep.clone());
+    }
+
+    ic
+}
+
+fn npc_to_config(npc: &Npc) -> NpcConfig {
+    NpcConfig {
+        id: npc.id.clone(),
+        name: npc.name.clone(),
+        aliases: npc.aliases.clone(),
+        start_room: npc.start_room.clone(),
+        room_text: multiline_desc_to_toml(&npc.room_text),
+        examine_text: multiline_desc_to_toml(&npc.examine_text),
+        conditions: conditions_to_strings(&npc.conditions),
+        action: npc.actions.iter().map(action_to_config).collect(),
+        roam_enabled: npc.roam.as_ref().map(|r| r.enabled),
+        roam_rooms: npc
+            .roam
+            .as_ref()
+            .map(|r| r.allowed_rooms.clone())
+            .unwrap_or_default(),
+        roam_chance_percent: npc.roam.as_ref().map(|r| r.chance_percent),
+        carries_on_roam: npc.carries_on_roam.clone(),
+        block_movement: Some(npc.block_movement),
+        block_conditions: conditions_to_strings(&npc.block_conditions),
+        block_text: npc.block_text.clone(),
+        block_exits: npc.block_exits.clone(),
+        block_priority: npc.block_priority,
+        foe: Some(npc.foe),
+        attack_chance_percent: Some(npc.attack_chance_percent),
+        attack_text: npc.attack_text.as_deref().map(multiline_desc_to_toml),
+        attack_effects: npc.attack_effects.clone(),
+        dialogue: npc
+            .dialogue
+            .iter()
+            .map(|d| NpcDialogueConfig {
+                id: d.id.clone(),
+                conditions: conditions_to_strings(&d.conditions),
+                response: multiline_desc_to_toml(&d.response),
+                effects: d.effects.clone(),
+                one_shot: d.one_shot,
+                sets_disposition: d.sets_disposition.clone(),
+            })
+            .collect(),
+        disposition: npc.disposition.clone(),
+        want: npc
+            .wants
+            .iter()
+            .map(|w| NpcWantConfig {
+                item: w.item.clone(),
+                conditions: conditions_to_strings(&w.conditions),
+                response: multiline_desc_to_toml(&w.response),
+                effects: w.effects.clone(),
+                consumed: w.consumed,
+            })
+            .collect(),
+        idle: npc.idle_texts.iter().map(state_desc_to_config).collect(),
+        on_player_enter: npc
+            .on_player_enter
+            .iter()
+            .map(|t| NpcTriggerConfig {
+                id: t.id.clone(),
+                conditions: conditions_to_strings(&t.conditions),
+                response: multiline_desc_to_toml(&t.response),
+                effects: t.effects.clone(),
+            })
+            .collect(),
+        responds_to_call: npc.responds_to_call,
+        call_text: npc.call_text.as_deref().map(multiline_desc_to_toml),
+        comes_when_called: npc.comes_when_called,
+    }
+}
+
+/// Refuse worlds that need a newer engine. Only major.minor is compared, so
+/// "0.2", "0.2.5" and "v0.2" all mean 0.2.
+fn check_engine_version(required: &str) -> Result<(), WorldLoadError> {
+    let wanted = parse_major_minor(required).ok_or_else(|| {
+        WorldLoadError::Invalid(format!(
+            "engine_min_version '{}' is not a version like \"0.2\"",
+            required
+        ))
+    })?;
+    let current = parse_major_minor(env!("CARGO_PKG_VERSION")).unwrap_or((0, 0));
+
+    if wanted > current {
+        return Err(WorldLoadError::EngineTooOld {
+            required: required.trim().to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn parse_major_minor(v: &str) -> Option<(u64, u64)> {
+    let v = v.trim();
+    let mut parts = v.strip_prefix('v').unwrap_or(v).split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = match parts.next() {
+        Some(m) => m.parse().ok()?,
+        None => 0,
+    };
+    Some((major, minor))
+}
+
+fn parse_conditions(raw: &[String]) -> Vec<Condition> {
+    raw.iter().map(|c| Condition::parse(c)).collect()
+}
+
+fn conditions_to_strings(conds: &[Condition]) -> Vec<String> {
+    conds.iter().map(Condition::to_string).collect()
+}
+
 fn normalize_multiline_desc(raw: &str) -> String {
     let mut result = String::new();
     let mut pending_blank_lines = 0usize;
@@ -635,11 +1853,77 @@ fn normalize_multiline_desc(raw: &str) -> String {
     result
 }
 
-////////////////////////////
-/// ITEM PARSE HELPERS   ///
-////////////////////////////
+/// The inverse of `normalize_multiline_desc`: spell a loaded text out so that
+/// loading it again gives the same text back. Each line break becomes one blank
+/// line and each paragraph break two.
+fn multiline_desc_to_toml(text: &str) -> String {
+    text.split("\n\n")
+        .map(|paragraph| paragraph.split('\n').collect::<Vec<_>>().join("\n\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n\n")
+}
+
+//////////////////////////
+// ITEM PARSE HELPERS   //
+//////////////////////////
+
+/// Shared by room, NPC and global actions.
+fn build_action(a: ActionConfig) -> Result<Action, WorldLoadError> {
+    let mut spawns = Vec::new();
+    for sp in a.spawn {
+        let location = parse_item_location(&sp.location, "spawn location").map_err(|e| {
+            WorldLoadError::Invalid(format!("Action '{}' spawns '{}': {}", a.id, sp.item, e))
+        })?;
+        spawns.push(ItemSpawn {
+            item: sp.item,
+            location,
+        });
+    }
+
+    Ok(Action {
+        id: a.id,
+        verbs: a.verbs,
+        nouns: a.nouns,
+        response: normalize_multiline_desc(&a.response),
+        effects: a.effects,
+        conditions: parse_conditions(&a.conditions),
+        scope_requirements: a.scope_requirements,
+        requires_inventory: a.requires_inventory,
+        requires_reachable: a.requires_reachable,
+        instrument: a.instrument,
+        conditional_effects: a
+            .conditional_effect
+            .into_iter()
+            .map(|ce| ConditionalEffect {
+                conditions: parse_conditions(&ce.conditions),
+                effects: ce.effects,
+            })
+            .collect(),
+        transforms: a
+            .transform
+            .into_iter()
+            .map(|t| ItemTransform {
+                from: t.from,
+                into: t.into,
+            })
+            .collect(),
+        spawns,
+        remove_items: a.remove_items,
+        toggle_flag: a.toggle_flag,
+        response_off: a.response_off.map(|s| normalize_multiline_desc(&s)),
+        missing_inventory_text: a
+            .missing_inventory_text
+            .map(|s| normalize_multiline_desc(&s)),
+        missing_scope_text: a.missing_scope_text.map(|s| normalize_multiline_desc(&s)),
+        blocked_text: a.blocked_text.map(|s| normalize_multiline_desc(&s)),
+        keep_items: a.keep_items,
+        cooldown_turns: a.cooldown_turns,
+        cooldown_text: a.cooldown_text.map(|s| normalize_multiline_desc(&s)),
+    })
+}
 
-fn parse_item_location(s: &str) -> Result<ItemLocation, String> {
+/// `field` names the setting in error messages, e.g. "start_location".
+fn parse_item_location(s: &str, field: &str) -> Result<ItemLocation, String> {
     let s = s.trim();
 
     if s.eq_ignore_ascii_case("inventory") {
@@ -649,7 +1933,7 @@ fn parse_item_location(s: &str) -> Result<ItemLocation, String> {
     if let Some(rest) = s.strip_prefix("room:") {
         let room_id = rest.trim();
         if room_id.is_empty() {
-            return Err(format!("Invalid start_location '{}': empty room id", s));
+            return Err(format!("Invalid {} '{}': empty room id", field, s));
         }
         return Ok(ItemLocation::Room(room_id.to_string()));
     }
@@ -657,7 +1941,7 @@ fn parse_item_location(s: &str) -> Result<ItemLocation, String> {
     if let Some(rest) = s.strip_prefix("item:") {
         let item_id = rest.trim();
         if item_id.is_empty() {
-            return Err(format!("Invalid start_location '{}': empty item id", s));
+            return Err(format!("Invalid {} '{}': empty item id", field, s));
         }
         return Ok(ItemLocation::Item(item_id.to_string()));
     }
@@ -665,28 +1949,40 @@ fn parse_item_location(s: &str) -> Result<ItemLocation, String> {
     if let Some(rest) = s.strip_prefix("npc:") {
         let npc_id = rest.trim();
         if npc_id.is_empty() {
-            return Err(format!("Invalid start_location '{}': empty npc id", s));
+            return Err(format!("Invalid {} '{}': empty npc id", field, s));
         }
         return Ok(ItemLocation::Npc(npc_id.to_string()));
     }
 
+    // "nowhere" is only meaningful for start_location and is handled by its caller
+    let tail = if field == "start_location" {
+        "'inventory', or 'nowhere'"
+    } else {
+        "or 'inventory'"
+    };
     Err(format!(
-        "Invalid start_location '{}': expected 'room:<id>', 'item:<id>', 'npc:<id>', or 'inventory'",
-        s
+        "Invalid {} '{}': expected 'room:<id>', 'item:<id>', 'npc:<id>', {}",
+        field, s, tail
     ))
 }
 
 fn parse_item_kind(ic: &ItemConfig) -> ItemKind {
     match ic.kind.as_deref().map(|s| s.to_lowercase()) {
-        Some(ref k) if k == "container" => ItemKind::Container(ContainerProps {
+        Some(ref k) if k == "container" => ItemKind::Container(Box::new(ContainerProps {
             capacity: ic.capacity,
-            conditions: ic.container_conditions.clone(),
+            conditions: parse_conditions(&ic.container_conditions),
             complete_when: ic.complete_when.clone(),
             complete_flag: ic.complete_flag.clone(),
             closed_text: ic
                 .container_closed_text
                 .clone()
                 .unwrap_or_else(|| "It is currently closed.".to_string()),
+            open_text: ic
+                .container_open_text
+                .as_deref()
+                .map(normalize_multiline_desc),
+            use_conditions: parse_conditions(&ic.container_use_conditions),
+            use_blocked_text: ic.container_use_blocked_text.clone(),
             complete_text: ic.complete_text.clone(),
             verbs: if ic.container_verbs.is_empty() {
                 vec!["put".to_string()]
@@ -697,7 +1993,7 @@ fn parse_item_kind(ic: &ItemConfig) -> ItemKind {
                 .container_prep
                 .clone()
                 .unwrap_or_else(|| "in".to_string()),
-        }),
+        })),
         Some(ref k) if k == "simple" => ItemKind::Simple,
         Some(ref k) if !k.is_empty() => {
             eprintln!("Warning: unknown item kind '{}', defaulting to Simple", k);
@@ -724,3 +2020,165 @@ fn parse_name_and_aliases(raw: &str) -> (String, Vec<String>) {
     let aliases = parts.into_iter().skip(1).collect();
     (primary, aliases)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{load_world_from_file, load_world_from_str};
+    use crate::world::{ItemLocation, WorldLoadError};
+
+    fn assert_round_trips(world_toml: &str) {
+        let world = load_world_from_str(world_toml).expect("world should load");
+        let exported = world.to_toml_string().expect("world should export");
+        let reloaded = load_world_from_str(&exported).expect("export should load");
+        assert_eq!(world, reloaded);
+    }
+
+    #[test]
+    fn export_keeps_line_and_paragraph_breaks() {
+        assert_round_trips(
+            r#"
+            [world]
+            id = "t"
+            name = "T"
+            start_room = "hall"
+
+            [[room]]
+            id = "hall"
+            name = "Hall"
+            desc = """
+            A long hall,
+            wrapped over two lines.
+
+            A line of its own.
+
+
+            A new paragraph.
+            """
+
+            [[room.action]]
+            id = "knock"
+            verbs = ["knock"]
+            nouns = ["wall"]
+            response = """
+            Knock.
+
+            Knock.
+            """
+
+            [[item]]
+            id = "note"
+            name = "note"
+            start_location = "room:hall"
+            examine_text = """
+            Dear reader,
+
+
+            Goodbye.
+            """
+            "#,
+        );
+    }
+
+    #[test]
+    fn default_world_round_trips() {
+        assert_round_trips(include_str!("../../public/default.toml"));
+    }
+
+    const HALL: &str = r#"
+        [world]
+        id = "t"
+        name = "T"
+        start_room = "hall"
+
+        [[room]]
+        id = "hall"
+        name = "Hall"
+        desc = "A hall."
+    "#;
+
+    fn load_error(world_toml: &str) -> WorldLoadError {
+        load_world_from_str(world_toml).expect_err("world should not load")
+    }
+
+    #[test]
+    fn malformed_worlds_give_specific_errors() {
+        assert!(matches!(
+            load_world_from_file(std::path::Path::new("no/such/world.toml")),
+            Err(WorldLoadError::Io(_))
+        ));
+        assert!(matches!(load_error("[world"), WorldLoadError::Parse(_)));
+
+        let duplicate = format!(
+            "{}\n[[room]]\nid = \"hall\"\nname = \"Hall\"\ndesc = \"Again.\"",
+            HALL
+        );
+        assert!(matches!(
+            load_error(&duplicate),
+            WorldLoadError::DuplicateId { kind: "room", ref id } if id == "hall"
+        ));
+
+        let missing_start = HALL.replace("start_room = \"hall\"", "start_room = \"attic\"");
+        assert!(matches!(
+            load_error(&missing_start),
+            WorldLoadError::MissingRoom { ref room_id, .. } if room_id == "attic"
+        ));
+
+        let too_new = HALL.replace("[world]", "[world]\nengine_min_version = \"999.0\"");
+        assert!(matches!(
+            load_error(&too_new),
+            WorldLoadError::EngineTooOld { .. }
+        ));
+
+        let bad_exit = format!(
+            "{}\n[[room.exit]]\ndirection = \"north\"\ntarget = \"attic\"",
+            HALL
+        );
+        assert!(matches!(
+            load_error(&bad_exit),
+            WorldLoadError::Validation(_)
+        ));
+    }
+
+    #[test]
+    fn ranks_are_sorted_and_checked() {
+        let ranks = |a: i64, b: i64| {
+            format!(
+                "{}\n[[rank]]\nmin_score = {}\ntitle = \"Master Thief\"\n[[rank]]\nmin_score = {}\ntitle = \"Novice\"",
+                HALL, a, b
+            )
+        };
+
+        let world = load_world_from_str(&ranks(50, 0)).expect("world should load");
+        let titles: Vec<&str> = world.ranks.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, ["Novice", "Master Thief"]);
+
+        assert!(matches!(
+            load_error(&ranks(50, -1)),
+            WorldLoadError::Invalid(_)
+        ));
+        assert!(matches!(
+            load_error(&ranks(10, 10)),
+            WorldLoadError::Validation(_)
+        ));
+    }
+
+    #[test]
+    fn start_inventory_overrides_start_location() {
+        let world = load_world_from_str(&format!(
+            r#"{}
+            [[item]]
+            id = "lamp"
+            name = "lamp"
+            start_location = "room:hall"
+            "#,
+            HALL.replace("[world]", "[world]\nstart_inventory = [\"lamp\"]")
+        ))
+        .expect("world should load");
+
+        let game = crate::GameState::new(world);
+        assert_eq!(
+            game.item_locations.get("lamp"),
+            Some(&ItemLocation::Inventory)
+        );
+    }
+}
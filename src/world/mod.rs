@@ -1,9 +1,19 @@
+mod condition;
+mod error;
 mod loader;
 mod model;
 mod validator;
 
-pub use loader::{load_world_from_file, load_world_from_str};
+pub use condition::{Condition, TurnOp};
+pub use error::WorldLoadError;
+pub use loader::{
+    load_world_from_file, load_world_from_json_file, load_world_from_json_str, load_world_from_str,
+};
 
 // Minimal, intentional surface area: re-export only what the game/engine uses.
-pub use model::{Action, Exit, Item, ItemKind, ItemLocation, Npc, Room, World};
+pub use model::{
+    Action, ConditionalEffect, ContainerProps, DropTrigger, EasterVerb, Ending, Exit, Item,
+    ItemKind, ItemLocation, ItemSpawn, ItemTransform, Messages, Npc, Room, TakeTrigger,
+    VerbCondition, World, WorldMetadata,
+};
 pub use validator::{ValidationError, validate_world};
@@ -1,23 +1,275 @@
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
-//////////////////////////////
-/// GAME STRUCTS AND ENUMS ///
-//////////////////////////////
+use super::condition::Condition;
+
+////////////////////////////
+// GAME STRUCTS AND ENUMS //
+////////////////////////////
 
 /// Runtime world type used by the game loop.
+#[derive(Debug, PartialEq)]
 pub struct World {
     #[allow(dead_code)]
     pub id: String,
     pub name: String,
     pub desc: String,
+    pub author: Option<String>,             // shown by "about"
+    pub version: Option<String>,            // shown by "about"
+    pub credits: Option<String>,            // shown by "about"
+    pub engine_min_version: Option<String>, // oldest engine ("major.minor") that can run it
     pub start_room: String,
-    pub rooms: HashMap<String, Room>,
-    pub items: HashMap<String, Item>,
-    pub npcs: HashMap<String, Npc>,
+    pub rooms: IndexMap<String, Room>,
+    pub items: IndexMap<String, Item>,
+    pub npcs: IndexMap<String, Npc>,
     pub global_conditions: Vec<GlobalCondition>,
     pub global_actions: Vec<Action>,
+    pub endings: Vec<Ending>,
+    pub score_events: Vec<ScoreEvent>,
+    pub ranks: Vec<Rank>, // sorted by min_score, lowest first
+    pub easter_verbs: Vec<EasterVerb>,
+    pub on_take: Vec<TakeTrigger>,
+    pub take_all_includes_containers: bool, // "take all" also empties open containers here
+    pub auto_open: bool,                    // taking from a closed container tries to open it
+    pub effects_clear_first: bool,          // effects lists apply every "!flag" before any "flag"
+    pub start_inventory: Vec<String>,       // item ids carried at the start
+    pub start_flags: Vec<String>,           // flags set before the first turn
+    pub autosave_rooms: Vec<String>,        // entering one saves a checkpoint for "revert"
+    pub deep_examine: bool,                 // examine lists nested container contents
+    pub show_affordances: bool,             // enables the "objects" command
+    pub goto_shows_each_room: bool,         // "goto" describes every room on the way
+    pub render_after_item_change: bool,     // redescribe the room when its items move
+    pub structured_room_text: bool,         // one Text block per room description source
+    pub command_aliases: IndexMap<String, String>, // lowercase input words => command
+    pub aliases_override_builtins: bool,    // lets an alias replace a built-in command
+    pub turn_limit: Option<u64>,            // the game is lost once more turns than this pass
+    pub turn_limit_text: Option<String>,    // shown when the turn limit runs out
+    pub ambiguity_text: Option<String>,     // "{candidates}" is replaced by the tied items
+    pub unknown_command_text: Option<String>, // replaces "I don't understand that command."
+    pub player_desc: Option<String>,        // shown for "examine me"
+    pub player_desc_states: Vec<StateDesc>, // conditional text appended to player_desc
+    pub player_max_hp: Option<u64>,         // starting and full health; None if untracked
+    pub messages: Messages,                 // built-in replies, reworded or translated
+}
+
+/// The descriptive header of a world, for save files and tooling.
+pub struct WorldMetadata<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    pub author: Option<&'a str>,
+    pub version: Option<&'a str>,
+    pub credits: Option<&'a str>,
+    pub engine_min_version: Option<&'a str>,
+}
+
+impl World {
+    pub fn metadata(&self) -> WorldMetadata<'_> {
+        WorldMetadata {
+            id: &self.id,
+            name: &self.name,
+            author: self.author.as_deref(),
+            version: self.version.as_deref(),
+            credits: self.credits.as_deref(),
+            engine_min_version: self.engine_min_version.as_deref(),
+        }
+    }
+}
+
+/// Built-in replies a world can reword (or translate) in its `[messages]` table.
+/// Words in braces ("{item}") are filled in when the message is shown.
+#[derive(Debug, PartialEq)]
+pub struct Messages {
+    pub take: String,          // {item}
+    pub take_from: String,     // {item}, {container}
+    pub take_from_npc: String, // {item}, {npc}
+    pub already_have: String,  // {item}
+    pub cant_take: String,     // {item}
+    pub drop: String,          // {item}
+    pub not_here: String,
+    pub inventory_empty: String,
+    pub inventory_header: String,
+    pub go: String,      // {direction}
+    pub cant_go: String, // {direction}
+    pub time_passes: String,
+    pub take_what: String,
+    pub drop_what: String,
+    pub examine_what: String,
+    pub give: String,        // {item}, {npc}
+    pub cant_give: String,   // {item}
+    pub npc_refuses: String, // {item}, {npc}
+    pub npc_lacks: String,   // {npc}
+    pub cant_drop: String,
+    pub not_carrying: String,
+    pub no_container: String,
+    pub not_container: String,
+    pub no_npc: String,
+    pub npc_gone: String,
+    pub nothing_to_take: String,
+    pub nothing_to_drop: String,
+    pub nothing_to_give: String,
+    pub nothing_to_examine: String,
+    pub nothing_special: String,     // {item}
+    pub npc_nothing_special: String, // {npc}
+    pub npc_nothing_to_say: String,  // {npc}
+    pub npc_nothing_new: String,     // {npc}
+    pub no_answer: String,
+    pub cant_do_that: String,
+    pub be_more_specific: String,
+    pub go_where: String,
+    pub talk_to_whom: String,
+    pub give_what: String,
+    pub give_to_whom: String,
+    pub give_what_to_whom: String,
+    pub give_unclear: String,
+    pub take_from_where: String,
+    pub take_from_whom: String,
+    pub take_what_from_whom: String,
+    pub take_all_from_where: String,
+    pub take_from_unclear: String,
+    pub not_in_container: String, // {container}
+    pub nothing_inside: String,   // {container}
+    pub store_what: String,       // {verb}
+    pub not_carrying_any: String,
+    pub cant_store: String,         // {verb}, {item}
+    pub store_where: String,        // {verb}, {item}
+    pub store_where_which: String,  // {verb}, {item}, {choices}
+    pub store_in_itself: String,    // {verb}, {item}
+    pub container_full: String,     // {container}
+    pub store: String,              // {verb}, {item}, {prep}, {container}
+    pub store_all_where: String,    // {verb}
+    pub store_all_which: String,    // {verb}, {choices}
+    pub nothing_to_store: String,   // {verb}, {prep}, {container}
+    pub container_unusable: String, // {container}
+    pub cooldown: String,
+    pub unknown_command: String,
+    pub goto_unknown: String,
+    pub goto_here: String,
+    pub goto_no_route: String,
+    pub moves_stopped: String, // {taken}, {total}
+    pub verbose_mode: String,
+    pub brief_mode: String,
+    pub superbrief_mode: String,
+    pub turns: String,           // {turns}
+    pub turns_of_limit: String,  // {turns}, {limit}
+    pub score: String,           // {score}, {max}
+    pub score_rank: String,      // {score}, {max}, {rank}
+    pub score_up: String,        // {points}
+    pub health: String,          // {hp}, {max}
+    pub wear: String,            // {item}
+    pub take_off: String,        // {item}
+    pub already_wearing: String, // {item}
+    pub wearing: String,         // {items}
+    pub out_of_time: String,
+    pub checkpoint_saved: String,
+    pub no_checkpoint: String,
+    pub checkpoint_restored: String,
+    pub revert_hint: String,
+    pub game_over: String,
+    pub game_over_revert: String,
+    pub goodbye: String,
 }
 
+impl Default for Messages {
+    fn default() -> Self {
+        Messages {
+            take: "You take the {item}.".to_string(),
+            take_from: "You take the {item} from the {container}.".to_string(),
+            take_from_npc: "You take the {item} from {npc}.".to_string(),
+            already_have: "You already have the {item}.".to_string(),
+            cant_take: "You can't take the {item}.".to_string(),
+            drop: "You drop the {item}.".to_string(),
+            not_here: "You don't see that here.".to_string(),
+            inventory_empty: "You are carrying nothing.".to_string(),
+            inventory_header: "You are carrying:".to_string(),
+            go: "You go {direction}.".to_string(),
+            cant_go: "You can't go {direction} from here.".to_string(),
+            time_passes: "Time passes.".to_string(),
+            take_what: "Take what?".to_string(),
+            drop_what: "Drop what?".to_string(),
+            examine_what: "Examine what?".to_string(),
+            give: "You give the {item} to {npc}.".to_string(),
+            cant_give: "You can't give away the {item}.".to_string(),
+            npc_refuses: "{npc} doesn't want the {item}.".to_string(),
+            npc_lacks: "{npc} doesn't have that.".to_string(),
+            cant_drop: "You'd better hold onto that.".to_string(),
+            not_carrying: "You aren't carrying that.".to_string(),
+            no_container: "You don't see any container like that here.".to_string(),
+            not_container: "That isn't a container.".to_string(),
+            no_npc: "You don't see anyone like that here.".to_string(),
+            npc_gone: "They're not here anymore.".to_string(),
+            nothing_to_take: "There is nothing here you can take.".to_string(),
+            nothing_to_drop: "You aren't carrying anything you can drop.".to_string(),
+            nothing_to_give: "You aren't carrying anything you can give.".to_string(),
+            nothing_to_examine: "There is nothing here to examine.".to_string(),
+            nothing_special: "You see nothing special about the {item}.".to_string(),
+            npc_nothing_special: "You see nothing special about {npc}.".to_string(),
+            npc_nothing_to_say: "{npc} has nothing to say.".to_string(),
+            npc_nothing_new: "{npc} has nothing new to say.".to_string(),
+            no_answer: "No one answers.".to_string(),
+            cant_do_that: "You can't do that.".to_string(),
+            be_more_specific: "Be more specific.".to_string(),
+            go_where: "Go where?".to_string(),
+            talk_to_whom: "Talk to whom?".to_string(),
+            give_what: "Give what?".to_string(),
+            give_to_whom: "Give it to whom?".to_string(),
+            give_what_to_whom: "Give what to whom?".to_string(),
+            give_unclear: "I don't understand who you want to give that to.".to_string(),
+            take_from_where: "Take it from where?".to_string(),
+            take_from_whom: "Take it from whom?".to_string(),
+            take_what_from_whom: "Take what from whom?".to_string(),
+            take_all_from_where: "Take all from where?".to_string(),
+            take_from_unclear: "I don't understand what you want to take from where.".to_string(),
+            not_in_container: "You don't see anything like that in the {container}.".to_string(),
+            nothing_inside: "There is nothing in the {container} you can take.".to_string(),
+            store_what: "What do you want to {verb}?".to_string(),
+            not_carrying_any: "You aren't carrying anything like that.".to_string(),
+            cant_store: "You can't {verb} the {item}.".to_string(),
+            store_where: "Where do you want to {verb} the {item}?".to_string(),
+            store_where_which: "Where do you want to {verb} the {item}: {choices}?".to_string(),
+            store_in_itself: "You can't {verb} the {item} inside itself.".to_string(),
+            container_full: "The {container} is full.".to_string(),
+            store: "You {verb} the {item} {prep} the {container}.".to_string(),
+            store_all_where: "Where do you want to {verb} everything?".to_string(),
+            store_all_which: "Where do you want to {verb} everything: {choices}?".to_string(),
+            nothing_to_store: "You aren't carrying anything to {verb} {prep} the {container}."
+                .to_string(),
+            container_unusable: "The {container} won't take anything right now.".to_string(),
+            cooldown: "You'll have to wait a while before doing that again.".to_string(),
+            unknown_command: "I don't understand that command.".to_string(),
+            goto_unknown: "You don't know of any such place.".to_string(),
+            goto_here: "You're already there.".to_string(),
+            goto_no_route: "You can't find a way there.".to_string(),
+            moves_stopped: "You stop after {taken} of {total} moves.".to_string(),
+            verbose_mode: "Verbose mode: rooms are always described in full.".to_string(),
+            brief_mode: "Brief mode: rooms are described in full only on your first visit."
+                .to_string(),
+            superbrief_mode:
+                "Superbrief mode: only room names are shown. Use \"look\" for details.".to_string(),
+            turns: "Turns: {turns}.".to_string(),
+            turns_of_limit: "Turns: {turns} of {limit}.".to_string(),
+            score: "Your score is {score} of a possible {max}.".to_string(),
+            score_rank:
+                "Your score is {score} of a possible {max}, earning you the rank of {rank}."
+                    .to_string(),
+            score_up: "[Your score has gone up by {points}.]".to_string(),
+            health: "Health: {hp}/{max}.".to_string(),
+            wear: "You put on the {item}.".to_string(),
+            take_off: "You take off the {item}.".to_string(),
+            already_wearing: "You're already wearing the {item}.".to_string(),
+            wearing: "You are wearing: {items}.".to_string(),
+            out_of_time: "You have run out of time.".to_string(),
+            checkpoint_saved: "Checkpoint saved.".to_string(),
+            no_checkpoint: "You haven't reached a checkpoint yet.".to_string(),
+            checkpoint_restored: "You return to your last checkpoint.".to_string(),
+            revert_hint: "Type \"revert\" to go back to your last checkpoint.".to_string(),
+            game_over: "The game is over.".to_string(),
+            game_over_revert:
+                "The game is over. Type \"revert\" to go back to your last checkpoint.".to_string(),
+            goodbye: "Goodbye.".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct Room {
     pub id: String,
     pub name: String,
@@ -25,35 +277,100 @@ pub struct Room {
     pub exits: Vec<Exit>,
     pub actions: Vec<Action>,
     pub state_descs: Vec<StateDesc>,
+    pub name_states: Vec<StateDesc>, // last matching `text` replaces the room name
+    pub liquid_source: Option<String>, // liquid available anywhere in the room (lake, river)
+    pub on_drop: Vec<DropTrigger>,   // reactions to items dropped here
+    pub unknown_command_text: Option<String>, // overrides the world's for input nothing handles
 }
 
+#[derive(Debug, PartialEq)]
+pub struct DropTrigger {
+    pub item: String, // item id that triggers it
+    pub response: String,
+    pub effects: Vec<String>,
+    pub one_shot: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TakeTrigger {
+    pub item: String, // item id that triggers it
+    pub conditions: Vec<Condition>,
+    pub response: String,
+    pub effects: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
 pub struct StateDesc {
-    pub conditions: Vec<String>,
+    pub conditions: Vec<Condition>,
     pub text: String,
 }
 
+#[derive(Debug, PartialEq)]
 pub struct Exit {
     pub direction: String,
     pub target: String,
     pub verbs: Vec<String>,
-    pub conditions: Vec<String>,
+    pub conditions: Vec<Condition>,
+    pub travel_text: Option<String>, // replaces "You go <direction>."
+    pub arrival_effects: Vec<String>, // flags applied after moving through
+    pub description: Option<String>, // shown for "examine <direction>"
+    pub secret: bool,                // unlisted until found or used
+    pub verb_conditions: Vec<VerbCondition>, // verbs that need flags of their own
+    pub turn_cost: Option<u64>,      // turns a crossing takes (default 1)
+}
+
+/// One of an exit's verbs that only works while its conditions hold.
+#[derive(Debug, PartialEq)]
+pub struct VerbCondition {
+    pub verb: String,
+    pub conditions: Vec<Condition>,
 }
 
+#[derive(Debug, PartialEq)]
 pub struct Action {
-    #[allow(dead_code)]
     pub id: String,
     pub verbs: Vec<String>,
     pub nouns: Vec<String>,
     pub response: String,
     pub effects: Vec<String>,
-    pub conditions: Vec<String>,
+    pub conditions: Vec<Condition>,
     pub scope_requirements: Vec<String>,
     pub requires_inventory: Vec<String>,
+    pub requires_reachable: Vec<String>, // carried or lying in the room
+    pub instrument: Option<String>,      // item used: "<verb> <target> with <instrument>"
+    pub conditional_effects: Vec<ConditionalEffect>, // extra effects that depend on state
+    pub transforms: Vec<ItemTransform>,  // items replaced when the action fires
+    pub spawns: Vec<ItemSpawn>,          // items placed when the action fires
+    pub remove_items: Vec<String>,       // items taken out of play when the action fires
+    pub toggle_flag: Option<String>,     // flipped each time the action fires
+    pub response_off: Option<String>,    // response when the toggle flag ends up unset
     pub missing_inventory_text: Option<String>,
     pub missing_scope_text: Option<String>,
+    pub blocked_text: Option<String>, // replaces "You can't do that right now."
+    pub keep_items: bool, // NPC actions: required items go to the NPC instead of leaving play
+    pub cooldown_turns: Option<u64>, // turns before it can fire again
+    pub cooldown_text: Option<String>, // shown when tried again too soon
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ConditionalEffect {
+    pub conditions: Vec<Condition>, // tested before the action changes anything
+    pub effects: Vec<String>,
 }
 
-#[derive(Clone)]
+#[derive(Debug, PartialEq)]
+pub struct ItemTransform {
+    pub from: String, // item that disappears
+    pub into: String, // item that takes its place
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ItemSpawn {
+    pub item: String,
+    pub location: ItemLocation, // moved here if already in play
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ItemLocation {
     Room(String),
     Inventory,
@@ -61,79 +378,157 @@ pub enum ItemLocation {
     Npc(String),  // held by an NPC
 }
 
+#[derive(Debug, PartialEq)]
 pub enum ItemKind {
     Simple,
-    Container(ContainerProps),
+    Container(Box<ContainerProps>),
     // Weapon(WeaponProps),
     // Armor(ArmorProps),
     // Consumable(ConsumableProps),
 }
 
+#[derive(Debug, PartialEq)]
 pub struct Item {
     pub id: String,
     pub name: String,
     pub aliases: Vec<String>,
     pub room_text: String,
+    pub room_state_texts: Vec<StateDesc>, // first match replaces room_text
     pub inventory_text: String,
     pub examine_text: String,
-    pub conditions: Vec<String>,
+    pub conditions: Vec<Condition>,
     pub portable: bool,
+    pub droppable: bool, // false: once carried, "drop" refuses to let it go
+    pub examine_requires_inventory: bool, // examined closely (and looked in) only while carried
+    pub inscription: Option<String>, // engraved or printed words, shown by "read"
     pub kind: ItemKind,
-    pub start_location: ItemLocation,
+    pub start_location: Option<ItemLocation>, // None = not placed until something spawns it
+    pub decays_after: Option<u64>,            // turns before the item decays
+    pub decays_into: Option<String>,          // item id it becomes (removed if None)
+    pub decay_text: Option<String>,           // message when it decays near the player
+    pub wearable: bool,                       // can be worn ("wear", "take off")
+    pub holds_liquid: bool,                   // can be filled/emptied/drunk from
+    pub start_liquid: Option<String>,         // liquid it holds at the start
+    pub liquid_source: Option<String>,        // liquid it provides for filling (fountain)
 }
 
+#[derive(Debug, PartialEq)]
 pub struct ContainerProps {
-    pub capacity: Option<usize>,       // number of items that can fit
-    pub conditions: Vec<String>,       // flags required to interact
-    pub complete_when: Vec<String>,    // item IDs
-    pub complete_flag: Option<String>, // flag to set
-    pub closed_text: String,           // message when conditions not met
-    pub complete_text: Option<String>, // message when completion triggers
+    pub capacity: Option<usize>,          // number of items that can fit
+    pub conditions: Vec<Condition>,       // flags required to interact
+    pub complete_when: Vec<String>,       // item IDs
+    pub complete_flag: Option<String>,    // flag to set
+    pub closed_text: String,              // message when conditions not met
+    pub open_text: Option<String>,        // said before the contents when it is open
+    pub use_conditions: Vec<Condition>,   // flags required to store items (e.g. powered)
+    pub use_blocked_text: Option<String>, // message when use_conditions not met
+    pub complete_text: Option<String>,    // message when completion triggers
     pub verbs: Vec<String>,
     pub prep: String,
 }
 
+#[derive(Debug, PartialEq)]
 pub struct GlobalCondition {
     pub id: String,
-    pub conditions: Vec<String>, // flag conditions like everywhere else
+    pub conditions: Vec<Condition>, // flag conditions like everywhere else
     pub allowed_rooms: Vec<String>, // optional whitelist of room IDs
     pub disallowed_rooms: Vec<String>, // optional blacklist of room IDs
-    pub response: String,        // text printed when it fires
-    pub effects: Vec<String>,    // flags to add/remove
-    pub one_shot: bool,          // if true, only fires once ever
+    pub response: String,           // text printed when it fires
+    pub effects: Vec<String>,       // flags to add/remove
+    pub one_shot: bool,             // if true, only fires once ever
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Ending {
+    pub id: String,
+    pub conditions: Vec<Condition>, // flag conditions that end the game
+    pub text: String,               // text printed when the game ends
+    pub is_victory: bool,           // victory vs defeat
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ScoreEvent {
+    pub id: String,
+    pub conditions: Vec<Condition>, // points are awarded the first time these hold
+    pub points: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Rank {
+    pub min_score: u64, // lowest score that earns the title
+    pub title: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct EasterVerb {
+    pub verbs: Vec<String>,
+    pub conditions: Vec<Condition>,
+    pub response: String,
 }
 
+#[derive(Debug, PartialEq)]
 pub struct Npc {
     pub id: String,
     pub name: String,
     pub aliases: Vec<String>,
     pub start_room: String,
     pub room_text: String,
+    pub idle_texts: Vec<StateDesc>, // first match is added after room_text
     pub examine_text: String,
-    pub conditions: Vec<String>,
-    pub actions: Vec<Action>,          // reuse existing Action struct
-    pub roam: Option<NpcRoam>,         // optional roaming behavior
-    pub block_movement: bool,          // if true, can block movement while present/visible
-    pub block_conditions: Vec<String>, // additional conditions for blocking
-    pub block_text: Option<String>,    // custom message when blocking movement
+    pub conditions: Vec<Condition>,
+    pub actions: Vec<Action>,             // reuse existing Action struct
+    pub roam: Option<NpcRoam>,            // optional roaming behavior
+    pub carries_on_roam: Vec<String>,     // items it picks up from the room it leaves
+    pub block_movement: bool,             // if true, can block movement while present/visible
+    pub block_conditions: Vec<Condition>, // additional conditions for blocking
+    pub block_text: Option<String>,       // custom message when blocking movement
     pub block_exits: Vec<String>, // optional list of exit directions/verbs to block (empty = all)
+    pub block_priority: i32,      // higher blocks first when several NPCs could
     pub foe: bool,                // if true, may attack when blocking
     pub attack_chance_percent: u8, // 0..=100 chance when blocking
     pub attack_text: Option<String>, // message when attack triggers
     pub attack_effects: Vec<String>, // effects applied on attack
     pub dialogue: Vec<NpcDialogue>, // optional dialogue entries
+    pub disposition: Option<String>, // starting disposition ("friendly", "hostile", ...)
+    pub wants: Vec<NpcWant>,      // items the NPC accepts; others are refused
+    pub on_player_enter: Vec<NpcTrigger>, // reactions to the player walking in
+    pub responds_to_call: bool,   // answers "call"/"shout" from an adjacent room
+    pub call_text: Option<String>, // its answer (default depends on comes_when_called)
+    pub comes_when_called: bool,  // walks into the player's room when it answers
+}
+
+/// Fires once per game when the player walks into the NPC's room while its
+/// conditions hold.
+#[derive(Debug, PartialEq)]
+pub struct NpcTrigger {
+    pub id: String,
+    pub conditions: Vec<Condition>,
+    pub response: String,
+    pub effects: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NpcWant {
+    pub item: String, // item id the NPC accepts
+    pub conditions: Vec<Condition>,
+    pub response: String,
+    pub effects: Vec<String>,
+    pub consumed: bool, // removed from the game instead of held by the NPC
 }
 
+#[derive(Debug, PartialEq)]
 pub struct NpcRoam {
     pub enabled: bool,
     pub allowed_rooms: Vec<String>,
     pub chance_percent: u8, // 0..=100
 }
 
+#[derive(Debug, PartialEq)]
 pub struct NpcDialogue {
     pub id: String,
-    pub conditions: Vec<String>,
+    pub conditions: Vec<Condition>,
     pub response: String,
     pub effects: Vec<String>,
     pub one_shot: bool,
+    pub sets_disposition: Option<String>, // new disposition for the NPC after this line
 }
@@ -1,5 +1,8 @@
 use std::collections::HashSet;
 
+use super::condition::{
+    Condition, parse_liquid_condition, parse_location_condition, parse_turn_condition,
+};
 use super::model::{Action, ItemKind, ItemLocation, World};
 
 #[derive(Debug, Clone)]
@@ -40,47 +43,157 @@ pub fn validate_world(world: &World) -> Vec<ValidationError> {
                     room_id, exit.direction, exit.target
                 )));
             }
+            for vc in &exit.verb_conditions {
+                if !exit
+                    .verbs
+                    .iter()
+                    .any(|v| v.trim().eq_ignore_ascii_case(vc.verb.trim()))
+                {
+                    errors.push(ValidationError::new(format!(
+                        "room '{}' exit '{}' verb_condition names '{}', which is not one of its verbs",
+                        room_id, exit.direction, vc.verb
+                    )));
+                }
+                if vc.conditions.is_empty() {
+                    errors.push(ValidationError::new(format!(
+                        "room '{}' exit '{}' verb_condition '{}' has no conditions",
+                        room_id, exit.direction, vc.verb
+                    )));
+                }
+            }
+            if exit.turn_cost == Some(0) {
+                errors.push(ValidationError::new(format!(
+                    "room '{}' exit '{}' turn_cost must be greater than zero",
+                    room_id, exit.direction
+                )));
+            }
+            for eff in &exit.arrival_effects {
+                let name = eff.strip_prefix('!').unwrap_or(eff);
+                if name.trim().is_empty() || name.contains(char::is_whitespace) {
+                    errors.push(ValidationError::new(format!(
+                        "room '{}' exit '{}' has an invalid arrival effect '{}'",
+                        room_id, exit.direction, eff
+                    )));
+                }
+            }
+        }
+    }
+
+    // Conditional room names must have a name and conditions
+    for (room_id, room) in &world.rooms {
+        for ns in &room.name_states {
+            if ns.text.trim().is_empty() {
+                errors.push(ValidationError::new(format!(
+                    "room '{}' has a name_state with an empty text",
+                    room_id
+                )));
+            }
+            if ns.conditions.is_empty() {
+                errors.push(ValidationError::new(format!(
+                    "room '{}' has a name_state without conditions (use name instead)",
+                    room_id
+                )));
+            }
+        }
+    }
+
+    for (room_id, room) in &world.rooms {
+        if let Some(liquid) = &room.liquid_source
+            && !is_single_word(liquid)
+        {
+            errors.push(ValidationError::new(format!(
+                "room '{}' has an invalid liquid_source '{}' (use a single word)",
+                room_id, liquid
+            )));
         }
     }
 
     // Index helpers
     let all_items: HashSet<String> = world.items.keys().cloned().collect();
     let all_rooms: HashSet<String> = world.rooms.keys().cloned().collect();
+    let all_npcs: HashSet<String> = world.npcs.keys().cloned().collect();
 
     // Validate item start locations and container completeness
     for item in world.items.values() {
-        match &item.start_location {
-            ItemLocation::Room(r) => {
-                if !world.rooms.contains_key(r) {
-                    errors.push(ValidationError::new(format!(
-                        "item '{}' start_location room '{}' not found",
-                        item.id, r
-                    )));
+        if let Some(start_location) = &item.start_location {
+            match start_location {
+                ItemLocation::Room(r) => {
+                    if !world.rooms.contains_key(r) {
+                        errors.push(ValidationError::new(format!(
+                            "item '{}' start_location room '{}' not found",
+                            item.id, r
+                        )));
+                    }
                 }
-            }
-            ItemLocation::Item(parent) => {
-                if parent == &item.id {
-                    errors.push(ValidationError::new(format!(
-                        "item '{}' cannot start inside itself",
-                        item.id
-                    )));
+                ItemLocation::Item(parent) => {
+                    if parent == &item.id {
+                        errors.push(ValidationError::new(format!(
+                            "item '{}' cannot start inside itself",
+                            item.id
+                        )));
+                    }
+                    if !world.items.contains_key(parent) {
+                        errors.push(ValidationError::new(format!(
+                            "item '{}' start_location item '{}' not found",
+                            item.id, parent
+                        )));
+                    }
                 }
-                if !world.items.contains_key(parent) {
-                    errors.push(ValidationError::new(format!(
-                        "item '{}' start_location item '{}' not found",
-                        item.id, parent
-                    )));
+                ItemLocation::Npc(npc_id) => {
+                    if !world.npcs.contains_key(npc_id) {
+                        errors.push(ValidationError::new(format!(
+                            "item '{}' start_location npc '{}' not found",
+                            item.id, npc_id
+                        )));
+                    }
                 }
+                ItemLocation::Inventory => {}
             }
-            ItemLocation::Npc(npc_id) => {
-                if !world.npcs.contains_key(npc_id) {
-                    errors.push(ValidationError::new(format!(
-                        "item '{}' start_location npc '{}' not found",
-                        item.id, npc_id
-                    )));
-                }
+        }
+
+        if item.decays_after == Some(0) {
+            errors.push(ValidationError::new(format!(
+                "item '{}' decays_after must be greater than zero",
+                item.id
+            )));
+        }
+
+        if let Some(into) = &item.decays_into {
+            if into == &item.id {
+                errors.push(ValidationError::new(format!(
+                    "item '{}' cannot decay into itself",
+                    item.id
+                )));
+            } else if !world.items.contains_key(into) {
+                errors.push(ValidationError::new(format!(
+                    "item '{}' decays_into references missing item '{}'",
+                    item.id, into
+                )));
             }
-            ItemLocation::Inventory => {}
+        }
+
+        // Liquids are named in conditions as "liquid:<item>:<liquid>"
+        for liquid in item.start_liquid.iter().chain(item.liquid_source.iter()) {
+            if !is_single_word(liquid) {
+                errors.push(ValidationError::new(format!(
+                    "item '{}' has an invalid liquid '{}' (use a single word)",
+                    item.id, liquid
+                )));
+            }
+        }
+
+        if item.start_liquid.is_some() && !item.holds_liquid {
+            errors.push(ValidationError::new(format!(
+                "item '{}' has start_liquid but holds_liquid is false",
+                item.id
+            )));
+        }
+
+        if item.wearable && !item.portable {
+            errors.push(ValidationError::new(format!(
+                "item '{}' is wearable but not portable",
+                item.id
+            )));
         }
 
         if let ItemKind::Container(props) = &item.kind {
@@ -95,6 +208,43 @@ pub fn validate_world(world: &World) -> Vec<ValidationError> {
         }
     }
 
+    // Starting inventory must name real items
+    for id in &world.start_inventory {
+        if !world.items.contains_key(id) {
+            errors.push(ValidationError::new(format!(
+                "start_inventory references missing item '{}'",
+                id
+            )));
+        }
+    }
+
+    for room_id in &world.autosave_rooms {
+        if !world.rooms.contains_key(room_id) {
+            errors.push(ValidationError::new(format!(
+                "autosave_rooms references missing room '{}'",
+                room_id
+            )));
+        }
+    }
+
+    for flag in &world.start_flags {
+        let name = flag.strip_prefix('!').unwrap_or(flag);
+        if name.trim().is_empty() || name.contains(char::is_whitespace) {
+            errors.push(ValidationError::new(format!(
+                "start_flags has an invalid entry '{}'",
+                flag
+            )));
+        }
+    }
+
+    validate_command_aliases(world, &mut errors);
+
+    if world.turn_limit_text.is_some() && world.turn_limit.is_none() {
+        errors.push(ValidationError::new(
+            "turn_limit_text is set but turn_limit is not".to_string(),
+        ));
+    }
+
     // Validate NPCs
     for (npc_id, npc) in &world.npcs {
         if !world.rooms.contains_key(&npc.start_room) {
@@ -114,21 +264,82 @@ pub fn validate_world(world: &World) -> Vec<ValidationError> {
             }
         }
 
+        // Dispositions live in flags as "disp:<npc>:<value>"
+        let dispositions = npc.disposition.iter().chain(
+            npc.dialogue
+                .iter()
+                .filter_map(|d| d.sets_disposition.as_ref()),
+        );
+        for disp in dispositions {
+            if !is_single_word(disp) {
+                errors.push(ValidationError::new(format!(
+                    "npc '{}' has an invalid disposition '{}' (use a single word)",
+                    npc_id, disp
+                )));
+            }
+        }
+
+        let mut trigger_ids: HashSet<&str> = HashSet::new();
+        for t in &npc.on_player_enter {
+            if !trigger_ids.insert(t.id.as_str()) {
+                errors.push(ValidationError::new(format!(
+                    "npc '{}' has duplicate on_player_enter id '{}'",
+                    npc_id, t.id
+                )));
+            }
+        }
+
+        if !npc.responds_to_call && (npc.call_text.is_some() || npc.comes_when_called) {
+            errors.push(ValidationError::new(format!(
+                "npc '{}' has call_text or comes_when_called but not responds_to_call",
+                npc_id
+            )));
+        }
+
+        for item_id in &npc.carries_on_roam {
+            if !all_items.contains(item_id) {
+                errors.push(ValidationError::new(format!(
+                    "npc '{}' carries_on_roam missing item '{}'",
+                    npc_id, item_id
+                )));
+            }
+        }
+
+        for want in &npc.wants {
+            if !all_items.contains(&want.item) {
+                errors.push(ValidationError::new(format!(
+                    "npc '{}' wants missing item '{}'",
+                    npc_id, want.item
+                )));
+            }
+        }
+
         validate_actions(
             &npc.actions,
             &all_items,
             &all_rooms,
+            &all_npcs,
             &mut errors,
             Some(format!("npc '{}'", npc_id)),
         );
     }
 
-    // Validate room actions
+    // Validate room actions and drop triggers
     for (room_id, room) in &world.rooms {
+        for trigger in &room.on_drop {
+            if !all_items.contains(&trigger.item) {
+                errors.push(ValidationError::new(format!(
+                    "room '{}' on_drop references missing item '{}'",
+                    room_id, trigger.item
+                )));
+            }
+        }
+
         validate_actions(
             &room.actions,
             &all_items,
             &all_rooms,
+            &all_npcs,
             &mut errors,
             Some(format!("room '{}'", room_id)),
         );
@@ -139,6 +350,7 @@ pub fn validate_world(world: &World) -> Vec<ValidationError> {
         &world.global_actions,
         &all_items,
         &all_rooms,
+        &all_npcs,
         &mut errors,
         Some("global actions".to_string()),
     );
@@ -163,19 +375,287 @@ pub fn validate_world(world: &World) -> Vec<ValidationError> {
         }
     }
 
+    // Validate endings
+    let mut ending_ids: HashSet<&str> = HashSet::new();
+    for ending in &world.endings {
+        if !ending_ids.insert(ending.id.as_str()) {
+            errors.push(ValidationError::new(format!(
+                "duplicate ending id '{}'",
+                ending.id
+            )));
+        }
+        if ending.conditions.is_empty() {
+            errors.push(ValidationError::new(format!(
+                "ending '{}' has no conditions and would end the game immediately",
+                ending.id
+            )));
+        }
+    }
+
+    // Validate score events
+    let mut score_ids: HashSet<&str> = HashSet::new();
+    for sc in &world.score_events {
+        if !score_ids.insert(sc.id.as_str()) {
+            errors.push(ValidationError::new(format!(
+                "duplicate score id '{}'",
+                sc.id
+            )));
+        }
+        if sc.conditions.is_empty() {
+            errors.push(ValidationError::new(format!(
+                "score '{}' has no conditions and would be awarded immediately",
+                sc.id
+            )));
+        }
+    }
+
+    // Ranks must climb: each threshold above the one before, so every score has
+    // exactly one title
+    for pair in world.ranks.windows(2) {
+        if pair[1].min_score <= pair[0].min_score {
+            errors.push(ValidationError::new(format!(
+                "rank '{}' (min_score {}) must have a higher min_score than rank '{}' (min_score {})",
+                pair[1].title, pair[1].min_score, pair[0].title, pair[0].min_score
+            )));
+        }
+    }
+    for rank in &world.ranks {
+        if rank.title.trim().is_empty() {
+            errors.push(ValidationError::new(format!(
+                "rank with min_score {} has an empty title",
+                rank.min_score
+            )));
+        }
+    }
+
+    // Validate easter verbs
+    for (idx, ev) in world.easter_verbs.iter().enumerate() {
+        if ev.verbs.is_empty() || ev.verbs.iter().any(|v| v.trim().is_empty()) {
+            errors.push(ValidationError::new(format!(
+                "easter_verb #{} must list at least one non-empty verb",
+                idx + 1
+            )));
+        }
+    }
+
+    // Validate take triggers
+    for trigger in &world.on_take {
+        if !all_items.contains(&trigger.item) {
+            errors.push(ValidationError::new(format!(
+                "on_take references missing item '{}'",
+                trigger.item
+            )));
+        }
+    }
+
+    // Item-location and liquid conditions must name real items and places; turn
+    // comparisons must parse
+    for (owner, conds) in all_conditions(world) {
+        for cond in conds {
+            let problem = match cond {
+                Condition::At(item, loc) | Condition::NotAt(item, loc) => {
+                    location_condition_problem(item, loc, world)
+                }
+                Condition::Liquid(item, liquid) | Condition::NotLiquid(item, liquid) => {
+                    liquid_condition_problem(item, liquid, world)
+                }
+                Condition::Flag(name) | Condition::NotFlag(name) => {
+                    match (
+                        parse_turn_condition(name),
+                        parse_location_condition(name),
+                        parse_liquid_condition(name),
+                    ) {
+                        (Some(Err(problem)), _, _)
+                        | (_, Some(Err(problem)), _)
+                        | (_, _, Some(Err(problem))) => Some(problem),
+                        _ => None,
+                    }
+                }
+                Condition::Turn(..) => None,
+            };
+            if let Some(problem) = problem {
+                errors.push(ValidationError::new(format!(
+                    "{} condition '{}' {}",
+                    owner, cond, problem
+                )));
+            }
+        }
+    }
+
     errors
 }
 
+/// Every condition list in the world, labelled with its owner.
+fn all_conditions(world: &World) -> Vec<(String, &[Condition])> {
+    fn push_actions<'w>(
+        lists: &mut Vec<(String, &'w [Condition])>,
+        owner: &str,
+        actions: &'w [Action],
+    ) {
+        for a in actions {
+            lists.push((format!("{} action '{}'", owner, a.id), &a.conditions));
+            for ce in &a.conditional_effects {
+                lists.push((
+                    format!("{} action '{}' conditional effect", owner, a.id),
+                    &ce.conditions,
+                ));
+            }
+        }
+    }
+
+    let mut lists: Vec<(String, &[Condition])> = Vec::new();
+
+    for (room_id, room) in &world.rooms {
+        let owner = format!("room '{}'", room_id);
+        for sd in room.state_descs.iter().chain(&room.name_states) {
+            lists.push((owner.clone(), &sd.conditions));
+        }
+        for exit in &room.exits {
+            lists.push((
+                format!("{} exit '{}'", owner, exit.direction),
+                &exit.conditions,
+            ));
+            for vc in &exit.verb_conditions {
+                lists.push((
+                    format!("{} exit '{}' verb '{}'", owner, exit.direction, vc.verb),
+                    &vc.conditions,
+                ));
+            }
+        }
+        push_actions(&mut lists, &owner, &room.actions);
+    }
+
+    for (item_id, item) in &world.items {
+        let owner = format!("item '{}'", item_id);
+        lists.push((owner.clone(), &item.conditions));
+        for rs in &item.room_state_texts {
+            lists.push((owner.clone(), &rs.conditions));
+        }
+        if let ItemKind::Container(props) = &item.kind {
+            lists.push((owner.clone(), &props.conditions));
+            lists.push((owner, &props.use_conditions));
+        }
+    }
+
+    for (npc_id, npc) in &world.npcs {
+        let owner = format!("npc '{}'", npc_id);
+        lists.push((owner.clone(), &npc.conditions));
+        lists.push((owner.clone(), &npc.block_conditions));
+        for d in &npc.dialogue {
+            lists.push((format!("{} dialogue '{}'", owner, d.id), &d.conditions));
+        }
+        for w in &npc.wants {
+            lists.push((format!("{} want '{}'", owner, w.item), &w.conditions));
+        }
+        for idle in &npc.idle_texts {
+            lists.push((owner.clone(), &idle.conditions));
+        }
+        for t in &npc.on_player_enter {
+            lists.push((
+                format!("{} on_player_enter '{}'", owner, t.id),
+                &t.conditions,
+            ));
+        }
+        push_actions(&mut lists, &owner, &npc.actions);
+    }
+
+    push_actions(&mut lists, "global", &world.global_actions);
+
+    for gc in &world.global_conditions {
+        lists.push((format!("global_condition '{}'", gc.id), &gc.conditions));
+    }
+    for ending in &world.endings {
+        lists.push((format!("ending '{}'", ending.id), &ending.conditions));
+    }
+    for sc in &world.score_events {
+        lists.push((format!("score '{}'", sc.id), &sc.conditions));
+    }
+    for ev in &world.easter_verbs {
+        lists.push(("easter_verb".to_string(), &ev.conditions));
+    }
+    for t in &world.on_take {
+        lists.push((format!("on_take '{}'", t.item), &t.conditions));
+    }
+
+    lists
+}
+
+/// Checks that a location condition names a real item and a real place to find it.
+/// Returns what's wrong, or None if it's fine.
+fn location_condition_problem(item: &str, loc: &ItemLocation, world: &World) -> Option<String> {
+    if !world.items.contains_key(item) {
+        return Some(format!("references missing item '{}'", item));
+    }
+
+    match loc {
+        ItemLocation::Inventory => None,
+        ItemLocation::Room(room) if world.rooms.contains_key(room) => None,
+        ItemLocation::Room(room) => Some(format!("references missing room '{}'", room)),
+        ItemLocation::Npc(npc) if world.npcs.contains_key(npc) => None,
+        ItemLocation::Npc(npc) => Some(format!("references missing npc '{}'", npc)),
+        ItemLocation::Item(container) => match world.items.get(container).map(|c| &c.kind) {
+            Some(ItemKind::Container(_)) => None,
+            Some(_) => Some(format!(
+                "references '{}', which is not a container",
+                container
+            )),
+            None => Some(format!("references missing container '{}'", container)),
+        },
+    }
+}
+
+fn liquid_condition_problem(item: &str, liquid: &str, world: &World) -> Option<String> {
+    match world.items.get(item) {
+        None => Some(format!("references missing item '{}'", item)),
+        Some(it) if !it.holds_liquid => {
+            Some(format!("references '{}', which can't hold a liquid", item))
+        }
+        Some(_) if !is_single_word(liquid) => Some(format!("names an invalid liquid '{}'", liquid)),
+        Some(_) => None,
+    }
+}
+
+/// Values written inside "prefix:<id>:<value>" flags and conditions must be one
+/// plain word.
+fn is_single_word(value: &str) -> bool {
+    !value.trim().is_empty() && !value.contains(':') && !value.contains(char::is_whitespace)
+}
+
 fn validate_actions(
     actions: &[Action],
     all_items: &HashSet<String>,
-    _all_rooms: &HashSet<String>,
+    all_rooms: &HashSet<String>,
+    all_npcs: &HashSet<String>,
     errors: &mut Vec<ValidationError>,
     scope_label: Option<String>,
 ) {
     let label = scope_label.unwrap_or_else(|| "actions".to_string());
 
     for action in actions {
+        for (idx, ce) in action.conditional_effects.iter().enumerate() {
+            if ce.conditions.is_empty() || ce.effects.is_empty() {
+                errors.push(ValidationError::new(format!(
+                    "{} action '{}' conditional effect #{} needs both conditions and effects",
+                    label,
+                    action.id,
+                    idx + 1
+                )));
+            }
+            for cond in &ce.conditions {
+                if let Some(name) = cond.flag()
+                    && (name.is_empty() || name.contains(char::is_whitespace))
+                {
+                    errors.push(ValidationError::new(format!(
+                        "{} action '{}' conditional effect #{} has an invalid condition '{}'",
+                        label,
+                        action.id,
+                        idx + 1,
+                        cond
+                    )));
+                }
+            }
+        }
+
         for req in &action.requires_inventory {
             if !all_items.contains(req) {
                 errors.push(ValidationError::new(format!(
@@ -185,6 +665,109 @@ fn validate_actions(
             }
         }
 
+        for req in &action.requires_reachable {
+            if !all_items.contains(req) {
+                errors.push(ValidationError::new(format!(
+                    "{} action '{}' requires_reachable missing item '{}'",
+                    label, action.id, req
+                )));
+            }
+        }
+
+        if let Some(inst) = &action.instrument
+            && !all_items.contains(inst)
+        {
+            errors.push(ValidationError::new(format!(
+                "{} action '{}' instrument references missing item '{}'",
+                label, action.id, inst
+            )));
+        }
+
+        if let Some(flag) = &action.toggle_flag
+            && (flag.trim().is_empty()
+                || flag.contains(char::is_whitespace)
+                || flag.starts_with('!'))
+        {
+            errors.push(ValidationError::new(format!(
+                "{} action '{}' has an invalid toggle_flag '{}' (use a single flag name)",
+                label, action.id, flag
+            )));
+        }
+
+        if action.response_off.is_some() && action.toggle_flag.is_none() {
+            errors.push(ValidationError::new(format!(
+                "{} action '{}' has response_off but no toggle_flag",
+                label, action.id
+            )));
+        }
+
+        if action.cooldown_turns == Some(0) {
+            errors.push(ValidationError::new(format!(
+                "{} action '{}' cooldown_turns must be greater than zero",
+                label, action.id
+            )));
+        }
+
+        if action.cooldown_text.is_some() && action.cooldown_turns.is_none() {
+            errors.push(ValidationError::new(format!(
+                "{} action '{}' has cooldown_text but no cooldown_turns",
+                label, action.id
+            )));
+        }
+
+        for t in &action.transforms {
+            for id in [&t.from, &t.into] {
+                if !all_items.contains(id) {
+                    errors.push(ValidationError::new(format!(
+                        "{} action '{}' transform references missing item '{}'",
+                        label, action.id, id
+                    )));
+                }
+            }
+            if t.from == t.into {
+                errors.push(ValidationError::new(format!(
+                    "{} action '{}' transforms item '{}' into itself",
+                    label, action.id, t.from
+                )));
+            }
+        }
+
+        for id in &action.remove_items {
+            if !all_items.contains(id) {
+                errors.push(ValidationError::new(format!(
+                    "{} action '{}' remove_items references missing item '{}'",
+                    label, action.id, id
+                )));
+            }
+        }
+
+        for sp in &action.spawns {
+            if !all_items.contains(&sp.item) {
+                errors.push(ValidationError::new(format!(
+                    "{} action '{}' spawns missing item '{}'",
+                    label, action.id, sp.item
+                )));
+            }
+            let missing = match &sp.location {
+                ItemLocation::Room(r) if !all_rooms.contains(r) => Some(("room", r)),
+                ItemLocation::Item(i) if !all_items.contains(i) => Some(("item", i)),
+                ItemLocation::Npc(n) if !all_npcs.contains(n) => Some(("npc", n)),
+                _ => None,
+            };
+            if let Some((kind, id)) = missing {
+                errors.push(ValidationError::new(format!(
+                    "{} action '{}' spawns '{}' into missing {} '{}'",
+                    label, action.id, sp.item, kind, id
+                )));
+            }
+            if matches!(&sp.location, ItemLocation::Item(i) if i == &sp.item) {
+                errors.push(ValidationError::new(format!(
+                    "{} action '{}' spawns item '{}' inside itself",
+                    label, action.id, sp.item
+                )));
+            }
+        }
+
         for req in &action.scope_requirements {
             if !all_items.contains(req) {
                 errors.push(ValidationError::new(format!(
@@ -218,3 +801,82 @@ fn validate_actions(
         }
     }
 }
+
+/// Commands the engine understands on its own; an alias may only take one of these
+/// words over when the world sets aliases_override_builtins.
+const BUILTIN_COMMANDS: [&str; 27] = [
+    "quit",
+    "exit",
+    "inventory",
+    "i",
+    "status",
+    "health",
+    "score",
+    "about",
+    "credits",
+    "version",
+    "wait",
+    "z",
+    "verbose",
+    "brief",
+    "superbrief",
+    "objects",
+    "look",
+    "l",
+    "examine",
+    "x",
+    "talk",
+    "speak",
+    "give",
+    "take",
+    "get",
+    "drop",
+    "put",
+];
+
+fn validate_command_aliases(world: &World, errors: &mut Vec<ValidationError>) {
+    for (alias, command) in &world.command_aliases {
+        if alias.is_empty() || command.is_empty() {
+            errors.push(ValidationError::new(format!(
+                "alias '{}' needs both a phrase and a command",
+                alias
+            )));
+            continue;
+        }
+
+        let first_word = alias.split_whitespace().next().unwrap_or("");
+        if !world.aliases_override_builtins && BUILTIN_COMMANDS.contains(&first_word) {
+            errors.push(ValidationError::new(format!(
+                "alias '{}' shadows the built-in command '{}' (set aliases_override_builtins to allow this)",
+                alias, first_word
+            )));
+        }
+
+        // Follow the chain of aliases this one expands into; coming back is a cycle
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut current = command.to_lowercase();
+        while let Some((next, next_command)) = world
+            .command_aliases
+            .iter()
+            .filter(|(a, _)| {
+                current == **a
+                    || current
+                        .strip_prefix(a.as_str())
+                        .is_some_and(|rest| rest.starts_with(' '))
+            })
+            .max_by_key(|(a, _)| a.split_whitespace().count())
+        {
+            if next == alias {
+                errors.push(ValidationError::new(format!(
+                    "alias '{}' expands back into itself",
+                    alias
+                )));
+                break;
+            }
+            if !seen.insert(next) {
+                break;
+            }
+            current = format!("{}{}", next_command.to_lowercase(), &current[next.len()..]);
+        }
+    }
+}